@@ -37,20 +37,45 @@ impl<O: ToTokenStream> ToTokenStream for DatabaseKeyDefinition<O> {
     }
 }
 
+#[derive(Clone, Default)]
+pub(crate) struct DatabasePrimaryKeyOptions {
+    pub(crate) check_len: Option<usize>,
+    pub(crate) allow_empty: bool,
+}
+
+impl ToTokenStream for DatabasePrimaryKeyOptions {
+    fn new_to_token_stream(&self) -> proc_macro2::TokenStream {
+        let check_len = match self.check_len {
+            Some(check_len) => quote! { Some(#check_len) },
+            None => quote! { None },
+        };
+        let allow_empty = self.allow_empty;
+        quote! {
+            native_db::db_type::DatabasePrimaryKeyOptions {
+                check_len: #check_len,
+                allow_empty: #allow_empty,
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct DatabaseSecondaryKeyOptions {
     pub(crate) unique: bool,
     pub(crate) optional: bool,
+    pub(crate) sparse: bool,
 }
 
 impl ToTokenStream for DatabaseSecondaryKeyOptions {
     fn new_to_token_stream(&self) -> proc_macro2::TokenStream {
         let unique = self.unique;
         let optional = self.optional;
+        let sparse = self.sparse;
         quote! {
             native_db::db_type::DatabaseSecondaryKeyOptions {
                 unique: #unique,
                 optional: #optional,
+                sparse: #sparse,
             }
         }
     }
@@ -67,6 +92,7 @@ impl Default for DatabaseSecondaryKeyOptions {
         Self {
             unique: false,
             optional: false,
+            sparse: false,
         }
     }
 }
@@ -1,29 +1,36 @@
-use crate::keys::{DatabaseKeyDefinition, DatabaseSecondaryKeyOptions};
+use crate::keys::{DatabaseKeyDefinition, DatabasePrimaryKeyOptions, DatabaseSecondaryKeyOptions};
 use crate::struct_name::StructName;
 use std::collections::HashSet;
 use syn::meta::ParseNestedMeta;
 use syn::parse::Result;
-use syn::Field;
+use syn::{Field, LitInt, Type};
 
 #[derive(Clone)]
 pub(crate) struct ModelAttributes {
     pub(crate) struct_name: StructName,
-    pub(crate) primary_key: Option<DatabaseKeyDefinition<()>>,
+    pub(crate) primary_key: Option<DatabaseKeyDefinition<DatabasePrimaryKeyOptions>>,
+    /// Type of the primary key field, `None` if the primary key is a function.
+    pub(crate) primary_key_type: Option<Type>,
     pub(crate) secondary_keys: HashSet<DatabaseKeyDefinition<DatabaseSecondaryKeyOptions>>,
 }
 
 impl ModelAttributes {
-    pub(crate) fn primary_key(&self) -> DatabaseKeyDefinition<()> {
+    pub(crate) fn primary_key(&self) -> DatabaseKeyDefinition<DatabasePrimaryKeyOptions> {
         self.primary_key.clone().expect("Primary key is not set")
     }
 
     pub(crate) fn parse(&mut self, meta: ParseNestedMeta) -> Result<()> {
         if meta.path.is_ident("primary_key") {
-            let mut key: DatabaseKeyDefinition<()> =
+            let mut key: DatabaseKeyDefinition<DatabasePrimaryKeyOptions> =
                 DatabaseKeyDefinition::new_empty(self.struct_name.clone());
             meta.parse_nested_meta(|meta| {
                 if key.is_empty() {
                     key.set_function_name(meta.path.get_ident().unwrap().clone());
+                } else if meta.path.is_ident("check_len") {
+                    let check_len: LitInt = meta.value()?.parse()?;
+                    key.options.check_len = Some(check_len.base10_parse()?);
+                } else if meta.path.is_ident("allow_empty") {
+                    key.options.allow_empty = true;
                 } else {
                     panic!(
                         "Unknown attribute: {}",
@@ -43,6 +50,8 @@ impl ModelAttributes {
                     key.options.unique = true;
                 } else if meta.path.is_ident("optional") {
                     key.options.optional = true;
+                } else if meta.path.is_ident("sparse") {
+                    key.options.sparse = true;
                 } else {
                     panic!(
                         "Unknown attribute: {}",
@@ -64,11 +73,27 @@ impl ModelAttributes {
     pub(crate) fn parse_field(&mut self, field: &Field) -> Result<()> {
         for attr in &field.attrs {
             if attr.path().is_ident("primary_key") {
+                let mut primary_options = DatabasePrimaryKeyOptions::default();
+                if attr.meta.require_list().is_ok() {
+                    attr.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("check_len") {
+                            let check_len: LitInt = meta.value()?.parse()?;
+                            primary_options.check_len = Some(check_len.base10_parse()?);
+                        } else if meta.path.is_ident("allow_empty") {
+                            primary_options.allow_empty = true;
+                        } else {
+                            panic!("primary_key support only 'check_len' and 'allow_empty'");
+                        }
+                        Ok(())
+                    })?;
+                }
+
                 self.primary_key = Some(DatabaseKeyDefinition::new_field(
                     self.struct_name.clone(),
                     field.ident.clone().unwrap(),
-                    (),
+                    primary_options,
                 ));
+                self.primary_key_type = Some(field.ty.clone());
             } else if attr.path().is_ident("secondary_key") {
                 let mut secondary_options = DatabaseSecondaryKeyOptions::default();
                 if let Ok(_) = attr.meta.require_list() {
@@ -77,8 +102,10 @@ impl ModelAttributes {
                             secondary_options.unique = true;
                         } else if meta.path.is_ident("optional") {
                             secondary_options.optional = true;
+                        } else if meta.path.is_ident("sparse") {
+                            secondary_options.sparse = true;
                         } else {
-                            panic!("secondary_key support only 'unique' or 'composable'");
+                            panic!("secondary_key support only 'unique', 'optional' or 'sparse'");
                         }
                         Ok(())
                     })?;
@@ -3,7 +3,7 @@ use crate::struct_name::StructName;
 use crate::ToTokenStream;
 use proc_macro::Span;
 use quote::quote;
-use syn::Ident;
+use syn::{Ident, Visibility};
 
 pub(crate) struct ModelNativeDB {
     struct_name: StructName,
@@ -51,8 +51,17 @@ impl ModelNativeDB {
                     panic!("Unknown key type")
                 };
 
+                let sparse = if key.options.sparse {
+                    quote! {
+                        let value = value.sparse();
+                    }
+                } else {
+                    quote! {}
+                };
+
                 quote! {
                     #out
+                    #sparse
                     secondary_tables_name.insert(#new_secondary_key, value);
                 }
             })
@@ -77,9 +86,70 @@ impl ModelNativeDB {
                 }
             }
         } else {
+            let key_source_name = self.key_source_name();
+            let primary_key_is_u64 = match &self.attrs.primary_key_type {
+                Some(primary_key_type) => quote! {
+                    fn native_db_primary_key_is_u64() -> bool {
+                        std::any::TypeId::of::<#primary_key_type>() == std::any::TypeId::of::<u64>()
+                    }
+                },
+                None => quote! {},
+            };
             quote! {
                 fn native_db_primary_key(&self) -> native_db::db_type::DatabaseInnerKeyValue {
-                    self.#ident.database_inner_key_value()
+                    #key_source_name::native_db_encode_primary_key(&self.#ident)
+                }
+
+                #primary_key_is_u64
+            }
+        }
+    }
+
+    pub(crate) fn key_source_name(&self) -> Ident {
+        let struct_name = self.struct_name.ident();
+        Ident::new(
+            &format!("{}KeySource", struct_name),
+            Span::call_site().into(),
+        )
+    }
+
+    /// Generate the `<Struct>KeySource` struct, only for models with a field primary key.
+    ///
+    /// The primary key of the model and of the key source are encoded by the same function
+    /// `native_db_encode_primary_key`, so a read by key source always matches the key written on insert.
+    pub(crate) fn key_source(&self, vis: &Visibility) -> proc_macro2::TokenStream {
+        let primary_key = self.attrs.primary_key();
+        let primary_key_type = match &self.attrs.primary_key_type {
+            Some(primary_key_type) if primary_key.is_field() => primary_key_type,
+            _ => return quote! {},
+        };
+        let struct_name = self.struct_name.ident();
+        let key_source_name = self.key_source_name();
+        let ident = primary_key.ident();
+
+        let doc = format!(
+            "Primary key fields of [`{}`], see [`PrimaryKeySource`](native_db::db_type::PrimaryKeySource).",
+            struct_name
+        );
+
+        quote! {
+            #[doc = #doc]
+            #vis struct #key_source_name {
+                #vis #ident: #primary_key_type,
+            }
+
+            impl #key_source_name {
+                #[doc(hidden)]
+                fn native_db_encode_primary_key(#ident: &#primary_key_type) -> native_db::db_type::DatabaseInnerKeyValue {
+                    #ident.database_inner_key_value()
+                }
+            }
+
+            impl native_db::db_type::PrimaryKeySource for #struct_name {
+                type KeySource = #key_source_name;
+
+                fn native_db_key_source_primary_key(key_source: &Self::KeySource) -> native_db::db_type::DatabaseInnerKeyValue {
+                    #key_source_name::native_db_encode_primary_key(&key_source.#ident)
                 }
             }
         }
@@ -12,6 +12,7 @@ pub fn native_db(args: TokenStream, input: TokenStream) -> TokenStream {
     let mut attrs = ModelAttributes {
         struct_name: struct_name.clone(),
         primary_key: None,
+        primary_key_type: None,
         secondary_keys: Default::default(),
     };
     let model_attributes_parser = syn::meta::parser(|meta| attrs.parse(meta));
@@ -37,6 +38,8 @@ pub fn native_db(args: TokenStream, input: TokenStream) -> TokenStream {
     let keys_enum = model_native_db.secondary_keys_enum();
     let keys_enum_database_key = model_native_db.keys_enum_database_key();
 
+    let key_source = model_native_db.key_source(&ast.vis);
+
     let struct_name = struct_name.ident();
     let gen = quote! {
         #[derive(native_db::KeyAttributes)]
@@ -64,6 +67,8 @@ pub fn native_db(args: TokenStream, input: TokenStream) -> TokenStream {
         impl native_db::db_type::KeyDefinition<native_db::db_type::DatabaseSecondaryKeyOptions> for #keys_enum_name {
             #keys_enum_database_key
         }
+
+        #key_source
     };
 
     gen.into()
@@ -0,0 +1,77 @@
+use crate::db_type::Result;
+use crate::transaction::internal::rw_transaction::InternalRwTransaction;
+use crate::watch::{Batch, Event};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// The kind of a write, see [`AuditRecord`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditKind {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// A write of a transaction, converted to an entry of the audit log by the function given to
+/// [`DatabaseBuilder::set_audit_log`](crate::DatabaseBuilder::set_audit_log).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditRecord {
+    /// The name of the table: `<model id>_<model version>_<primary key name>` for a model,
+    /// `raw_<name>` for a [raw table](crate::DatabaseBuilder::define_raw).
+    pub table: String,
+    /// The encoded primary key of the value.
+    pub key: Vec<u8>,
+    pub kind: AuditKind,
+    /// The time of the commit, the same for all the writes of a transaction.
+    pub timestamp: SystemTime,
+}
+
+pub(crate) type AuditWriter =
+    Arc<dyn for<'db> Fn(&InternalRwTransaction<'db>, AuditRecord) -> Result<()> + Send + Sync>;
+
+/// The audit log of the database, see [`DatabaseBuilder::set_audit_log`](crate::DatabaseBuilder::set_audit_log).
+#[derive(Clone)]
+pub(crate) struct AuditLog {
+    /// The table of the entries, its writes are not audited.
+    pub(crate) table_name: String,
+    pub(crate) write: AuditWriter,
+}
+
+impl AuditLog {
+    /// Writes an entry for each write of the `batch` in the transaction `internal`.
+    pub(crate) fn write_batch(
+        &self,
+        internal: &InternalRwTransaction,
+        batch: &Batch,
+    ) -> Result<()> {
+        let timestamp = SystemTime::now();
+        for (watcher_request, event) in batch.events() {
+            if watcher_request.table_name == self.table_name {
+                continue;
+            }
+            let kind = match event {
+                Event::Insert(_) => AuditKind::Insert,
+                Event::Update(_) => AuditKind::Update,
+                Event::Delete(_) => AuditKind::Delete,
+            };
+            let record = AuditRecord {
+                table: watcher_request.table_name.clone(),
+                key: watcher_request.primary_key.as_slice().to_vec(),
+                kind,
+                timestamp,
+            };
+            (self.write)(internal, record)?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for AuditLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditLog")
+            .field("table_name", &self.table_name)
+            .finish()
+    }
+}
@@ -0,0 +1,173 @@
+use crate::Database;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, RwLock, RwLockReadGuard, TryLockError};
+use std::thread::Scope;
+use std::time::Duration;
+
+/// A database compacted by a background thread when it is fragmented.
+///
+/// Created with [`Database::auto_compaction`](crate::Database::auto_compaction). The compaction needs an exclusive
+/// access to the database, so the database is owned by the `AutoCompaction` and used through [`database`](Self::database),
+/// which returns a read guard. Every `interval`, the background thread:
+/// - skips the check if a guard is alive, e.g. during a transaction: it never waits for the foreground, it tries
+///   again at the next interval,
+/// - otherwise counts the [allocated pages](crate::StatsDatabase::allocated_pages) that are not
+///   [leaf](crate::StatsDatabase::leaf_pages) or [branch](crate::StatsDatabase::branch_pages) pages, e.g. the pages
+///   freed by the previous commits, and [compacts](crate::Database::compact) the database if they are at least
+///   `min_reclaim_fraction` of the allocated pages.
+///
+/// redb keeps a few pages for its own metadata, so a small database can stay above the threshold after a compaction:
+/// the next compactions find nothing to move and are not counted by [`compactions`](Self::compactions).
+///
+/// During a compaction, [`database`](Self::database) waits for it to end. Take a guard for each transaction
+/// instead of keeping one: the database is never compacted while a guard is alive.
+///
+/// The background thread is spawned in the scope given to [`Database::auto_compaction`](crate::Database::auto_compaction)
+/// and stops when the `AutoCompaction` is dropped. The errors of the check and of the compaction are ignored, the check
+/// runs again at the next interval.
+///
+/// # Example
+/// ```rust
+/// use native_db::*;
+/// use native_model::{native_model, Model};
+/// use serde::{Deserialize, Serialize};
+/// use std::time::Duration;
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[native_model(id=1, version=1)]
+/// #[native_db]
+/// struct Data {
+///     #[primary_key]
+///     id: u64,
+/// }
+///
+/// fn main() -> Result<(), db_type::Error> {
+///     let mut builder = DatabaseBuilder::new();
+///     builder.define::<Data>()?;
+///     let db = builder.create_in_memory()?;
+///
+///     std::thread::scope(|scope| {
+///         // Compact when a quarter of the file is fragmented, checked every minute
+///         let db = db.auto_compaction(scope, Duration::from_secs(60), 0.25);
+///         let database = db.database();
+///         let rw = database.rw_transaction()?;
+///         rw.insert(Data { id: 1 })?;
+///         rw.commit()
+///     })
+/// }
+/// ```
+pub struct AutoCompaction<'scope, 'a> {
+    shared: Arc<Shared<'a>>,
+    _scope: PhantomData<&'scope ()>,
+}
+
+struct Shared<'a> {
+    database: RwLock<Database<'a>>,
+    interval: Duration,
+    min_reclaim_fraction: f64,
+    compactions: AtomicU64,
+    stopped: Mutex<bool>,
+    /// Notified when the `AutoCompaction` is dropped.
+    changed: Condvar,
+}
+
+impl<'scope, 'a: 'scope> AutoCompaction<'scope, 'a> {
+    pub(crate) fn new(
+        database: Database<'a>,
+        scope: &'scope Scope<'scope, '_>,
+        interval: Duration,
+        min_reclaim_fraction: f64,
+    ) -> Self {
+        let shared = Arc::new(Shared {
+            database: RwLock::new(database),
+            interval,
+            min_reclaim_fraction,
+            compactions: AtomicU64::new(0),
+            stopped: Mutex::new(false),
+            changed: Condvar::new(),
+        });
+        let compactor = shared.clone();
+        scope.spawn(move || compactor.run_compactor());
+        Self {
+            shared,
+            _scope: PhantomData,
+        }
+    }
+}
+
+impl<'a> AutoCompaction<'_, 'a> {
+    /// Returns the database, waits if a compaction is in progress.
+    pub fn database(&self) -> RwLockReadGuard<'_, Database<'a>> {
+        // The database is only borrowed mutably by the compaction, it stays valid if it panicked.
+        self.shared
+            .database
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Number of compactions done by the background thread, without the ones that had no page to move.
+    pub fn compactions(&self) -> u64 {
+        self.shared.compactions.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for AutoCompaction<'_, '_> {
+    fn drop(&mut self) {
+        *self
+            .shared
+            .stopped
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = true;
+        self.shared.changed.notify_all();
+    }
+}
+
+impl Shared<'_> {
+    /// Checks the fragmentation every `interval`, until the `AutoCompaction` is dropped.
+    fn run_compactor(&self) {
+        let mut stopped = self
+            .stopped
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        loop {
+            stopped = self
+                .changed
+                .wait_timeout_while(stopped, self.interval, |stopped| !*stopped)
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .0;
+            if *stopped {
+                return;
+            }
+            drop(stopped);
+            self.compact_if_fragmented();
+            stopped = self
+                .stopped
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+
+    fn compact_if_fragmented(&self) {
+        let mut database = match self.database.try_write() {
+            Ok(database) => database,
+            // The database is in use, try again at the next interval.
+            Err(TryLockError::WouldBlock) => return,
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        };
+        let stats = match database.redb_database_stats() {
+            Ok(stats) => stats,
+            Err(_) => return,
+        };
+        let data_pages = stats.leaf_pages + stats.branch_pages;
+        let reclaimable_pages = stats.allocated_pages.saturating_sub(data_pages);
+        if stats.allocated_pages == 0
+            || (reclaimable_pages as f64) < self.min_reclaim_fraction * stats.allocated_pages as f64
+        {
+            return;
+        }
+        if let Ok(true) = database.compact() {
+            self.compactions.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
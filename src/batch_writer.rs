@@ -0,0 +1,257 @@
+use crate::db_type::{Error, Result};
+use crate::transaction::RwTransaction;
+use crate::Database;
+use std::sync::{mpsc, Arc, Condvar, Mutex, MutexGuard};
+use std::thread::Scope;
+use std::time::{Duration, Instant};
+
+type BatchWrite<'db> = Box<dyn FnOnce(&RwTransaction) -> Result<()> + Send + 'db>;
+
+/// Accumulates writes and commits them together in a single read-write transaction.
+///
+/// Created with [`Database::batch_writer`](crate::Database::batch_writer). Each call to [`write`](Self::write)
+/// queues a closure, the queued closures are run in the same read-write transaction when:
+/// - the number of queued writes reaches `max_ops`, the batch is committed by the call to [`write`](Self::write),
+/// - `max_interval` has elapsed since the first queued write of the batch, the batch is committed by a
+///   background thread spawned in the scope given to [`Database::batch_writer`](crate::Database::batch_writer),
+/// - [`flush`](Self::flush) or [`BatchWriteHandle::wait`] is called,
+/// - the `BatchWriter` is dropped.
+///
+/// The writer can be shared between the threads of the scope, [`write`](Self::write) takes `&self`.
+/// It can't leave the scope, and the scope waits for its background thread: drop the writer before the end of the scope.
+///
+/// # Durability
+///
+/// A queued write is **not** durable until its batch is committed: if the process stops, the writes queued
+/// during the last `max_interval` (plus the duration of the commit) are lost. Use the [`BatchWriteHandle`]
+/// returned by [`write`](Self::write) to commit the batch and wait for the result.
+///
+/// If a write of the batch fails, the whole batch is aborted: nothing is written and all the handles of the batch
+/// receive an error [`BatchWriteAborted`](crate::db_type::Error::BatchWriteAborted).
+///
+/// Watchers receive the events of each write once the batch is committed.
+///
+/// # Example
+/// ```rust
+/// use native_db::*;
+/// use native_model::{native_model, Model};
+/// use serde::{Deserialize, Serialize};
+/// use std::time::Duration;
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[native_model(id=1, version=1)]
+/// #[native_db]
+/// struct Data {
+///     #[primary_key]
+///     id: u64,
+/// }
+///
+/// fn main() -> Result<(), db_type::Error> {
+///     let mut builder = DatabaseBuilder::new();
+///     builder.define::<Data>()?;
+///     let db = builder.create_in_memory()?;
+///
+///     std::thread::scope(|scope| {
+///         let writer = db.batch_writer(scope, 100, Duration::from_millis(10));
+///         let handle = writer.write(|rw| rw.insert(Data { id: 1 }))?;
+///         // Commit the batch and wait for the result
+///         handle.wait()
+///     })?;
+///     Ok(())
+/// }
+/// ```
+pub struct BatchWriter<'db, 'a> {
+    shared: Arc<Shared<'db, 'a>>,
+}
+
+struct Shared<'db, 'a> {
+    database: &'db Database<'a>,
+    max_ops: usize,
+    max_interval: Duration,
+    state: Mutex<State<'db>>,
+    /// Notified when a batch starts or when the writer is dropped.
+    changed: Condvar,
+    /// Held during a flush, so the batches are committed in the order they are queued.
+    commit: Mutex<()>,
+}
+
+struct State<'db> {
+    pending: Vec<(BatchWrite<'db>, mpsc::Sender<Result<()>>)>,
+    first_pending_at: Option<Instant>,
+    closed: bool,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // The pending writes are taken before they are run, the state is valid even if a write panicked.
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl<'db, 'a> BatchWriter<'db, 'a> {
+    pub(crate) fn new(
+        database: &'db Database<'a>,
+        scope: &'db Scope<'db, '_>,
+        max_ops: usize,
+        max_interval: Duration,
+    ) -> Self {
+        let shared = Arc::new(Shared {
+            database,
+            max_ops,
+            max_interval,
+            state: Mutex::new(State {
+                pending: Vec::new(),
+                first_pending_at: None,
+                closed: false,
+            }),
+            changed: Condvar::new(),
+            commit: Mutex::new(()),
+        });
+        let flusher = shared.clone();
+        scope.spawn(move || flusher.run_flusher());
+        Self { shared }
+    }
+
+    /// Queue a write, and commit the batch if `max_ops` is reached.
+    ///
+    /// Returns an error only if the batch is committed by this call and fails.
+    pub fn write<R, F>(&self, f: F) -> Result<BatchWriteHandle<'db, 'a>>
+    where
+        F: FnOnce(&RwTransaction) -> Result<R> + Send + 'db,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let full = {
+            let mut state = lock(&self.shared.state);
+            state
+                .pending
+                .push((Box::new(move |rw| f(rw).map(|_| ())), sender));
+            if state.first_pending_at.is_none() {
+                state.first_pending_at = Some(Instant::now());
+                self.shared.changed.notify_all();
+            }
+            state.pending.len() >= self.shared.max_ops
+        };
+        if full {
+            self.shared.flush()?;
+        }
+        Ok(BatchWriteHandle {
+            receiver,
+            shared: self.shared.clone(),
+        })
+    }
+
+    /// Commit all the queued writes in a single read-write transaction.
+    pub fn flush(&self) -> Result<()> {
+        self.shared.flush()
+    }
+
+    /// Number of queued writes that are not committed yet.
+    pub fn len(&self) -> usize {
+        lock(&self.shared.state).pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        lock(&self.shared.state).pending.is_empty()
+    }
+}
+
+impl Drop for BatchWriter<'_, '_> {
+    fn drop(&mut self) {
+        // Errors are reported to the handles.
+        let _ = self.shared.flush();
+        lock(&self.shared.state).closed = true;
+        self.shared.changed.notify_all();
+    }
+}
+
+impl Shared<'_, '_> {
+    fn flush(&self) -> Result<()> {
+        let _commit = lock(&self.commit);
+        let pending = {
+            let mut state = lock(&self.state);
+            state.first_pending_at = None;
+            std::mem::take(&mut state.pending)
+        };
+        if pending.is_empty() {
+            return Ok(());
+        }
+        let mut senders = Vec::with_capacity(pending.len());
+
+        let result = self.database.write(|rw| {
+            for (f, sender) in pending {
+                senders.push(sender);
+                f(rw)?;
+            }
+            Ok(())
+        });
+
+        for sender in senders {
+            let handle_result = match &result {
+                Ok(()) => Ok(()),
+                Err(_) => Err(Error::BatchWriteAborted),
+            };
+            // The handle may have been dropped, nobody is waiting for the result.
+            let _ = sender.send(handle_result);
+        }
+        result
+    }
+
+    /// Commits the batches `max_interval` after their first write, until the writer is dropped.
+    fn run_flusher(&self) {
+        let mut state = lock(&self.state);
+        while !state.closed {
+            let deadline = state
+                .first_pending_at
+                .and_then(|first_pending_at| first_pending_at.checked_add(self.max_interval));
+            let remaining =
+                deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            state = match remaining {
+                None => self
+                    .changed
+                    .wait(state)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner()),
+                Some(remaining) if !remaining.is_zero() => {
+                    self.changed
+                        .wait_timeout(state, remaining)
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .0
+                }
+                Some(_) => {
+                    drop(state);
+                    // Errors are reported to the handles.
+                    let _ = self.flush();
+                    lock(&self.state)
+                }
+            };
+        }
+    }
+}
+
+/// Handle of a write queued in a [`BatchWriter`].
+pub struct BatchWriteHandle<'db, 'a> {
+    receiver: mpsc::Receiver<Result<()>>,
+    shared: Arc<Shared<'db, 'a>>,
+}
+
+impl BatchWriteHandle<'_, '_> {
+    /// Commit the batch of the write if it is still pending, and block until it is committed or aborted.
+    pub fn wait(self) -> Result<()> {
+        if let Some(result) = self.try_wait() {
+            return result;
+        }
+        // Errors are reported to the handles, including this one.
+        let _ = self.shared.flush();
+        self.receiver
+            .recv()
+            .unwrap_or(Err(Error::BatchWriteAborted))
+    }
+
+    /// Returns the result if the batch of the write is already committed or aborted.
+    pub fn try_wait(&self) -> Option<Result<()>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => Some(Err(Error::BatchWriteAborted)),
+        }
+    }
+}
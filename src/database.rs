@@ -1,22 +1,45 @@
-use crate::database_builder::ModelBuilder;
-use crate::db_type::Result;
-use crate::stats::{Stats, StatsTable};
-use crate::table_definition::PrimaryTableDefinition;
+use crate::audit::AuditLog;
+use crate::auto_compaction::AutoCompaction;
+use crate::batch_writer::BatchWriter;
+use crate::database_builder::{ModelBuilder, SlowCommit};
+use crate::db_type::{
+    DatabaseKeyDefinition, DatabaseSecondaryKeyOptions, Error, Input, KeyDefinition, Result,
+};
+use crate::stats::{Stats, StatsDatabase, StatsTable};
+use crate::table_definition::{raw_table_name, PrimaryTableDefinition, RedbPrimaryTableDefinition};
 use crate::transaction::internal::r_transaction::InternalRTransaction;
 use crate::transaction::internal::rw_transaction::InternalRwTransaction;
 use crate::transaction::RTransaction;
 use crate::transaction::RwTransaction;
+use crate::version::ModifiedRows;
 use crate::watch;
 use crate::watch::query::{InternalWatch, Watch};
+use crate::write_gate::{WriteGate, WriteGateGuard};
 use redb::TableHandle;
-use std::cell::RefCell;
-use std::collections::HashMap;
-use std::sync::atomic::AtomicU64;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::u64;
 
 /// The database instance. Allows you to create [rw_transaction](database/struct.Database.html#method.rw_transaction) and [r_transaction](database/struct.Database.html#method.r_transaction), [watch](database/struct.Database.html#method.watch) queries, and [unwatch](database/struct.Database.html#method.unwatch) etc.
 ///
+/// # Sharing between threads
+///
+/// `Database` is `Send` and `Sync`: share it between threads with an [`Arc`](std::sync::Arc) (or a reference
+/// with [`std::thread::scope`]). All its methods take `&self` and can be called concurrently:
+/// - the models are defined by the [`DatabaseBuilder`](crate::DatabaseBuilder) before the database is created,
+///   they are never modified after.
+/// - [`r_transaction`](Self::r_transaction) can be opened concurrently from many threads,
+///   [`rw_transaction`](Self::rw_transaction) waits until the read-write transaction in progress, if any, is committed or aborted,
+///   [`rw_transaction_timeout`](Self::rw_transaction_timeout) gives up after a timeout.
+/// - the watchers are behind a lock: [`watch`](Self::watch) and [`unwatch`](Self::unwatch) can be called from any thread,
+///   while the transactions are running.
+///
+/// [`RwTransaction`](crate::transaction::RwTransaction) is not `Sync`: use it in the thread that opened it.
+///
 /// # Example
 /// ```rust
 /// use native_db::*;
@@ -36,47 +59,641 @@ pub struct Database<'a> {
     pub(crate) primary_table_definitions: HashMap<String, PrimaryTableDefinition<'a>>,
     pub(crate) watchers: Arc<RwLock<watch::Watchers>>,
     pub(crate) watchers_counter_id: AtomicU64,
+    pub(crate) slow_commit: Option<SlowCommit>,
+    /// See [`DatabaseBuilder::set_max_size`](crate::DatabaseBuilder::set_max_size).
+    pub(crate) max_size_bytes: Option<u64>,
+    /// See [`DatabaseBuilder::set_version_tracking`](crate::DatabaseBuilder::set_version_tracking).
+    pub(crate) version_tracking: bool,
+    /// See [`DatabaseBuilder::set_audit_log`](crate::DatabaseBuilder::set_audit_log).
+    pub(crate) audit_log: Option<AuditLog>,
+    /// Names of the redb tables of the raw tables, see [`raw_table_name`](crate::table_definition::raw_table_name).
+    pub(crate) raw_tables: HashSet<String>,
+    pub(crate) write_gate: WriteGate,
+    // Declared after `instance`: the file is removed after the redb database is closed.
+    pub(crate) temp_file: Option<TempFile>,
+}
+
+/// A database file created by [`DatabaseBuilder::create_temp`](crate::DatabaseBuilder::create_temp),
+/// removed when it is dropped.
+pub(crate) struct TempFile(PathBuf);
+
+impl TempFile {
+    pub(crate) fn new() -> Self {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or_default();
+        let name = format!(
+            "native_db_{}_{}_{}.redb",
+            std::process::id(),
+            nanos,
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        Self(std::env::temp_dir().join(name))
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        // The file may not exist if the creation of the database failed.
+        let _ = std::fs::remove_file(&self.0);
+    }
 }
 
 impl Database<'_> {
     /// Creates a new read-write transaction.
     pub fn rw_transaction(&self) -> Result<RwTransaction> {
+        self.new_rw_transaction(self.write_gate.acquire())
+    }
+
+    /// Creates a new read-write transaction, waiting at most `timeout` for the read-write transaction
+    /// in progress, if any.
+    ///
+    /// Returns the error [`WriteTimeout`](crate::db_type::Error::WriteTimeout) if the read-write
+    /// transaction in progress is not committed or aborted within `timeout`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use std::time::Duration;
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let builder = DatabaseBuilder::new();
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     // A read-write transaction is already in progress
+    ///     let result = db.rw_transaction_timeout(Duration::from_millis(10));
+    ///     assert!(matches!(result, Err(db_type::Error::WriteTimeout { .. })));
+    ///     rw.commit()?;
+    ///
+    ///     let rw = db.rw_transaction_timeout(Duration::from_millis(10))?;
+    ///     rw.commit()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn rw_transaction_timeout(&self, timeout: Duration) -> Result<RwTransaction<'_>> {
+        let write_guard = self
+            .write_gate
+            .acquire_timeout(timeout)
+            .ok_or(Error::WriteTimeout { timeout })?;
+        self.new_rw_transaction(write_guard)
+    }
+
+    fn new_rw_transaction<'db>(
+        &'db self,
+        write_guard: WriteGateGuard<'db>,
+    ) -> Result<RwTransaction<'db>> {
         let rw = self.instance.begin_write()?;
         let write_txn = RwTransaction {
             watcher: &self.watchers,
-            batch: RefCell::new(watch::Batch::new()),
+            batch: RefCell::new(match self.audit_log {
+                Some(_) => watch::Batch::new_audited(),
+                None => watch::Batch::new(),
+            }),
+            slow_commit: self.slow_commit.as_ref(),
+            max_size_bytes: self.max_size_bytes,
+            audit_log: self.audit_log.as_ref(),
+            audit_log_disabled: Cell::new(false),
+            deferred_error: RefCell::new(None),
             internal: InternalRwTransaction {
                 redb_transaction: rw,
                 primary_table_definitions: &self.primary_table_definitions,
+                raw_tables: &self.raw_tables,
+                modified_rows: self
+                    .version_tracking
+                    .then(|| RefCell::new(ModifiedRows::new())),
             },
+            _write_guard: write_guard,
         };
         Ok(write_txn)
     }
 
+    /// The path of the file created by [`DatabaseBuilder::create_temp`](crate::DatabaseBuilder::create_temp),
+    /// `None` for the other databases.
+    pub fn temp_path(&self) -> Option<&Path> {
+        self.temp_file.as_ref().map(TempFile::path)
+    }
+
     /// Creates a new read-only transaction.
+    ///
+    /// The transaction keeps a snapshot of the database until it is [closed](crate::transaction::RTransaction::close)
+    /// or dropped: keeping it open indefinitely prevents redb from reusing the freed pages, and the file grows.
     pub fn r_transaction(&self) -> Result<RTransaction> {
         let txn = self.instance.begin_read()?;
         let read_txn = RTransaction {
             internal: InternalRTransaction {
                 redb_transaction: txn,
                 table_definitions: &self.primary_table_definitions,
+                raw_tables: &self.raw_tables,
             },
         };
         Ok(read_txn)
     }
+
+    /// Runs `f` in a new read-write transaction and commits it if `f` returns `Ok`.
+    ///
+    /// If `f` returns `Err`, the transaction is aborted and the error is returned as is.
+    /// The error type `E` can be any type that implements `From<`[`db_type::Error`](crate::db_type::Error)`>`,
+    /// so `?` can be used with both the database errors and the application errors inside `f`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// #[derive(Debug)]
+    /// enum AppError {
+    ///     Db(db_type::Error),
+    ///     InvalidId,
+    /// }
+    ///
+    /// impl From<db_type::Error> for AppError {
+    ///     fn from(err: db_type::Error) -> Self {
+    ///         AppError::Db(err)
+    ///     }
+    /// }
+    ///
+    /// fn main() -> Result<(), AppError> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     db.write(|rw| {
+    ///         rw.insert(Data { id: 1 })?;
+    ///         Ok::<(), AppError>(())
+    ///     })?;
+    ///
+    ///     // The transaction is aborted: `Data { id: 0 }` is not inserted.
+    ///     let result = db.write(|rw| {
+    ///         rw.insert(Data { id: 0 })?;
+    ///         Err::<(), AppError>(AppError::InvalidId)
+    ///     });
+    ///     assert!(matches!(result, Err(AppError::InvalidId)));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn write<R, E, F>(&self, f: F) -> std::result::Result<R, E>
+    where
+        E: From<Error>,
+        F: FnOnce(&RwTransaction) -> std::result::Result<R, E>,
+    {
+        let rw = self.rw_transaction()?;
+        // If `f` fails, `rw` is dropped without commit and the transaction is aborted.
+        let result = f(&rw)?;
+        rw.commit()?;
+        Ok(result)
+    }
+
+    /// Replace all the values of the model `T` by `items`, in a new read-write transaction.
+    ///
+    /// Same as [`RwTransaction::replace_table`](crate::transaction::RwTransaction::replace_table), committed
+    /// on success: e.g. to swap in a full dataset downloaded from a server. Returns the number of inserted values.
+    pub fn replace_table<T: Input>(&self, items: impl IntoIterator<Item = T>) -> Result<u64> {
+        self.write(|rw| rw.replace_table(items))
+    }
+
+    /// Creates a [`BatchWriter`](crate::BatchWriter) that commits the queued writes in a single transaction
+    /// when `max_ops` writes are queued or when `max_interval` has elapsed since the first queued write.
+    ///
+    /// The batches are committed after `max_interval` by a thread spawned in `scope`, it stops when the writer
+    /// is dropped. See [`BatchWriter`](crate::BatchWriter) for the durability window it introduces.
+    pub fn batch_writer<'scope>(
+        &'scope self,
+        scope: &'scope std::thread::Scope<'scope, '_>,
+        max_ops: usize,
+        max_interval: Duration,
+    ) -> BatchWriter<'scope, '_> {
+        BatchWriter::new(self, scope, max_ops, max_interval)
+    }
+
+    /// Runs `f` in a new read-only transaction.
+    ///
+    /// Same as [`write`](Self::write) but with a read-only transaction.
+    pub fn read<R, E, F>(&self, f: F) -> std::result::Result<R, E>
+    where
+        E: From<Error>,
+        F: FnOnce(&RTransaction) -> std::result::Result<R, E>,
+    {
+        let r = self.r_transaction()?;
+        f(&r)
+    }
+
+    /// Index the values already stored in the database in the secondary key `key_def` of the model `T`.
+    ///
+    /// When a secondary key is added to an existing model without changing its version, the values
+    /// inserted before are not indexed: [`scan().secondary()`](crate::transaction::query::RScan::secondary)
+    /// and [`get().secondary()`](crate::transaction::query::RGet::secondary) don't find them.
+    /// This method scans the primary table and indexes the values by chunks of `chunk_size`,
+    /// each chunk is committed in its own read-write transaction so the writes of the
+    /// application are not blocked during the whole backfill.
+    ///
+    /// The backfill is idempotent: if it is interrupted, call it again to resume it, the values already
+    /// indexed are left unchanged. Returns the number of scanned values.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     // New secondary key
+    ///     #[secondary_key]
+    ///     name: String,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     db.backfill_secondary_index::<Data>(DataKey::name, 1000)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn backfill_secondary_index<T: Input>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        chunk_size: usize,
+    ) -> Result<u64> {
+        let model = T::native_db_model();
+        let key_def = key_def.database_key();
+        let chunk_size = chunk_size.max(1);
+        let mut after = None;
+        let mut total = 0;
+        loop {
+            let rw = self.rw_transaction()?;
+            let (count, last_key) = rw.internal.concrete_backfill_secondary_chunk::<T>(
+                &model,
+                &key_def,
+                after.take(),
+                chunk_size,
+            )?;
+            rw.commit()?;
+            total += count as u64;
+            if count < chunk_size {
+                break;
+            }
+            after = last_key;
+        }
+        Ok(total)
+    }
+
+    /// Calls `f` with each value of the model `T`, in the order of the primary keys, reading them by
+    /// chunks of `chunk_size` in short read transactions.
+    ///
+    /// Each chunk is read in its own read transaction, which is closed before `f` is called, and the
+    /// next chunk resumes after the last primary key. The scan doesn't keep a snapshot open during the
+    /// whole iteration, and it sees the values committed in the meantime after its current position.
+    ///
+    /// The consistency is weaker than a scan in a single [`r_transaction`](Self::r_transaction):
+    /// the chunks come from different snapshots, the values inserted before the current position
+    /// during the scan are not seen, and a value updated between two chunks can be seen in its new
+    /// version while the previous values were seen in their old version. Use it for eventually-consistent
+    /// background processing, not for exact counts or consistent exports. Returns the number of values
+    /// given to `f`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     for id in 0..10 {
+    ///         rw.insert(Data { id })?;
+    ///     }
+    ///     rw.commit()?;
+    ///
+    ///     let mut sum = 0;
+    ///     let count = db.live_scan::<Data>(3, |data| sum += data.id)?;
+    ///     assert_eq!(count, 10);
+    ///     assert_eq!(sum, 45);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn live_scan<T: Input>(&self, chunk_size: usize, mut f: impl FnMut(T)) -> Result<u64> {
+        let chunk_size = chunk_size.max(1);
+        let mut after: Option<Vec<u8>> = None;
+        let mut total = 0;
+        loop {
+            let r = self.r_transaction()?;
+            let page = r
+                .scan()
+                .primary::<T>()?
+                .page(after.as_deref(), chunk_size)?;
+            r.close();
+            total += page.values.len() as u64;
+            for value in page.values {
+                f(value);
+            }
+            match page.next {
+                Some(next) => after = Some(next),
+                None => break,
+            }
+        }
+        Ok(total)
+    }
+
+    /// Rebuild the secondary key `key_def` of the model `T` from the values stored in the database.
+    ///
+    /// Use it to repair a single secondary index that became inconsistent with the primary table,
+    /// e.g. after a bug in a previous version: the secondary table is cleared and repopulated by scanning
+    /// the primary table, the other secondary indexes are left untouched. Unlike
+    /// [`backfill_secondary_index`](Self::backfill_secondary_index), the stale entries are removed, and the whole
+    /// rebuild runs in a single read-write transaction: the index is never seen half rebuilt.
+    ///
+    /// Returns the number of entries written in the secondary table, the values with a `None` optional
+    /// key are not indexed. Returns the error [`DuplicateKey`](crate::db_type::Error::DuplicateKey) if two values
+    /// have the same unique secondary key, the index is then left unchanged.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     #[secondary_key]
+    ///     name: String,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Data { id: 1, name: "a".to_string() })?;
+    ///     rw.insert(Data { id: 2, name: "b".to_string() })?;
+    ///     rw.commit()?;
+    ///
+    ///     let count = db.rebuild_secondary_index::<Data>(DataKey::name)?;
+    ///     assert_eq!(count, 2);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn rebuild_secondary_index<T: Input>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+    ) -> Result<u64> {
+        let model = T::native_db_model();
+        let key_def = key_def.database_key();
+        let rw = self.rw_transaction()?;
+        let count = rw
+            .internal
+            .concrete_rebuild_secondary_index::<T>(&model, &key_def)?;
+        rw.commit()?;
+        Ok(count)
+    }
+
+    /// Returns the secondary keys of the model `T`, sorted by name.
+    ///
+    /// Use [`name`](crate::db_type::DatabaseKeyDefinition::name) to display them and pass them as the
+    /// key definition of the secondary queries, e.g. [`RScan::secondary`](crate::transaction::query::RScan::secondary).
+    ///
+    /// Returns the error [`TableDefinitionNotFound`](crate::db_type::Error::TableDefinitionNotFound)
+    /// if the model is not defined.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     #[secondary_key]
+    ///     name: String,
+    ///     #[secondary_key(unique)]
+    ///     email: String,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let indexes = db.secondary_indexes::<Data>()?;
+    ///     let names: Vec<&str> = indexes.iter().map(|key| key.name()).collect();
+    ///     assert_eq!(names, vec!["email", "name"]);
+    ///     assert!(indexes[0].options().unique);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn secondary_indexes<T: Input>(
+        &self,
+    ) -> Result<Vec<DatabaseKeyDefinition<DatabaseSecondaryKeyOptions>>> {
+        let model = T::native_db_model();
+        if !self
+            .primary_table_definitions
+            .contains_key(model.primary_key.unique_table_name.as_str())
+        {
+            return Err(Error::TableDefinitionNotFound {
+                table: model.primary_key.unique_table_name,
+            });
+        }
+        let mut keys: Vec<_> = model.secondary_keys.into_iter().collect();
+        keys.sort_by_key(|key| key.name);
+        Ok(keys)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Database<'static> {
+    /// Runs [`write`](Self::write) on the blocking thread pool of tokio and returns a future of the result.
+    ///
+    /// Use it in async code so the disk I/O of the transaction and of the commit don't block the runtime.
+    /// The transaction is created, used and committed inside the blocking task, `f` never
+    /// crosses an `.await`. The database must be `'static` to be moved to the blocking task,
+    /// for example a database created from a `static` [`DatabaseBuilder`](crate::DatabaseBuilder).
+    ///
+    /// If the blocking task panics, the future returns the error [`BlockingTask`](crate::db_type::Error::BlockingTask).
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    /// use once_cell::sync::Lazy;
+    /// use std::sync::Arc;
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// static BUILDER: Lazy<DatabaseBuilder> = Lazy::new(|| {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>().unwrap();
+    ///     builder
+    /// });
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() -> Result<(), db_type::Error> {
+    ///     let db = Arc::new(BUILDER.create_in_memory()?);
+    ///
+    ///     db.clone()
+    ///         .write_blocking(|rw| rw.insert(Data { id: 1 }))
+    ///         .await?;
+    ///     let len = db
+    ///         .read_blocking(|r| r.len().primary::<Data>())
+    ///         .await?;
+    ///     assert_eq!(len, 1);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn write_blocking<R, E, F>(self: Arc<Self>, f: F) -> std::result::Result<R, E>
+    where
+        R: Send + 'static,
+        E: From<Error> + Send + 'static,
+        F: FnOnce(&RwTransaction) -> std::result::Result<R, E> + Send + 'static,
+    {
+        tokio::task::spawn_blocking(move || self.write(f))
+            .await
+            .map_err(|err| E::from(Error::from(err)))?
+    }
+
+    /// Runs [`read`](Self::read) on the blocking thread pool of tokio and returns a future of the result.
+    ///
+    /// Same as [`write_blocking`](Self::write_blocking) but with a read-only transaction.
+    pub async fn read_blocking<R, E, F>(self: Arc<Self>, f: F) -> std::result::Result<R, E>
+    where
+        R: Send + 'static,
+        E: From<Error> + Send + 'static,
+        F: FnOnce(&RTransaction) -> std::result::Result<R, E> + Send + 'static,
+    {
+        tokio::task::spawn_blocking(move || self.read(f))
+            .await
+            .map_err(|err| E::from(Error::from(err)))?
+    }
 }
 
 impl Database<'_> {
     /// Watch queries.
+    ///
+    /// The channels of the watchers are unbounded: sending an event never blocks the commit and
+    /// never drops the event because a channel is full. Use the bounded watchers, e.g.
+    /// [`WatchGet::primary_bounded`](crate::watch::query::WatchGet::primary_bounded), to limit the events
+    /// waiting to be received, with a [`FullPolicy`](crate::watch::FullPolicy). The events are sent after the redb commit,
+    /// so an error while sending them (e.g. a dropped receiver) is returned by
+    /// [`commit`](crate::transaction::RwTransaction::commit) but does not roll back the written data.
+    /// Such an error does not affect the other watchers: they receive all the events of the commit.
+    ///
+    /// To detect a slow consumer, see [`watcher_backlog`](Self::watcher_backlog).
     pub fn watch(&self) -> Watch {
         Watch {
             internal: InternalWatch {
                 watchers: &self.watchers,
                 watchers_counter_id: &self.watchers_counter_id,
+                keys_only_deletes: false,
             },
         }
     }
 
+    /// Returns the number of events sent to the watcher `id` and not received yet.
+    ///
+    /// - A bounded watcher, e.g. [`WatchGet::primary_bounded`](crate::watch::query::WatchGet::primary_bounded),
+    ///   returns [`Pending`](crate::watch::WatcherBacklog::Pending) with the number of events in its channel,
+    ///   at most its capacity.
+    /// - A callback watcher, e.g. [`WatchGet::primary_callback`](crate::watch::query::WatchGet::primary_callback),
+    ///   always returns `Pending(0)`: the events are passed to the callback during the commit.
+    /// - The other watchers return [`NotMeasured`](crate::watch::WatcherBacklog::NotMeasured): their unbounded
+    ///   channel doesn't expose its length to the sender. With the `tokio` feature, use `UnboundedReceiver::len()`
+    ///   on the receiver.
+    ///
+    /// Returns `None` only if the `id` is not a watcher anymore, e.g. after [`unwatch`](Self::unwatch).
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_db::watch::{FullPolicy, WatcherBacklog};
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let (recv, id) = db.watch().scan().primary().all_bounded::<Data>(100, FullPolicy::DropEvent)?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Data { id: 1 })?;
+    ///     rw.insert(Data { id: 2 })?;
+    ///     rw.commit()?;
+    ///     assert_eq!(db.watcher_backlog(id), Some(WatcherBacklog::Pending(2)));
+    ///
+    ///     recv.try_recv().unwrap();
+    ///     assert_eq!(db.watcher_backlog(id), Some(WatcherBacklog::Pending(1)));
+    ///
+    ///     db.unwatch(id)?;
+    ///     assert_eq!(db.watcher_backlog(id), None);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn watcher_backlog(&self, id: u64) -> Option<watch::WatcherBacklog> {
+        let watchers = self
+            .watchers
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        watchers.backlog(id)
+    }
+
     /// Unwatch the given `id`.
     /// You can get the `id` from the return value of [`watch`](Self::watch).
     /// If the `id` is not valid anymore, this function will do nothing.
@@ -119,6 +736,74 @@ impl<'a> Database<'a> {
         Ok(())
     }
 
+    pub(crate) fn seed_raw_table(&mut self, name: &str) -> Result<()> {
+        let table_name = raw_table_name(name);
+        let rw = self.instance.begin_write()?;
+        rw.open_table(RedbPrimaryTableDefinition::new(&table_name))?;
+        rw.commit()?;
+        self.raw_tables.insert(table_name);
+        Ok(())
+    }
+
+    /// Compacts the database file, see [redb::Database::compact](https://docs.rs/redb/latest/redb/struct.Database.html#method.compact).
+    ///
+    /// Returns `true` if the compaction was performed, `false` if no further compaction was possible.
+    ///
+    /// The compaction needs an exclusive access to the database: it takes `&mut self`, so it can't run
+    /// while a transaction is open or while the database is shared (e.g. in an [`Arc`](std::sync::Arc)).
+    /// Call it at a quiet point of the application, for example when
+    /// [`redb_database_stats`](Self::redb_database_stats) reports a lot of fragmentation,
+    /// or let a background thread do it with [`auto_compaction`](Self::auto_compaction).
+    pub fn compact(&mut self) -> Result<bool> {
+        Ok(self.instance.compact()?)
+    }
+
+    /// Moves the database into an [`AutoCompaction`](crate::AutoCompaction) that compacts it from a background thread,
+    /// when the pages that don't hold data reach `min_reclaim_fraction` of the allocated pages.
+    ///
+    /// The fragmentation is checked every `interval` by a thread spawned in `scope`, it stops when the
+    /// `AutoCompaction` is dropped. See [`AutoCompaction`](crate::AutoCompaction) for how it waits for the transactions.
+    pub fn auto_compaction<'scope>(
+        self,
+        scope: &'scope std::thread::Scope<'scope, '_>,
+        interval: Duration,
+        min_reclaim_fraction: f64,
+    ) -> AutoCompaction<'scope, 'a>
+    where
+        'a: 'scope,
+    {
+        AutoCompaction::new(self, scope, interval, min_reclaim_fraction)
+    }
+
+    /// Makes the previous commits durable, without the exclusive access of [`compact`](Self::compact).
+    ///
+    /// redb has no write-ahead log: each commit writes its pages in the database file and flushes them to the
+    /// disk before [`commit`](crate::transaction::RwTransaction::commit) returns. There is no log to fold into the
+    /// main tree and nothing to replay after a crash, so the recovery time doesn't grow between checkpoints.
+    /// A checkpoint is only an empty read-write transaction committed with the immediate durability: it is cheap
+    /// and can be called periodically, but it doesn't reduce the file size, use [`compact`](Self::compact) for that.
+    ///
+    /// Like [`rw_transaction`](Self::rw_transaction), it waits for the read-write transaction in progress, if any.
+    pub fn checkpoint(&self) -> Result<()> {
+        self.rw_transaction()?.commit()
+    }
+
+    /// Returns the storage usage of the database: pages, tree height and fragmentation.
+    ///
+    /// Use it to decide when to [`compact`](Self::compact) the database, for example when
+    /// [`fragmented_bytes`](crate::StatsDatabase::fragmented_bytes) becomes large compared to
+    /// [`stored_bytes`](crate::StatsDatabase::stored_bytes). redb does not report cache metrics.
+    ///
+    /// redb only reports these stats from a write transaction: this method opens a read-write transaction,
+    /// without committing it, so it waits for the read-write transaction in progress, if any.
+    pub fn redb_database_stats(&self) -> Result<StatsDatabase> {
+        let _write_guard = self.write_gate.acquire();
+        let rw = self.instance.begin_write()?;
+        let stats = rw.stats()?;
+        rw.abort()?;
+        Ok(stats.into())
+    }
+
     pub fn redb_stats(&self) -> Result<Stats> {
         use redb::ReadableTable;
         let rx = self.instance.begin_read()?;
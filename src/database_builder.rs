@@ -1,25 +1,127 @@
-use crate::db_type::Result;
+use crate::audit::{AuditLog, AuditRecord};
+use crate::database::TempFile;
+use crate::db_type::{Error, Result};
 use crate::table_definition::NativeModelOptions;
+#[cfg(feature = "serde_json")]
+use crate::table_definition::{json_decoder, JsonDecoder};
+use crate::transaction::internal::rw_transaction::InternalRwTransaction;
+use crate::write_gate::WriteGate;
 use crate::{watch, Database, DatabaseModel, Input};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
 use std::path::Path;
 use std::sync::atomic::AtomicU64;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Callback called when a commit is slower than the threshold, see [`DatabaseBuilder::set_slow_commit_threshold`].
+#[derive(Clone)]
+pub(crate) struct SlowCommit {
+    pub(crate) threshold: Duration,
+    pub(crate) callback: Arc<dyn Fn(Duration, usize) + Send + Sync>,
+}
+
+impl SlowCommit {
+    pub(crate) fn check(&self, elapsed: Duration, events: usize) {
+        if elapsed > self.threshold {
+            (self.callback)(elapsed, events);
+        }
+    }
+}
+
+impl Debug for SlowCommit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SlowCommit")
+            .field("threshold", &self.threshold)
+            .finish()
+    }
+}
 
 /// Builder that allows you to create a [`Database`](crate::Database) instance via [`create`](Self::create) or [`open`](Self::open) etc. and [define](Self::define) models.
+///
+/// # Configuration
+///
+/// All the options of the database are set on the builder, the setters are chainable:
+/// - [`set_cache_size`](Self::set_cache_size): the cache size of redb.
+/// - [`set_slow_commit_threshold`](Self::set_slow_commit_threshold): a callback for the slow commits.
+/// - [`set_max_size`](Self::set_max_size): the maximum size of the database.
+/// - [`set_version_tracking`](Self::set_version_tracking): the version of the rows, for the incremental syncs.
+/// - [`set_audit_log`](Self::set_audit_log): an entry for each write, committed with the write.
+///
+/// Then the models and the tables are defined with [`define`](Self::define), [`define_raw`](Self::define_raw)
+/// etc., and the database is created with [`create`](Self::create), [`open`](Self::open),
+/// [`create_in_memory`](Self::create_in_memory) or [`create_with_backend`](Self::create_with_backend).
+/// New options are added as setters, so adding an option doesn't break the existing code.
+///
+/// The combinations of options are checked when the database is created, before the file is created or opened:
+/// an invalid combination returns the error [`InvalidConfiguration`](crate::db_type::Error::InvalidConfiguration),
+/// e.g. an [audit log](Self::set_audit_log) to a model that is not defined, or the same name given to two
+/// models with [`define_json`](Self::define_json).
+///
+/// # Example
+/// ```rust
+/// use native_db::*;
+/// use std::time::Duration;
+///
+/// fn main() -> Result<(), db_type::Error> {
+///     let mut builder = DatabaseBuilder::new();
+///     builder
+///         .set_cache_size(64 * 1024 * 1024)
+///         .set_slow_commit_threshold(Duration::from_millis(100), |elapsed, _| {
+///             eprintln!("slow commit: {:?}", elapsed);
+///         });
+///     builder.define_raw("blobs")?;
+///     let _db = builder.create_in_memory()?;
+///     Ok(())
+/// }
+/// ```
 #[derive(Debug)]
 pub struct DatabaseBuilder {
     cache_size_bytes: Option<usize>,
+    slow_commit: Option<SlowCommit>,
+    max_size_bytes: Option<u64>,
+    version_tracking: bool,
+    audit_log: Option<AuditLog>,
     models_builder: HashMap<String, ModelBuilder>,
+    raw_tables: HashSet<String>,
 }
 
 impl DatabaseBuilder {
-    fn new_rdb_builder(&self) -> redb::Builder {
+    fn new_rdb_builder(&self) -> Result<redb::Builder> {
+        self.validate()?;
         let mut redb_builder = redb::Builder::new();
         if let Some(cache_size_bytes) = self.cache_size_bytes {
             redb_builder.set_cache_size(cache_size_bytes);
         }
-        redb_builder
+        Ok(redb_builder)
+    }
+
+    /// Checks the combinations of options, before the database file is created or opened.
+    fn validate(&self) -> Result<()> {
+        if let Some(audit_log) = &self.audit_log {
+            if !self.models_builder.contains_key(&audit_log.table_name) {
+                return Err(Error::InvalidConfiguration {
+                    reason: format!(
+                        "the model {} of the audit log is not defined",
+                        audit_log.table_name
+                    ),
+                });
+            }
+        }
+        #[cfg(feature = "serde_json")]
+        {
+            let mut json_names = HashSet::new();
+            for model_builder in self.models_builder.values() {
+                if let Some((name, _)) = &model_builder.json {
+                    if !json_names.insert(name) {
+                        return Err(Error::InvalidConfiguration {
+                            reason: format!("the JSON name {} is used by two models", name),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
     }
 
     fn init<'a>(&'a self, redb_database: redb::Database) -> Result<Database<'a>> {
@@ -28,12 +130,23 @@ impl DatabaseBuilder {
             primary_table_definitions: HashMap::new(),
             watchers: Arc::new(RwLock::new(watch::Watchers::new())),
             watchers_counter_id: AtomicU64::new(0),
+            slow_commit: self.slow_commit.clone(),
+            max_size_bytes: self.max_size_bytes,
+            version_tracking: self.version_tracking,
+            audit_log: self.audit_log.clone(),
+            raw_tables: HashSet::new(),
+            write_gate: WriteGate::new(),
+            temp_file: None,
         };
 
         for (_, model_builder) in &self.models_builder {
             database.seed_model(&model_builder)?;
         }
 
+        for name in &self.raw_tables {
+            database.seed_raw_table(name)?;
+        }
+
         Ok(database)
     }
 }
@@ -43,7 +156,12 @@ impl DatabaseBuilder {
     pub fn new() -> Self {
         Self {
             cache_size_bytes: None,
+            slow_commit: None,
+            max_size_bytes: None,
+            version_tracking: false,
+            audit_log: None,
             models_builder: HashMap::new(),
+            raw_tables: HashSet::new(),
         }
     }
 
@@ -53,31 +171,224 @@ impl DatabaseBuilder {
         self
     }
 
+    /// Calls `callback` after each [`commit`](crate::transaction::RwTransaction::commit) that takes more than `threshold`.
+    ///
+    /// The duration of the commit includes the commit of redb and the dispatch of the events to the watchers.
+    /// The callback receives the duration of the commit and the number of events sent to the watchers.
+    /// It is called in the thread that commits the transaction, so it must be fast: e.g. log a message.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use std::time::Duration;
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.set_slow_commit_threshold(Duration::from_millis(100), |elapsed, events| {
+    ///         eprintln!("slow commit: {:?} for {} events", elapsed, events);
+    ///     });
+    ///     let _db = builder.create_in_memory()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_slow_commit_threshold(
+        &mut self,
+        threshold: Duration,
+        callback: impl Fn(Duration, usize) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.slow_commit = Some(SlowCommit {
+            threshold,
+            callback: Arc::new(callback),
+        });
+        self
+    }
+
+    /// Refuses the commits that would make the database larger than `bytes`.
+    ///
+    /// Before each [`commit`](crate::transaction::RwTransaction::commit), the size of the pages allocated by
+    /// the database, including the pages written by the transaction, is compared to `bytes`: if it is larger,
+    /// the transaction is aborted and the commit returns the error
+    /// [`DatabaseFull`](crate::db_type::Error::DatabaseFull): the database is left unchanged.
+    ///
+    /// The size is the one reported by redb (see [`Database::redb_database_stats`](crate::Database::redb_database_stats)),
+    /// not the size of the file: the file can be slightly larger, redb grows it by regions.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.set_max_size(64 * 1024 * 1024);
+    ///     let _db = builder.create_in_memory()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_max_size(&mut self, bytes: u64) -> &mut Self {
+        self.max_size_bytes = Some(bytes);
+        self
+    }
+
+    /// Stamps each inserted or updated row with the version of the commit, to get the rows modified since
+    /// a version with [`Database::modified_since`](crate::Database::modified_since). Disabled by default.
+    ///
+    /// The version is a counter incremented by each commit that writes values. The stamps are stored in two
+    /// internal tables: for each row, the name of its table and its primary key twice, plus 16 bytes, and the
+    /// commits write these tables in addition to the tables of the models.
+    ///
+    /// Only the rows written while the tracking is enabled have a version: enable it when the database is
+    /// created, or rewrite the existing rows after enabling it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.set_version_tracking(true);
+    ///     let _db = builder.create_in_memory()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_version_tracking(&mut self, enabled: bool) -> &mut Self {
+        self.version_tracking = enabled;
+        self
+    }
+
+    /// Writes an entry of the model `T` for each insert, update and remove, in the same transaction as the write.
+    ///
+    /// At each [`commit`](crate::transaction::RwTransaction::commit), `entry` converts each write of the
+    /// transaction to an entry, in the order of the writes, and the entries are inserted before the commit:
+    /// the entries are committed atomically with the writes, a write can't be committed without its entry.
+    /// The writes of the models whose notifications are
+    /// [suppressed](crate::transaction::RwTransaction::suppress_notifications) are audited too, but the
+    /// writes of `T` are not. The model `T` must be [defined](Self::define), otherwise creating the database returns
+    /// the error [`InvalidConfiguration`](crate::db_type::Error::InvalidConfiguration). Its values are read like the
+    /// values of any other model.
+    ///
+    /// The audit log can be disabled for a transaction, e.g. a bulk load, with
+    /// [`RwTransaction::disable_audit_log`](crate::transaction::RwTransaction::disable_audit_log).
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=2, version=1)]
+    /// #[native_db]
+    /// struct AuditEntry {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     table: String,
+    ///     kind: AuditKind,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     static NEXT_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    ///
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     builder.define::<AuditEntry>()?;
+    ///     builder.set_audit_log(|record: AuditRecord| AuditEntry {
+    ///         id: NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+    ///         table: record.table,
+    ///         kind: record.kind,
+    ///     });
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Data { id: 1 })?;
+    ///     rw.commit()?;
+    ///
+    ///     let r = db.r_transaction()?;
+    ///     let entries: Vec<AuditEntry> = r.scan().primary()?.all().collect();
+    ///     assert_eq!(entries.len(), 1);
+    ///     assert_eq!(entries[0].kind, AuditKind::Insert);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_audit_log<T: Input + 'static>(
+        &mut self,
+        entry: impl Fn(AuditRecord) -> T + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.audit_log = Some(AuditLog {
+            table_name: T::native_db_model().primary_key.unique_table_name,
+            write: Arc::new(
+                move |internal: &InternalRwTransaction<'_>, record: AuditRecord| {
+                    internal.concrete_insert(T::native_db_model(), entry(record).to_item())?;
+                    Ok(())
+                },
+            ),
+        });
+        self
+    }
+
     /// Creates a new `Db` instance using the given path.
     ///
     /// Similar to [redb::Builder.create(...)](https://docs.rs/redb/latest/redb/struct.Builder.html#method.create)
+    ///
+    /// If the file already contains a database, it is opened: the tables of the models defined with
+    /// [`define`](Self::define) are reattached with their data, they are never cleared. The tables
+    /// of the new models are created empty.
     pub fn create(&self, path: impl AsRef<Path>) -> Result<Database> {
-        let db = self.new_rdb_builder().create(path)?;
+        let db = self.new_rdb_builder()?.create(path)?;
         // Ok(Self::from_redb(db))
         self.init(db)
     }
 
     /// Similar to [redb::Builder::open(...)](https://docs.rs/redb/latest/redb/struct.Builder.html#method.open)
+    ///
+    /// Fails if the file does not exist. Same as [`create`](Self::create), the existing tables are never cleared.
     pub fn open(&self, path: impl AsRef<Path>) -> Result<Database> {
-        let db = self.new_rdb_builder().open(path)?;
+        let db = self.new_rdb_builder()?.open(path)?;
         // Ok(Self::from_redb(db))
         self.init(db)
     }
 
+    /// Creates a new [`Database`](crate::Database) instance in a new file of the temporary directory
+    /// ([`std::env::temp_dir`]), removed when the database is dropped.
+    ///
+    /// Unlike [`create_in_memory`](Self::create_in_memory), the database is written to a real file, e.g. to
+    /// test the durability or the file size, without leaving stale files behind. The path is given by
+    /// [`Database::temp_path`](crate::Database::temp_path). Only this file is removed: the files given to
+    /// [`create`](Self::create) and [`open`](Self::open) are never removed.
+    pub fn create_temp(&self) -> Result<Database<'_>> {
+        // Dropped on error: the file is removed if it was created.
+        let temp_file = TempFile::new();
+        let db = self.new_rdb_builder()?.create(temp_file.path())?;
+        let mut database = self.init(db)?;
+        database.temp_file = Some(temp_file);
+        Ok(database)
+    }
+
     /// Creates a new [`Database`](crate::Database) instance in memory.
     pub fn create_in_memory(&self) -> Result<Database> {
         let in_memory_backend = redb::backends::InMemoryBackend::new();
-        let db = self.new_rdb_builder();
+        let db = self.new_rdb_builder()?;
         let db = db.create_with_backend(in_memory_backend)?;
         // Ok(Self::from_redb(db))
         self.init(db)
     }
 
+    /// Creates a new [`Database`](crate::Database) instance on a custom storage backend.
+    ///
+    /// Similar to [redb::Builder::create_with_backend(...)](https://docs.rs/redb/latest/redb/struct.Builder.html#method.create_with_backend),
+    /// e.g. to store the database in a custom storage or to inject I/O failures in tests.
+    pub fn create_with_backend(&self, backend: impl redb::StorageBackend) -> Result<Database<'_>> {
+        let db = self.new_rdb_builder()?.create_with_backend(backend)?;
+        self.init(db)
+    }
+
     /// Defines a table using the given model.
     ///
     /// Native DB depends of `native_model` to define the model.
@@ -85,6 +396,10 @@ impl DatabaseBuilder {
     /// you can use any other serialization library see the documentation of [`native_model`](https://github.com/vincent-herlemont/native_model) for more information.
     /// So in the example below we import `serde` and we use the `Serialize` and `Deserialize` traits.
     ///
+    /// The keys of the model are checked with [`DatabaseModel::validate`](crate::DatabaseModel::validate): e.g. a
+    /// secondary key with the same name as the primary key returns the error
+    /// [`InvalidSchema`](crate::db_type::Error::InvalidSchema).
+    ///
     /// # Primary key
     ///
     /// The primary key is *strict*, you **must**:
@@ -99,6 +414,10 @@ impl DatabaseBuilder {
     ///
     /// The primary key is **unique**, so you can't have two instances of the model with the same primary key saved in the database.
     ///
+    /// The primary key can have these options:
+    /// - [`check_len`](#checked-length) (default: none)
+    /// - [`allow_empty`](#empty-key) (default: disabled)
+    ///
     /// ## Define a simple model with a primary key
     /// ```rust
     /// use native_db::*;
@@ -139,6 +458,55 @@ impl DatabaseBuilder {
     ///
     /// ```
     ///
+    /// ## Checked length
+    ///
+    /// `check_len = <bytes>` declares that the encoded primary key always has the given length, e.g. `8` for a `u64`.
+    /// Each inserted key is checked against it and the [`insert`](crate::transaction::RwTransaction::insert) method
+    /// returns an error [`KeyLengthMismatch`](crate::db_type::Error::KeyLengthMismatch) if the length differs.
+    /// It is a guard against key functions that accidentally produce variable-length keys.
+    ///
+    /// This option only validates the keys: they are stored in the same variable-width redb table as without
+    /// it, there is no fixed-width storage layout.
+    ///
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key(check_len = 8)]
+    ///     id: u64,
+    /// }
+    /// ```
+    ///
+    /// Or with a method: `#[native_db(primary_key(<method_name>, check_len = <bytes>))]`.
+    ///
+    /// ## Empty key
+    ///
+    /// By default, the [`insert`](crate::transaction::RwTransaction::insert) method returns an error
+    /// [`EmptyPrimaryKey`](crate::db_type::Error::EmptyPrimaryKey) if the encoded primary key is empty,
+    /// e.g. an empty `String`. Otherwise all the values with an empty key would be stored under the same key,
+    /// which is usually a bug of the key function. `allow_empty` accepts the empty key.
+    ///
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key(allow_empty)]
+    ///     name: String,
+    /// }
+    /// ```
+    ///
+    /// Or with a method: `#[native_db(primary_key(<method_name>, allow_empty))]`.
+    ///
     /// ## Secondary key
     ///
     /// The secondary key is *flexible*, you can:
@@ -152,6 +520,7 @@ impl DatabaseBuilder {
     /// The secondary key can have two options:
     /// - [`unique`](#unique) (default: false)
     /// - [`optional`](#optional) (default: false)
+    /// - [`sparse`](#sparse) (default: false)
     ///
     /// ## Define a model with a secondary key
     /// ```rust
@@ -203,6 +572,29 @@ impl DatabaseBuilder {
     /// Under the hood, the secondary key is stored in a separate redb table. So if the secondary key is optional,
     /// the value will be stored in the table only if the value is not `None`.
     ///
+    /// ## Sparse
+    ///
+    /// This means that an instance of the model with an empty secondary key (e.g. an empty `String` or `Vec`)
+    /// is not stored in the secondary key table, like a `None` value of an [`optional`](#optional) secondary key.
+    /// So [`scan().secondary()`](crate::transaction::query::RScan::secondary) doesn't return it and several instances
+    /// can have an empty `unique` secondary key.
+    ///
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     #[secondary_key(unique, sparse)]
+    ///     email: String,
+    /// }
+    /// ```
+    ///
     /// # Define a model with a secondary key and a custom secondary key optional
     /// ```rust
     /// use native_db::*;
@@ -265,10 +657,53 @@ impl DatabaseBuilder {
     ///     builder.define::<Vegetable>()
     /// }
     /// ```
+    ///
+    /// # Define all models before creating the database
+    ///
+    /// The set of models is frozen when the [`Database`](crate::Database) is created with [`create`](Self::create),
+    /// [`open`](Self::open) or [`create_in_memory`](Self::create_in_memory): the database borrows the builder, so
+    /// `define` can't be called while the database, or any of its transactions, is alive.
+    /// A transaction therefore always sees the table definitions of all the models, and there is no separate
+    /// step to freeze the models: creating the database is that step.
+    ///
+    /// ```rust,compile_fail
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Animal {
+    ///     #[primary_key]
+    ///     name: String,
+    /// }
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=2, version=1)]
+    /// #[native_db]
+    /// struct Vegetable {
+    ///     #[primary_key]
+    ///     name: String,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Animal>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///     let rw = db.rw_transaction()?;
+    ///     // Does not compile: the builder is borrowed by the database.
+    ///     builder.define::<Vegetable>()?;
+    ///     rw.commit()
+    /// }
+    /// ```
     pub fn define<T: Input>(&mut self) -> Result<()> {
+        T::native_db_model().validate()?;
         let mut new_model_builder = ModelBuilder {
             model: T::native_db_model(),
             native_model_options: NativeModelOptions::default(),
+            #[cfg(feature = "serde_json")]
+            json: None,
         };
 
         new_model_builder.native_model_options.native_model_id = T::native_model_id();
@@ -320,10 +755,210 @@ impl DatabaseBuilder {
 
         Ok(())
     }
+
+    /// Defines all the models of the tuple `M` at once, same as [`define`](Self::define) for each model
+    /// in the order of the tuple.
+    ///
+    /// A single call lists all the models of the database in one place, so a missing model is easy to spot
+    /// in a review. Tuples of up to 12 models are supported, use the macro [`define_all!`](crate::define_all)
+    /// for longer lists.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct User {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=2, version=1)]
+    /// #[native_db]
+    /// struct Order {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define_many::<(User, Order)>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn define_many<M: Models>(&mut self) -> Result<()> {
+        M::define(self)
+    }
+
+    /// Defines a raw table named `name`: a table of bytes values, without model and without serialization.
+    ///
+    /// Use it to store values already serialized, e.g. protobuf blobs, without paying the cost of a codec.
+    /// The keys are any [`InnerKeyValue`](crate::InnerKeyValue), like the primary keys of the models.
+    /// The values are written with [`insert_raw`](crate::transaction::RwTransaction::insert_raw)
+    /// and read with [`get().raw()`](crate::transaction::query::RGet::raw).
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define_raw("blobs")?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert_raw("blobs", 1u64, &[1, 2, 3])?;
+    ///     rw.commit()?;
+    ///
+    ///     let r = db.r_transaction()?;
+    ///     let value: Option<Vec<u8>> = r.get().raw("blobs", 1u64)?;
+    ///     assert_eq!(value, Some(vec![1, 2, 3]));
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn define_raw(&mut self, name: &str) -> Result<()> {
+        self.raw_tables.insert(name.to_string());
+        Ok(())
+    }
+
+    /// Same as [`define`](Self::define) and registers the model under `name` for the JSON scans,
+    /// available with the feature `serde_json`.
+    ///
+    /// The values of the model are read without knowing its type with
+    /// [`scan().primary_json(name)`](crate::transaction::query::RScan::primary_json): each value is
+    /// decoded with the model `T` and converted to a [`serde_json::Value`]. Use it to expose the tables
+    /// in a generic API, e.g. an admin endpoint. `name` must be unique among the models defined with
+    /// `define_json`, otherwise creating the database returns the error
+    /// [`InvalidConfiguration`](crate::db_type::Error::InvalidConfiguration).
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define_json::<Data>("data")?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Data { id: 1 })?;
+    ///     rw.commit()?;
+    ///
+    ///     // Open a read transaction
+    ///     let r = db.r_transaction()?;
+    ///     let values: Vec<serde_json::Value> =
+    ///         r.scan().primary_json("data")?.all().collect::<Result<_, _>>()?;
+    ///     assert_eq!(values, vec![serde_json::json!({ "id": 1 })]);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "serde_json")]
+    pub fn define_json<T: Input + serde::Serialize>(&mut self, name: &str) -> Result<()> {
+        self.define::<T>()?;
+        let table_name = T::native_db_model().primary_key.unique_table_name;
+        if let Some(model_builder) = self.models_builder.get_mut(&table_name) {
+            model_builder.json = Some((name.to_string(), json_decoder::<T>));
+        }
+        Ok(())
+    }
+}
+
+/// A list of models defined at once by [`DatabaseBuilder::define_many`], implemented for the tuples
+/// of up to 12 [`Input`](crate::Input).
+pub trait Models {
+    fn define(builder: &mut DatabaseBuilder) -> Result<()>;
+}
+
+macro_rules! impl_models_for_tuple {
+    ( $($t:ident),+ ) => {
+        impl<$($t: Input),+> Models for ($($t,)+) {
+            fn define(builder: &mut DatabaseBuilder) -> Result<()> {
+                $(
+                    builder.define::<$t>()?;
+                )+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl_models_for_tuple!(T0);
+impl_models_for_tuple!(T0, T1);
+impl_models_for_tuple!(T0, T1, T2);
+impl_models_for_tuple!(T0, T1, T2, T3);
+impl_models_for_tuple!(T0, T1, T2, T3, T4);
+impl_models_for_tuple!(T0, T1, T2, T3, T4, T5);
+impl_models_for_tuple!(T0, T1, T2, T3, T4, T5, T6);
+impl_models_for_tuple!(T0, T1, T2, T3, T4, T5, T6, T7);
+impl_models_for_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8);
+impl_models_for_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_models_for_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_models_for_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+
+/// Defines a list of models of any length on a [`DatabaseBuilder`](crate::DatabaseBuilder), same as
+/// [`DatabaseBuilder::define_many`] without the limit on the number of models.
+///
+/// Returns the first error of [`define`](crate::DatabaseBuilder::define).
+///
+/// # Example
+/// ```rust
+/// use native_db::*;
+/// use native_model::{native_model, Model};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[native_model(id=1, version=1)]
+/// #[native_db]
+/// struct User {
+///     #[primary_key]
+///     id: u64,
+/// }
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[native_model(id=2, version=1)]
+/// #[native_db]
+/// struct Order {
+///     #[primary_key]
+///     id: u64,
+/// }
+///
+/// fn main() -> Result<(), db_type::Error> {
+///     let mut builder = DatabaseBuilder::new();
+///     define_all!(builder, User, Order)?;
+///     let db = builder.create_in_memory()?;
+///     Ok(())
+/// }
+/// ```
+#[macro_export]
+macro_rules! define_all {
+    ( $builder:expr, $($model:ty),+ $(,)? ) => {{
+        let builder: &mut $crate::DatabaseBuilder = &mut $builder;
+        $crate::db_type::Result::Ok(())
+            $(.and_then(|_| builder.define::<$model>()))+
+    }};
 }
 
 #[derive(Debug)]
 pub(crate) struct ModelBuilder {
     pub(crate) model: DatabaseModel,
     pub(crate) native_model_options: NativeModelOptions,
+    /// Name and decoder of the model for the JSON scans, see [`DatabaseBuilder::define_json`].
+    #[cfg(feature = "serde_json")]
+    pub(crate) json: Option<(String, JsonDecoder)>,
 }
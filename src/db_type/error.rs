@@ -21,10 +21,15 @@ pub enum Error {
     #[error("Redb commit error")]
     RedbCommitError(#[from] redb::CommitError),
 
+    #[error("Redb compaction error")]
+    RedbCompactionError(#[from] redb::CompactionError),
+
     #[error("IO error")]
     Io(#[from] std::io::Error),
 
-    #[error("Table definition not found {table}")]
+    #[error(
+        "Table definition not found {table}, is the model defined with DatabaseBuilder::define?"
+    )]
     TableDefinitionNotFound { table: String },
 
     #[error("Secondary key definition not found {table} {key}")]
@@ -40,21 +45,202 @@ pub enum Error {
     #[error("The secondary key {key_name} is not unique ")]
     NotUniqueSecondaryKey { key_name: String },
 
+    #[error("The secondary key {key_name} is expected to be unique but {count} values have it")]
+    NonUniqueSecondaryKey { key_name: String, count: usize },
+
     #[error("Key not found {key:?}")]
     KeyNotFound { key: Vec<u8> },
 
     #[error("Primary key associated with the secondary key not found")]
     PrimaryKeyNotFound,
 
+    #[error("Key length mismatch, expected {expected} bytes but got {got} bytes")]
+    KeyLengthMismatch { expected: usize, got: usize },
+
+    #[error("Empty primary key for the table {table}")]
+    EmptyPrimaryKey { table: String },
+
+    #[error("The primary key of the value does not match the key {key:?}")]
+    PrimaryKeyMismatch { key: Vec<u8> },
+
     #[error("Duplicate key for \"{key_name}\"")]
     DuplicateKey { key_name: String },
 
+    #[error("The stored value of the key {key:?} is not the old value of the update")]
+    StaleUpdate { key: Vec<u8> },
+
     #[error("Watch event error")]
     WatchEventError(#[from] watch::WatchEventError),
 
     #[error("Max watcher reached (should be impossible)")]
     MaxWatcherReached,
 
+    #[error("The bounded watcher {id} is full, the commit is aborted")]
+    WatcherFull { id: u64 },
+
+    #[error("The step of a sequence must be greater than 0")]
+    InvalidSequenceStep,
+
+    #[error("The limit of a page must be greater than 0")]
+    InvalidPageLimit,
+
+    #[error("The sequence of the model {model_id} has reached the maximum value")]
+    SequenceOverflow { model_id: u32 },
+
+    #[error("The sequence of the model {model_id} can not be reset below the existing key {key}")]
+    SequenceBelowExistingKey { model_id: u32, key: u64 },
+
+    #[error("The read-write transaction could not be started within {timeout:?}")]
+    WriteTimeout { timeout: std::time::Duration },
+
+    #[error("The database would be {size} bytes after the commit, more than the maximum of {max_size} bytes")]
+    DatabaseFull { size: u64, max_size: u64 },
+
+    #[error("The version tracking is disabled, see DatabaseBuilder::set_version_tracking")]
+    VersionTrackingDisabled,
+
+    #[error("The batch of the write has been aborted")]
+    BatchWriteAborted,
+
+    #[cfg(feature = "tokio")]
+    #[error("The blocking task of the transaction failed")]
+    BlockingTask(#[from] tokio::task::JoinError),
+
+    #[cfg(feature = "serde_json")]
+    #[error("The value can not be converted to JSON")]
+    Json(#[from] serde_json::Error),
+
     #[error("You can not migrate the table {0} because it is a legacy model")]
     MigrateLegacyModel(String),
+
+    #[error("The migration of the value failed: {0}")]
+    MigrationFailed(String),
+
+    #[error("Invalid schema: {reason}")]
+    InvalidSchema { reason: String },
+
+    #[error("Invalid configuration of the database builder: {reason}")]
+    InvalidConfiguration { reason: String },
+}
+
+/// Stable classification of [`Error`], returned by [`Error::kind`].
+///
+/// New variants of [`Error`] are mapped to one of these kinds, so a `match` on the kind keeps working
+/// when the database adds errors. It is useful to map the errors to the status codes of an HTTP API.
+///
+/// # Example
+/// ```rust
+/// use native_db::db_type::{Error, ErrorKind};
+///
+/// fn status_code(err: &Error) -> u16 {
+///     match err.kind() {
+///         ErrorKind::NotFound => 404,
+///         ErrorKind::Conflict => 409,
+///         ErrorKind::InvalidInput => 400,
+///         _ => 500,
+///     }
+/// }
+///
+/// let err = Error::DuplicateKey { key_name: "name".to_string() };
+/// assert_eq!(status_code(&err), 409);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The storage (redb or the file system) failed.
+    Storage,
+    /// A model, a key or a value is not found.
+    NotFound,
+    /// The input does not match the definition of the model.
+    InvalidInput,
+    /// A unique key already exists.
+    Conflict,
+    /// A limit of the database is reached.
+    LimitReached,
+    /// An event could not be sent to a watcher.
+    Watch,
+    /// The operation has been aborted before completion.
+    Aborted,
+}
+
+impl Error {
+    /// Returns the [`ErrorKind`] of the error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Redb(_)
+            | Error::RedbDatabaseError(_)
+            | Error::RedbTransactionError(_)
+            | Error::RedbStorageError(_)
+            | Error::RedbTableError(_)
+            | Error::RedbCommitError(_)
+            | Error::RedbCompactionError(_)
+            | Error::Io(_) => ErrorKind::Storage,
+            Error::TableDefinitionNotFound { .. }
+            | Error::SecondaryKeyDefinitionNotFound { .. }
+            | Error::KeyNotFound { .. }
+            | Error::PrimaryKeyNotFound => ErrorKind::NotFound,
+            Error::SecondaryKeyConstraintMismatch { .. }
+            | Error::NotUniqueSecondaryKey { .. }
+            | Error::KeyLengthMismatch { .. }
+            | Error::EmptyPrimaryKey { .. }
+            | Error::PrimaryKeyMismatch { .. }
+            | Error::InvalidSequenceStep
+            | Error::InvalidPageLimit
+            | Error::VersionTrackingDisabled
+            | Error::MigrateLegacyModel(_)
+            | Error::MigrationFailed(_)
+            | Error::InvalidSchema { .. }
+            | Error::InvalidConfiguration { .. } => ErrorKind::InvalidInput,
+            Error::DuplicateKey { .. }
+            | Error::StaleUpdate { .. }
+            | Error::NonUniqueSecondaryKey { .. }
+            | Error::SequenceBelowExistingKey { .. } => ErrorKind::Conflict,
+            Error::MaxWatcherReached
+            | Error::WatcherFull { .. }
+            | Error::SequenceOverflow { .. }
+            | Error::DatabaseFull { .. } => ErrorKind::LimitReached,
+            Error::WatchEventError(_) => ErrorKind::Watch,
+            Error::BatchWriteAborted | Error::WriteTimeout { .. } => ErrorKind::Aborted,
+            #[cfg(feature = "tokio")]
+            Error::BlockingTask(_) => ErrorKind::Aborted,
+            #[cfg(feature = "serde_json")]
+            Error::Json(_) => ErrorKind::InvalidInput,
+        }
+    }
+}
+
+/// Returns [`ExitCode::FAILURE`](std::process::ExitCode::FAILURE), so the `main` of a command line tool
+/// that returns an `ExitCode` can return `err.into()`.
+impl From<Error> for std::process::ExitCode {
+    fn from(_: Error) -> Self {
+        std::process::ExitCode::FAILURE
+    }
+}
+
+/// Converts the error to an HTTP response, with the feature `axum`: the status code is given by the
+/// [`ErrorKind`] and the body is the message of the error.
+///
+/// | Kind | Status code |
+/// |------|-------------|
+/// | [`NotFound`](ErrorKind::NotFound) | 404 Not Found |
+/// | [`InvalidInput`](ErrorKind::InvalidInput) | 400 Bad Request |
+/// | [`Conflict`](ErrorKind::Conflict) | 409 Conflict |
+/// | [`LimitReached`](ErrorKind::LimitReached), [`Aborted`](ErrorKind::Aborted) | 503 Service Unavailable |
+/// | [`Storage`](ErrorKind::Storage), [`Watch`](ErrorKind::Watch) | 500 Internal Server Error |
+///
+/// The message can contain the keys of the values, map the error yourself to hide them from the clients.
+#[cfg(feature = "axum")]
+impl axum::response::IntoResponse for Error {
+    fn into_response(self) -> axum::response::Response {
+        use axum::http::StatusCode;
+
+        let status = match self.kind() {
+            ErrorKind::NotFound => StatusCode::NOT_FOUND,
+            ErrorKind::InvalidInput => StatusCode::BAD_REQUEST,
+            ErrorKind::Conflict => StatusCode::CONFLICT,
+            ErrorKind::LimitReached | ErrorKind::Aborted => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorKind::Storage | ErrorKind::Watch => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, self.to_string()).into_response()
+    }
 }
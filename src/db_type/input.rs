@@ -48,6 +48,14 @@ pub trait Input: Sized + native_model::Model {
 
     fn native_db_primary_key(&self) -> DatabaseInnerKeyValue;
 
+    /// Returns `true` if the primary key is a `u64` field, like the values of the sequences.
+    ///
+    /// Implemented by `#[native_db]`, `false` for a primary key computed by a method.
+    #[doc(hidden)]
+    fn native_db_primary_key_is_u64() -> bool {
+        false
+    }
+
     fn native_db_secondary_keys(
         &self,
     ) -> std::collections::HashMap<
@@ -65,3 +73,46 @@ pub trait Input: Sized + native_model::Model {
         }
     }
 }
+
+/// Typed primary key of a model.
+///
+/// Implemented by `#[native_db]` for the models with a field [`primary_key`](crate::DatabaseBuilder::define#primary-key).
+/// The macro generates a struct `<Model>KeySource` containing only the primary key field, its key is
+/// encoded by the same generated function as the one used on insert. Use it with
+/// [`RGet::primary_by`](crate::transaction::query::RGet::primary_by).
+///
+/// For example, for the model:
+/// ```rust,ignore
+/// #[native_db]
+/// struct Data {
+///     #[primary_key]
+///     id: u64,
+///     name: String,
+/// }
+/// ```
+/// the macro generates:
+/// ```rust,ignore
+/// struct DataKeySource {
+///     id: u64,
+/// }
+///
+/// impl DataKeySource {
+///     fn native_db_encode_primary_key(id: &u64) -> DatabaseInnerKeyValue {
+///         id.database_inner_key_value()
+///     }
+/// }
+///
+/// impl PrimaryKeySource for Data {
+///     type KeySource = DataKeySource;
+///
+///     fn native_db_key_source_primary_key(key_source: &Self::KeySource) -> DatabaseInnerKeyValue {
+///         DataKeySource::native_db_encode_primary_key(&key_source.id)
+///     }
+/// }
+/// ```
+/// and `Data::native_db_primary_key` calls `DataKeySource::native_db_encode_primary_key(&self.id)`.
+pub trait PrimaryKeySource: Input {
+    type KeySource;
+
+    fn native_db_key_source_primary_key(key_source: &Self::KeySource) -> DatabaseInnerKeyValue;
+}
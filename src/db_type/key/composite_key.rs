@@ -0,0 +1,91 @@
+use crate::db_type::{DatabaseInnerKeyValue, InnerKeyValue};
+
+/// A key made of several components, e.g. a tenant and an entity, that can be scanned by its leading components.
+///
+/// The tuples concatenate the encoded components, so `("a", "bc")` and `("ab", "c")` are the same key.
+/// `CompositeKey` escapes the `0x00` bytes of each component as `0x00 0xFF` and ends each component with
+/// `0x00 0x01`: the components can't collide, the keys are sorted by their first component then by the next
+/// ones, and the key of the leading components is a prefix of the full keys. So a
+/// [`start_with`](crate::transaction::query::PrimaryScan::start_with) with the first components returns
+/// all the values with these components, e.g. all the entities of a tenant.
+///
+/// Use it as the type of a key defined with a method, and for the queries on this key.
+/// The encoding is different from the plain components: a key can't be queried with both.
+///
+/// # Example
+/// ```rust
+/// use native_db::*;
+/// use native_model::{native_model, Model};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[native_model(id=1, version=1)]
+/// #[native_db(primary_key(tenant_entity))]
+/// struct Entity {
+///     tenant_id: String,
+///     entity_id: String,
+/// }
+///
+/// impl Entity {
+///     fn tenant_entity(&self) -> CompositeKey {
+///         CompositeKey::new()
+///             .with(self.tenant_id.as_str())
+///             .with(self.entity_id.as_str())
+///     }
+/// }
+///
+/// fn main() -> Result<(), db_type::Error> {
+///     let mut builder = DatabaseBuilder::new();
+///     builder.define::<Entity>()?;
+///     let db = builder.create_in_memory()?;
+///
+///     let rw = db.rw_transaction()?;
+///     rw.insert(Entity { tenant_id: "a".to_string(), entity_id: "bc".to_string() })?;
+///     rw.insert(Entity { tenant_id: "ab".to_string(), entity_id: "c".to_string() })?;
+///     rw.commit()?;
+///
+///     // Open a read transaction
+///     let r = db.r_transaction()?;
+///
+///     // Get a value by its full key
+///     let key = CompositeKey::new().with("a").with("bc");
+///     let entity: Option<Entity> = r.get().primary(key)?;
+///     assert!(entity.is_some());
+///
+///     // All the entities of the tenant "a"
+///     let entities: Vec<Entity> = r
+///         .scan()
+///         .primary()?
+///         .start_with(CompositeKey::new().with("a"))
+///         .collect();
+///     assert_eq!(entities.len(), 1);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CompositeKey(Vec<u8>);
+
+impl CompositeKey {
+    /// A key without components.
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Appends a component to the key.
+    pub fn with(mut self, component: impl InnerKeyValue) -> Self {
+        for byte in component.database_inner_key_value().as_slice() {
+            self.0.push(*byte);
+            if *byte == 0x00 {
+                self.0.push(0xFF);
+            }
+        }
+        self.0.extend_from_slice(&[0x00, 0x01]);
+        self
+    }
+}
+
+impl InnerKeyValue for CompositeKey {
+    fn database_inner_key_value(&self) -> DatabaseInnerKeyValue {
+        DatabaseInnerKeyValue::new(self.0.clone())
+    }
+}
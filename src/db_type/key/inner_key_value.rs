@@ -1,12 +1,13 @@
 use redb::{RedbKey, RedbValue, TypeName};
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use std::ops::{Bound, Range, RangeBounds, RangeFrom, RangeInclusive, RangeTo, RangeToInclusive};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct DatabaseInnerKeyValue(Vec<u8>);
 
 impl DatabaseInnerKeyValue {
-    fn new(data: Vec<u8>) -> Self {
+    pub(crate) fn new(data: Vec<u8>) -> Self {
         Self(data)
     }
 
@@ -14,7 +15,8 @@ impl DatabaseInnerKeyValue {
         self.0.extend(data.0.iter());
     }
 
-    pub(crate) fn as_slice(&self) -> &[u8] {
+    /// Returns the encoded key, as stored in the database.
+    pub fn as_slice(&self) -> &[u8] {
         self.0.as_slice()
     }
 }
@@ -257,7 +259,10 @@ where
 
 impl RedbValue for DatabaseInnerKeyValue {
     type SelfType<'a> = DatabaseInnerKeyValue;
-    type AsBytes<'a> = &'a [u8] where Self: 'a;
+    type AsBytes<'a>
+        = &'a [u8]
+    where
+        Self: 'a;
 
     fn fixed_width() -> Option<usize> {
         None
@@ -331,8 +336,8 @@ impl DatabaseInnerKeyValueRange {
                 })
             }
             (Bound::Unbounded, Bound::Included(end)) => {
-                DatabaseInnerKeyValueRange::RangeTo(RangeTo {
-                    end: { end.database_inner_key_value() },
+                DatabaseInnerKeyValueRange::RangeToInclusive(RangeToInclusive {
+                    end: end.database_inner_key_value(),
                 })
             }
             (Bound::Unbounded, Bound::Excluded(end)) => {
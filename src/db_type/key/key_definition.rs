@@ -7,6 +7,7 @@ pub trait KeyDefinition<O> {
 
 #[derive(Default, Clone, Debug)]
 pub struct DatabaseKeyDefinition<O> {
+    pub(crate) name: &'static str,
     pub(crate) unique_table_name: String,
     pub(crate) options: O,
 }
@@ -21,14 +22,26 @@ impl<O> DatabaseKeyDefinition<O> {
     pub fn new(model_id: u32, model_version: u32, name: &'static str, options: O) -> Self {
         let table_name = format!("{}_{}_{}", model_id, model_version, name);
         Self {
+            name,
             options,
             unique_table_name: table_name,
         }
     }
 
+    /// Name of the key, e.g. `id` for the primary key `id`.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
     pub fn options(&self) -> &O {
         &self.options
     }
+
+    /// Name of the redb table of the key, e.g. `1_1_id` for the primary key `id` of the model with the id `1`
+    /// and the version `1`.
+    pub fn unique_table_name(&self) -> &str {
+        &self.unique_table_name
+    }
 }
 
 impl From<&'static str> for DatabaseKeyDefinition<()> {
@@ -37,6 +50,12 @@ impl From<&'static str> for DatabaseKeyDefinition<()> {
     }
 }
 
+impl From<&'static str> for DatabaseKeyDefinition<DatabasePrimaryKeyOptions> {
+    fn from(name: &'static str) -> Self {
+        Self::new(0, 0, name, DatabasePrimaryKeyOptions::default())
+    }
+}
+
 impl From<&'static str> for DatabaseKeyDefinition<DatabaseSecondaryKeyOptions> {
     fn from(name: &'static str) -> Self {
         Self::new(0, 0, name, DatabaseSecondaryKeyOptions::default())
@@ -57,10 +76,17 @@ impl Hash for DatabaseKeyDefinition<DatabaseSecondaryKeyOptions> {
     }
 }
 
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DatabasePrimaryKeyOptions {
+    pub check_len: Option<usize>,
+    pub allow_empty: bool,
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct DatabaseSecondaryKeyOptions {
     pub unique: bool,
     pub optional: bool,
+    pub sparse: bool,
 }
 
 pub fn composite_key(
@@ -0,0 +1,90 @@
+use crate::db_type::InnerKeyValue;
+use std::ops::{Bound, RangeBounds};
+
+/// Typed bounds of a key range, to use with the range queries.
+///
+/// The bounds are encoded like the keys: the integers in big-endian, so the range follows the
+/// numeric order of the unsigned keys. For the signed keys, see [`SignedKey`](crate::SignedKey). The start is included, the end is excluded with [`to`](Self::to)
+/// and included with [`to_inclusive`](Self::to_inclusive).
+///
+/// Can be used everywhere a range of keys is expected, e.g. [`PrimaryScan::range`](crate::transaction::query::PrimaryScan::range)
+/// or [`RLen::primary_range`](crate::transaction::query::RLen::primary_range).
+///
+/// # Example
+/// ```rust
+/// use native_db::*;
+/// use native_model::{native_model, Model};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[native_model(id=1, version=1)]
+/// #[native_db]
+/// struct Data {
+///     #[primary_key]
+///     id: u32,
+/// }
+///
+/// fn main() -> Result<(), db_type::Error> {
+///     let mut builder = DatabaseBuilder::new();
+///     builder.define::<Data>()?;
+///     let db = builder.create_in_memory()?;
+///
+///     // Open a read transaction
+///     let r = db.r_transaction()?;
+///
+///     // Get the values with an id from 42 to 99 excluded
+///     let _values: Vec<Data> = r.scan().primary()?.range(KeyRange::from(42u32).to(99u32)).collect();
+///     // Count the values with an id lower than or equal to 10
+///     let _count = r.len().primary_range::<Data, _, _>(KeyRange::all().to_inclusive(10u32))?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyRange<K> {
+    start: Bound<K>,
+    end: Bound<K>,
+}
+
+impl<K: InnerKeyValue> KeyRange<K> {
+    /// All the keys.
+    pub fn all() -> Self {
+        Self {
+            start: Bound::Unbounded,
+            end: Bound::Unbounded,
+        }
+    }
+
+    /// The keys greater than or equal to `start`.
+    pub fn from(start: K) -> Self {
+        Self {
+            start: Bound::Included(start),
+            end: Bound::Unbounded,
+        }
+    }
+
+    /// Limits the range to the keys lower than `end`.
+    pub fn to(self, end: K) -> Self {
+        Self {
+            start: self.start,
+            end: Bound::Excluded(end),
+        }
+    }
+
+    /// Limits the range to the keys lower than or equal to `end`.
+    pub fn to_inclusive(self, end: K) -> Self {
+        Self {
+            start: self.start,
+            end: Bound::Included(end),
+        }
+    }
+}
+
+impl<K: InnerKeyValue> RangeBounds<K> for KeyRange<K> {
+    fn start_bound(&self) -> Bound<&K> {
+        self.start.as_ref()
+    }
+
+    fn end_bound(&self) -> Bound<&K> {
+        self.end.as_ref()
+    }
+}
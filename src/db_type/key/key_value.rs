@@ -5,3 +5,19 @@ pub enum DatabaseKeyValue {
     Default(DatabaseInnerKeyValue),
     Optional(Option<DatabaseInnerKeyValue>),
 }
+
+impl DatabaseKeyValue {
+    /// Returns the value of a `sparse` secondary key: an empty key is not indexed.
+    pub fn sparse(self) -> Self {
+        match self {
+            DatabaseKeyValue::Default(value) | DatabaseKeyValue::Optional(Some(value)) => {
+                if value.as_slice().is_empty() {
+                    DatabaseKeyValue::Optional(None)
+                } else {
+                    DatabaseKeyValue::Optional(Some(value))
+                }
+            }
+            DatabaseKeyValue::Optional(None) => DatabaseKeyValue::Optional(None),
+        }
+    }
+}
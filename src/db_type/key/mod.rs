@@ -1,7 +1,13 @@
+mod composite_key;
 mod inner_key_value;
 mod key_definition;
+mod key_range;
 mod key_value;
+mod signed_key;
 
+pub use composite_key::*;
 pub use inner_key_value::*;
 pub use key_definition::*;
+pub use key_range::*;
 pub use key_value::*;
+pub use signed_key::*;
@@ -0,0 +1,72 @@
+use crate::db_type::{DatabaseInnerKeyValue, InnerKeyValue};
+
+/// A signed integer key encoded in numeric order.
+///
+/// The signed integers are encoded in big-endian two's complement, so the negative values are sorted
+/// after the positive ones and a range from a negative to a positive value is empty. `SignedKey`
+/// flips the sign bit: the encoded keys are sorted like the numbers, e.g. `-2 < -1 < 0 < 1`.
+///
+/// Use it as the type of a key defined with a method, and for the bounds of the queries on this key.
+/// The encoding is different from the plain integer: a key can't be queried with both.
+///
+/// # Example
+/// ```rust
+/// use native_db::*;
+/// use native_model::{native_model, Model};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[native_model(id=1, version=1)]
+/// #[native_db(secondary_key(delta_key))]
+/// struct Data {
+///     #[primary_key]
+///     id: u64,
+///     delta: i64,
+/// }
+///
+/// impl Data {
+///     fn delta_key(&self) -> SignedKey<i64> {
+///         SignedKey(self.delta)
+///     }
+/// }
+///
+/// fn main() -> Result<(), db_type::Error> {
+///     let mut builder = DatabaseBuilder::new();
+///     builder.define::<Data>()?;
+///     let db = builder.create_in_memory()?;
+///
+///     let rw = db.rw_transaction()?;
+///     rw.insert(Data { id: 1, delta: -5 })?;
+///     rw.insert(Data { id: 2, delta: 3 })?;
+///     rw.commit()?;
+///
+///     // Open a read transaction
+///     let r = db.r_transaction()?;
+///     let values: Vec<Data> = r
+///         .scan()
+///         .secondary(DataKey::delta_key)?
+///         .range(SignedKey(-10i64)..SignedKey(10i64))
+///         .collect();
+///     assert_eq!(values.len(), 2);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SignedKey<T>(pub T);
+
+macro_rules! impl_inner_key_value_for_signed_key {
+    ($type:ty, $unsigned:ty) => {
+        impl InnerKeyValue for SignedKey<$type> {
+            fn database_inner_key_value(&self) -> DatabaseInnerKeyValue {
+                let flipped = (self.0 as $unsigned) ^ (1 << (<$unsigned>::BITS - 1));
+                DatabaseInnerKeyValue::new(flipped.to_be_bytes().to_vec())
+            }
+        }
+    };
+}
+
+impl_inner_key_value_for_signed_key!(i8, u8);
+impl_inner_key_value_for_signed_key!(i16, u16);
+impl_inner_key_value_for_signed_key!(i32, u32);
+impl_inner_key_value_for_signed_key!(i64, u64);
+impl_inner_key_value_for_signed_key!(i128, u128);
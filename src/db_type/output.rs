@@ -1,6 +1,7 @@
 use crate::db_type::Input;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct DatabaseOutputValue(pub(crate) Vec<u8>);
 
 impl From<&[u8]> for DatabaseOutputValue {
@@ -3,14 +3,21 @@
 //! It supports multiple indexes, real-time watch with filters, model migration, hot snapshot, and more.
 //!
 //! See [README.md](https://github.com/vincent-herlemont/native_db) for more information.
+mod audit;
+mod auto_compaction;
+mod batch_writer;
 mod database;
 mod database_builder;
 pub mod db_type;
+mod migration;
 mod model;
+mod sequence;
 mod serialization;
 mod snapshot;
 mod stats;
 mod table_definition;
+mod version;
+mod write_gate;
 
 /// All database interactions here,[`r_transaction`](transaction/struct.RTransaction.html), [`rw_transaction`](transaction/struct.RwTransaction.html) and [`query`](transaction/query/index.html).
 pub mod transaction;
@@ -18,13 +25,22 @@ pub mod transaction;
 pub mod watch;
 
 // Re-export
+pub use db_type::CompositeKey;
 pub use db_type::InnerKeyValue;
 pub use db_type::Input;
+pub use db_type::KeyRange;
+pub use db_type::SignedKey;
 
 // Export
+pub use audit::{AuditKind, AuditRecord};
+pub use auto_compaction::*;
+pub use batch_writer::*;
 pub use database::*;
 pub use database_builder::*;
+pub use migration::*;
 pub use model::*;
+pub use stats::*;
+pub use version::*;
 
 #[cfg(doctest)]
 #[macro_use]
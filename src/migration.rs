@@ -0,0 +1,199 @@
+use crate::db_type::{
+    DatabaseInnerKeyValue, DatabaseInput, DatabaseKeyDefinition, DatabaseKeyValue,
+    DatabaseSecondaryKeyOptions, Error, Input, Result,
+};
+use crate::transaction::internal::private_readable_transaction::PrivateReadableTransaction;
+use crate::{bincode_decode_from_slice, bincode_encode_to_vec, Database};
+use redb::ReadableTable;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+
+/// Result of [`Database::migrate_dry_run`].
+#[derive(Debug)]
+pub struct MigrationReport {
+    /// Number of values read from the old model.
+    pub total: u64,
+    /// Number of values that would be written with a different primary key or different bytes.
+    pub changed: u64,
+    /// Values that would fail to migrate.
+    pub failures: Vec<MigrationFailure>,
+}
+
+impl MigrationReport {
+    /// Returns `true` if no value would fail to migrate.
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// A value that would fail to migrate, see [`MigrationReport`].
+#[derive(Debug)]
+pub struct MigrationFailure {
+    /// Primary key of the value in the old model.
+    pub primary_key: DatabaseInnerKeyValue,
+    pub error: Error,
+}
+
+impl Database<'_> {
+    /// Validates a migration from the model `Old` to the model `New` without writing anything.
+    ///
+    /// Every value of `Old` is read from a single read transaction and mapped with `f`. The result
+    /// must serialize and deserialize back, and its keys must be valid for `New`: fixed length primary key,
+    /// no primary key or unique secondary key shared by two migrated values or by a value already stored in `New`.
+    /// The report contains the number of values that would change and the values that would fail,
+    /// with their old primary key.
+    ///
+    /// Both models must be defined. Use it against a copy of the production data
+    /// (see [`snapshot`](Self::snapshot)) before running the migration with
+    /// [`convert_all`](crate::transaction::RwTransaction::convert_all) or
+    /// [`migrate`](crate::transaction::RwTransaction::migrate).
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct OldData {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=2, version=1)]
+    /// #[native_db]
+    /// struct NewData {
+    ///     #[primary_key]
+    ///     id: String,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<OldData>()?;
+    ///     builder.define::<NewData>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let report = db.migrate_dry_run::<OldData, NewData, _, String>(|old| {
+    ///         Ok(NewData { id: old.id.to_string() })
+    ///     })?;
+    ///     assert!(report.is_ok());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn migrate_dry_run<Old, New, F, E>(&self, mut f: F) -> Result<MigrationReport>
+    where
+        Old: Input + serde::de::DeserializeOwned,
+        New: Input + serde::Serialize + serde::de::DeserializeOwned,
+        F: FnMut(Old) -> std::result::Result<New, E>,
+        E: Display,
+    {
+        let old_model = Old::native_db_model();
+        let new_model = New::native_db_model();
+        let same_table =
+            old_model.primary_key.unique_table_name == new_model.primary_key.unique_table_name;
+
+        let r = self.r_transaction()?;
+        let old_table = r.internal.get_primary_table(&old_model)?;
+        let new_table = r.internal.get_primary_table(&new_model)?;
+        let mut new_secondary_tables = HashMap::new();
+        for secondary_key_def in &new_model.secondary_keys {
+            if secondary_key_def.options.unique {
+                let table = r
+                    .internal
+                    .get_secondary_table(&new_model, secondary_key_def)?;
+                new_secondary_tables.insert(secondary_key_def.clone(), table);
+            }
+        }
+
+        let mut report = MigrationReport {
+            total: 0,
+            changed: 0,
+            failures: vec![],
+        };
+        let mut primary_keys = HashSet::new();
+        let mut secondary_keys: HashMap<
+            DatabaseKeyDefinition<DatabaseSecondaryKeyOptions>,
+            HashSet<DatabaseInnerKeyValue>,
+        > = HashMap::new();
+
+        for result in old_table.iter()? {
+            let (key, value) = result?;
+            let old_primary_key = key.value();
+            let old_value = value.value();
+            report.total += 1;
+
+            let mut check = || -> Result<DatabaseInput> {
+                let (old, _) = bincode_decode_from_slice::<Old>(old_value).ok_or_else(|| {
+                    Error::MigrationFailed("the old value can not be deserialized".to_string())
+                })?;
+                let new = f(old).map_err(|err| Error::MigrationFailed(err.to_string()))?;
+                let value = bincode_encode_to_vec(&new).ok_or_else(|| {
+                    Error::MigrationFailed("the new value can not be serialized".to_string())
+                })?;
+                bincode_decode_from_slice::<New>(&value).ok_or_else(|| {
+                    Error::MigrationFailed("the new value can not be deserialized".to_string())
+                })?;
+                let item = DatabaseInput {
+                    primary_key: new.native_db_primary_key(),
+                    secondary_keys: new.native_db_secondary_keys(),
+                    value,
+                };
+
+                new_model.check_primary_key(&item.primary_key)?;
+                let already_exists = !same_table && new_table.get(&item.primary_key)?.is_some();
+                if already_exists || primary_keys.contains(&item.primary_key) {
+                    return Err(Error::DuplicateKey {
+                        key_name: new_model.primary_key.unique_table_name.clone(),
+                    });
+                }
+
+                for (secondary_key_def, table) in &new_secondary_tables {
+                    let value = match item.secondary_key_value(secondary_key_def)? {
+                        DatabaseKeyValue::Default(value) => value,
+                        DatabaseKeyValue::Optional(Some(value)) => value,
+                        DatabaseKeyValue::Optional(None) => continue,
+                    };
+                    let duplicate = secondary_keys
+                        .get(secondary_key_def)
+                        .is_some_and(|values| values.contains(&value))
+                        || (!same_table && table.get(&value)?.is_some());
+                    if duplicate {
+                        return Err(Error::DuplicateKey {
+                            key_name: secondary_key_def.unique_table_name.clone(),
+                        });
+                    }
+                }
+                Ok(item)
+            };
+
+            match check() {
+                Ok(item) => {
+                    if item.primary_key != old_primary_key || item.value != old_value {
+                        report.changed += 1;
+                    }
+                    for secondary_key_def in new_secondary_tables.keys() {
+                        if let DatabaseKeyValue::Default(value)
+                        | DatabaseKeyValue::Optional(Some(value)) =
+                            item.secondary_key_value(secondary_key_def)?
+                        {
+                            secondary_keys
+                                .entry(secondary_key_def.clone())
+                                .or_default()
+                                .insert(value);
+                        }
+                    }
+                    primary_keys.insert(item.primary_key);
+                }
+                Err(error) => report.failures.push(MigrationFailure {
+                    primary_key: old_primary_key,
+                    error,
+                }),
+            }
+        }
+
+        Ok(report)
+    }
+}
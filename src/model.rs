@@ -1,9 +1,12 @@
-use crate::db_type::{DatabaseKeyDefinition, DatabaseSecondaryKeyOptions, Error, Result};
+use crate::db_type::{
+    DatabaseInnerKeyValue, DatabaseKeyDefinition, DatabasePrimaryKeyOptions,
+    DatabaseSecondaryKeyOptions, Error, Result,
+};
 use std::collections::HashSet;
 
 #[derive(Clone, Debug)]
 pub struct DatabaseModel {
-    pub primary_key: DatabaseKeyDefinition<()>,
+    pub primary_key: DatabaseKeyDefinition<DatabasePrimaryKeyOptions>,
     pub secondary_keys: HashSet<DatabaseKeyDefinition<DatabaseSecondaryKeyOptions>>,
 }
 
@@ -34,4 +37,70 @@ impl DatabaseModel {
             })
         }
     }
+
+    /// Checks that the keys of the model are consistent: the names of the keys are not empty, the
+    /// secondary keys belong to the same model as the primary key and their tables don't collide with
+    /// the primary table or with each other.
+    ///
+    /// Called by [`DatabaseBuilder::define`](crate::DatabaseBuilder::define), returns the error
+    /// [`InvalidSchema`](crate::db_type::Error::InvalidSchema) otherwise.
+    pub fn validate(&self) -> Result<()> {
+        let primary_table = &self.primary_key.unique_table_name;
+        if self.primary_key.name.is_empty() {
+            return Err(Error::InvalidSchema {
+                reason: format!("the primary key of the table {} has no name", primary_table),
+            });
+        }
+        // The tables of the keys are named `<model id>_<model version>_<key name>`.
+        let prefix = &primary_table[..primary_table.len() - self.primary_key.name.len()];
+        let mut names = HashSet::new();
+        for secondary_key in &self.secondary_keys {
+            if secondary_key.name.is_empty() {
+                return Err(Error::InvalidSchema {
+                    reason: format!("a secondary key of the table {} has no name", primary_table),
+                });
+            }
+            if secondary_key.unique_table_name == *primary_table {
+                return Err(Error::InvalidSchema {
+                    reason: format!(
+                        "the secondary key {} has the same table as the primary key",
+                        secondary_key.name
+                    ),
+                });
+            }
+            if secondary_key.unique_table_name != format!("{}{}", prefix, secondary_key.name) {
+                return Err(Error::InvalidSchema {
+                    reason: format!(
+                        "the secondary key {} doesn't belong to the model of the table {}",
+                        secondary_key.unique_table_name, primary_table
+                    ),
+                });
+            }
+            if !names.insert(secondary_key.name) {
+                return Err(Error::InvalidSchema {
+                    reason: format!(
+                        "the secondary key {} is defined twice in the table {}",
+                        secondary_key.name, primary_table
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the primary key against the options of the model: `check_len` and `allow_empty`.
+    pub(crate) fn check_primary_key(&self, primary_key: &DatabaseInnerKeyValue) -> Result<()> {
+        if !self.primary_key.options.allow_empty && primary_key.as_slice().is_empty() {
+            return Err(Error::EmptyPrimaryKey {
+                table: self.primary_key.unique_table_name.to_string(),
+            });
+        }
+        if let Some(expected) = self.primary_key.options.check_len {
+            let got = primary_key.as_slice().len();
+            if got != expected {
+                return Err(Error::KeyLengthMismatch { expected, got });
+            }
+        }
+        Ok(())
+    }
 }
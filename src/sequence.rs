@@ -0,0 +1,178 @@
+use crate::db_type::{Error, Input, Result};
+use crate::Database;
+use redb::ReadableTable;
+use std::convert::TryFrom;
+
+/// Sequences of the models: `native_model_id -> (next value, step)`.
+///
+/// The sequence is shared by all the versions of a model, so it survives the migrations.
+pub(crate) const SEQUENCE_TABLE: redb::TableDefinition<u32, (u64, u64)> =
+    redb::TableDefinition::new("native_db_sequence");
+
+/// Sequence of a model that has never been configured: starts at 1 with a step of 1.
+const DEFAULT_SEQUENCE: (u64, u64) = (1, 1);
+
+pub(crate) fn read_sequence(
+    table: &impl ReadableTable<u32, (u64, u64)>,
+    model_id: u32,
+) -> Result<(u64, u64)> {
+    Ok(table
+        .get(model_id)?
+        .map(|value| value.value())
+        .unwrap_or(DEFAULT_SEQUENCE))
+}
+
+/// Returns the next value of the sequence of the model and advances it, in the write transaction `rw`.
+pub(crate) fn next_sequence(rw: &redb::WriteTransaction, model_id: u32) -> Result<u64> {
+    let mut table = rw.open_table(SEQUENCE_TABLE)?;
+    let (next, step) = read_sequence(&table, model_id)?;
+    let following = next
+        .checked_add(step)
+        .ok_or(Error::SequenceOverflow { model_id })?;
+    table.insert(model_id, (following, step))?;
+    Ok(next)
+}
+
+impl Database<'_> {
+    /// Configure the sequence of the model `T`: the next value returned by
+    /// [`next_sequence`](crate::transaction::RwTransaction::next_sequence) is `start`,
+    /// then each value is `step` more than the previous one.
+    ///
+    /// The sequence is stored in the database, so it is kept across restarts. A sequence that has
+    /// never been configured starts at `1` with a step of `1`. Configuring the sequence again resets it
+    /// to `start`: the values already returned can be returned again.
+    ///
+    /// Returns the error [`InvalidSequenceStep`](crate::db_type::Error::InvalidSequenceStep) if `step` is `0`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     // The ids of the legacy system are lower than 1,000,000.
+    ///     db.configure_sequence::<Data>(1_000_000, 1)?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     let id = rw.next_sequence::<Data>()?;
+    ///     rw.insert(Data { id })?;
+    ///     rw.commit()?;
+    ///
+    ///     assert_eq!(id, 1_000_000);
+    ///     assert_eq!(db.current_sequence::<Data>()?, 1_000_001);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn configure_sequence<T: Input>(&self, start: u64, step: u64) -> Result<()> {
+        if step == 0 {
+            return Err(Error::InvalidSequenceStep);
+        }
+        let _write_guard = self.write_gate.acquire();
+        let rw = self.instance.begin_write()?;
+        {
+            let mut table = rw.open_table(SEQUENCE_TABLE)?;
+            table.insert(T::native_model_id(), (start, step))?;
+        }
+        rw.commit()?;
+        Ok(())
+    }
+
+    /// Returns the current value of the sequence of the model `T`: the value that the next call to
+    /// [`next_sequence`](crate::transaction::RwTransaction::next_sequence) returns.
+    ///
+    /// See [`configure_sequence`](Self::configure_sequence).
+    pub fn current_sequence<T: Input>(&self) -> Result<u64> {
+        let r = self.instance.begin_read()?;
+        let (next, _) = match r.open_table(SEQUENCE_TABLE) {
+            Ok(table) => read_sequence(&table, T::native_model_id())?,
+            Err(redb::TableError::TableDoesNotExist(_)) => DEFAULT_SEQUENCE,
+            Err(err) => return Err(err.into()),
+        };
+        Ok(next)
+    }
+
+    /// Reset the sequence of the model `T`: the next value returned by
+    /// [`next_sequence`](crate::transaction::RwTransaction::next_sequence) is `to`. The step is kept.
+    ///
+    /// To avoid collisions, if the primary key of `T` is a `u64` field, the sequence can not be reset to a
+    /// value lower than or equal to the greatest primary key already stored for `T`: it returns the error
+    /// [`SequenceBelowExistingKey`](crate::db_type::Error::SequenceBelowExistingKey) and the sequence
+    /// is left unchanged. The primary keys of other types, and the ones computed by a method, are not checked.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Data { id: 10 })?;
+    ///     rw.commit()?;
+    ///
+    ///     // The id 10 is already used.
+    ///     assert!(db.reset_sequence::<Data>(10).is_err());
+    ///
+    ///     db.reset_sequence::<Data>(11)?;
+    ///     assert_eq!(db.current_sequence::<Data>()?, 11);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn reset_sequence<T: Input>(&self, to: u64) -> Result<()> {
+        let model = T::native_db_model();
+        let model_id = T::native_model_id();
+        let table_definition = self
+            .primary_table_definitions
+            .get(model.primary_key.unique_table_name.as_str())
+            .ok_or_else(|| Error::TableDefinitionNotFound {
+                table: model.primary_key.unique_table_name.to_string(),
+            })?;
+
+        let _write_guard = self.write_gate.acquire();
+        let rw = self.instance.begin_write()?;
+        {
+            if T::native_db_primary_key_is_u64() {
+                let primary_table = rw.open_table(table_definition.redb)?;
+                if let Some((key, _)) = primary_table.last()? {
+                    if let Ok(bytes) = <[u8; 8]>::try_from(key.value().as_slice()) {
+                        let key = u64::from_be_bytes(bytes);
+                        if key >= to {
+                            return Err(Error::SequenceBelowExistingKey { model_id, key });
+                        }
+                    }
+                }
+            }
+
+            let mut table = rw.open_table(SEQUENCE_TABLE)?;
+            let (_, step) = read_sequence(&table, model_id)?;
+            table.insert(model_id, (to, step))?;
+        }
+        rw.commit()?;
+        Ok(())
+    }
+}
@@ -1,14 +1,122 @@
-use crate::db_type::Result;
+use crate::db_type::{Error, Input, Result};
+use crate::sequence::SEQUENCE_TABLE;
+use crate::table_definition::RedbPrimaryTableDefinition;
+use crate::version::{MODIFIED_INDEX_TABLE, MODIFIED_TABLE, VERSION_TABLE};
 use crate::{Database, DatabaseBuilder};
 use redb::ReadableTable;
+use std::fs::OpenOptions;
 use std::path::Path;
 
 impl Database<'_> {
     pub fn snapshot<'a>(&self, builder: &'a DatabaseBuilder, path: &Path) -> Result<Database<'a>> {
         // TODO: builder must have well defined models
         let new_db = builder.create(path)?;
+        self.copy_to(&new_db.instance)?;
+        Ok(new_db)
+    }
+
+    /// Exports a consistent snapshot of the database to a new file at `path`.
+    ///
+    /// All tables are read from a single read transaction, so the snapshot reflects one point
+    /// in time and concurrent [`rw_transaction`](Self::rw_transaction) are not blocked while it runs.
+    /// The file can be opened later with [`DatabaseBuilder::open`](crate::DatabaseBuilder::open)
+    /// using the same models.
+    ///
+    /// The file must not exist, otherwise returns an [`Io`](crate::db_type::Error::Io) error of kind
+    /// [`AlreadyExists`](std::io::ErrorKind::AlreadyExists): an existing database is never merged with the snapshot.
+    pub fn snapshot_to(&self, path: &Path) -> Result<()> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(path)?;
+        let instance = redb::Builder::new().create_file(file)?;
+        self.copy_to(&instance)
+    }
+
+    /// Copies the values of the model `T` and its secondary indexes into `dest`, returning the
+    /// number of values copied.
+    ///
+    /// The tables of `T` in `dest` are replaced, so `dest` ends up with exactly the values of this
+    /// database. It is useful to fork one model into another database (e.g. an in-memory one),
+    /// run a migration or a batch rewrite on the copy and validate it before touching the real data.
+    /// The source is read from a single read transaction and the watchers of `dest` are not notified.
+    ///
+    /// Returns the error [`TableDefinitionNotFound`](crate::db_type::Error::TableDefinitionNotFound)
+    /// if `T` is not defined in both databases.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///     let fork = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Data { id: 1 })?;
+    ///     rw.commit()?;
+    ///
+    ///     assert_eq!(db.copy_table::<Data>(&fork)?, 1);
+    ///     let r = fork.r_transaction()?;
+    ///     assert_eq!(r.len().primary::<Data>()?, 1);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn copy_table<T: Input>(&self, dest: &Database) -> Result<u64> {
+        let model = T::native_db_model();
+        let table_name = model.primary_key.unique_table_name.as_str();
+        let primary_table_definition = self
+            .primary_table_definitions
+            .get(table_name)
+            .filter(|_| dest.primary_table_definitions.contains_key(table_name))
+            .ok_or_else(|| Error::TableDefinitionNotFound {
+                table: table_name.to_string(),
+            })?;
+
+        let _write_guard = dest.write_gate.acquire();
+        let r = self.instance.begin_read()?;
+        let w = dest.instance.begin_write()?;
+        let mut copied = 0;
+        {
+            w.delete_table(primary_table_definition.redb)?;
+            let table = r.open_table(primary_table_definition.redb)?;
+            let mut new_table = w.open_table(primary_table_definition.redb)?;
+            for result in table.iter()? {
+                let (key, value) = result?;
+                new_table.insert(key.value(), value.value())?;
+                copied += 1;
+            }
+
+            for secondary_table_definition in primary_table_definition.secondary_tables.values() {
+                w.delete_table(secondary_table_definition.redb)?;
+                let table = r.open_table(secondary_table_definition.redb)?;
+                let mut new_table = w.open_table(secondary_table_definition.redb)?;
+                for result in table.iter()? {
+                    let (key, value) = result?;
+                    new_table.insert(key.value(), value.value())?;
+                }
+            }
+        }
+        w.commit()?;
+        Ok(copied)
+    }
+
+    fn copy_to(&self, instance: &redb::Database) -> Result<()> {
         let r = self.instance.begin_read()?;
-        let w = new_db.instance.begin_write()?;
+        let w = instance.begin_write()?;
         {
             // Copy primary tables
             for (_, primary_table_definition) in &self.primary_table_definitions {
@@ -29,8 +137,44 @@ impl Database<'_> {
                     }
                 }
             }
+
+            // Copy raw tables
+            for table_name in &self.raw_tables {
+                let table_definition = RedbPrimaryTableDefinition::new(table_name);
+                let table = r.open_table(table_definition)?;
+                let mut new_table = w.open_table(table_definition)?;
+                for result in table.iter()? {
+                    let (key, value) = result?;
+                    new_table.insert(key.value(), value.value())?;
+                }
+            }
+
+            // Copy sequences, versions, row versions and the index of the row versions
+            copy_redb_table(&r, &w, SEQUENCE_TABLE)?;
+            copy_redb_table(&r, &w, VERSION_TABLE)?;
+            copy_redb_table(&r, &w, MODIFIED_TABLE)?;
+            copy_redb_table(&r, &w, MODIFIED_INDEX_TABLE)?;
         }
         w.commit()?;
-        Ok(new_db)
+        Ok(())
+    }
+}
+
+/// Copies the table `definition` from `r` to `w`, skips it if it doesn't exist in `r`.
+fn copy_redb_table<K: redb::RedbKey + 'static, V: redb::RedbValue + 'static>(
+    r: &redb::ReadTransaction,
+    w: &redb::WriteTransaction,
+    definition: redb::TableDefinition<K, V>,
+) -> Result<()> {
+    let table = match r.open_table(definition) {
+        Ok(table) => table,
+        Err(redb::TableError::TableDoesNotExist(_)) => return Ok(()),
+        Err(err) => return Err(err.into()),
+    };
+    let mut new_table = w.open_table(definition)?;
+    for result in table.iter()? {
+        let (key, value) = result?;
+        new_table.insert(key.value(), value.value())?;
     }
+    Ok(())
 }
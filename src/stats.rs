@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 #[derive(Debug)]
 pub struct Stats {
     pub primary_tables: Vec<StatsTable>,
@@ -9,3 +11,64 @@ pub struct StatsTable {
     pub name: String,
     pub n_entries: Option<u64>,
 }
+
+/// Storage usage of the database, reported by redb.
+///
+/// See [`Database::redb_database_stats`](crate::Database::redb_database_stats).
+#[derive(Debug)]
+pub struct StatsDatabase {
+    /// Maximum traversal distance to reach the deepest (key, value) pair, across all tables.
+    pub tree_height: u32,
+    /// Number of pages allocated.
+    pub allocated_pages: u64,
+    /// Number of leaf pages that store user data.
+    pub leaf_pages: u64,
+    /// Number of branch pages in btrees that store user data.
+    pub branch_pages: u64,
+    /// Number of bytes consumed by keys and values, without indexing overhead.
+    pub stored_bytes: u64,
+    /// Number of bytes consumed by keys in internal branch pages, plus other metadata.
+    pub metadata_bytes: u64,
+    /// Number of bytes consumed by fragmentation, both in data pages and internal metadata tables.
+    pub fragmented_bytes: u64,
+    /// Number of bytes per page.
+    ///
+    /// The page size is chosen by redb when the database file is created (4 KiB) and can't be configured:
+    /// redb 1.5 only allows to set it in its own tests.
+    pub page_size: usize,
+}
+
+/// Metrics of a commit, returned by [`RwTransaction::commit_with_metrics`](crate::transaction::RwTransaction::commit_with_metrics).
+///
+/// redb 1.5 doesn't report the bytes or the pages written by a transaction: only the pages allocated by the
+/// whole database are available. Compare [`allocated_pages`](Self::allocated_pages) between two commits to
+/// get the pages added by the second one, the pages freed by a transaction are only reused by the next ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommitMetrics {
+    /// Duration of the commit, including the commit of redb and the dispatch of the events to the watchers.
+    pub duration: Duration,
+    /// Number of writes of the transaction (inserts, updates and removes), as sent to the watchers.
+    ///
+    /// The writes of the tables whose notifications are suppressed with
+    /// [`suppress_notifications`](crate::transaction::RwTransaction::suppress_notifications) are not counted.
+    pub writes: usize,
+    /// Number of pages allocated by the database, including the pages written by the transaction.
+    pub allocated_pages: u64,
+    /// Number of bytes per page, see [`StatsDatabase::page_size`].
+    pub page_size: usize,
+}
+
+impl From<redb::DatabaseStats> for StatsDatabase {
+    fn from(stats: redb::DatabaseStats) -> Self {
+        Self {
+            tree_height: stats.tree_height(),
+            allocated_pages: stats.allocated_pages(),
+            leaf_pages: stats.leaf_pages(),
+            branch_pages: stats.branch_pages(),
+            stored_bytes: stats.stored_bytes(),
+            metadata_bytes: stats.metadata_bytes(),
+            fragmented_bytes: stats.fragmented_bytes(),
+            page_size: stats.page_size(),
+        }
+    }
+}
@@ -8,12 +8,33 @@ pub(crate) type RedbPrimaryTableDefinition<'a> =
 pub(crate) type RedbSecondaryTableDefinition<'a> =
     redb::TableDefinition<'a, DatabaseInnerKeyValue, DatabaseInnerKeyValue>;
 
+/// Converts the stored bytes of a model to JSON, see [`DatabaseBuilder::define_json`](crate::DatabaseBuilder::define_json).
+#[cfg(feature = "serde_json")]
+pub(crate) type JsonDecoder = fn(&[u8]) -> crate::db_type::Result<serde_json::Value>;
+
+#[cfg(feature = "serde_json")]
+pub(crate) fn json_decoder<T: crate::Input + serde::Serialize>(
+    slice: &[u8],
+) -> crate::db_type::Result<serde_json::Value> {
+    let value = T::native_db_bincode_decode_from_slice(slice);
+    Ok(serde_json::to_value(value)?)
+}
+
+/// Name of the redb table of the raw table `name`, it can't collide with the tables of the models
+/// that start with the id of the model.
+pub(crate) fn raw_table_name(name: &str) -> String {
+    format!("raw_{}", name)
+}
+
 pub struct PrimaryTableDefinition<'a> {
     pub(crate) model: crate::DatabaseModel,
     pub(crate) redb: RedbPrimaryTableDefinition<'a>,
     pub(crate) secondary_tables:
         HashMap<DatabaseKeyDefinition<DatabaseSecondaryKeyOptions>, SecondaryTableDefinition<'a>>,
     pub(crate) native_model_options: NativeModelOptions,
+    /// Name and decoder of the model for the JSON scans.
+    #[cfg(feature = "serde_json")]
+    pub(crate) json: Option<(String, JsonDecoder)>,
 }
 
 #[derive(Clone, Debug)]
@@ -44,6 +65,8 @@ impl<'a> From<(&ModelBuilder, RedbPrimaryTableDefinition<'a>)> for PrimaryTableD
             redb,
             secondary_tables: HashMap::new(),
             native_model_options: builder.native_model_options.clone(),
+            #[cfg(feature = "serde_json")]
+            json: builder.json.clone(),
         }
     }
 }
@@ -1,11 +1,14 @@
 use crate::db_type::{
-    DatabaseInnerKeyValue, DatabaseKeyDefinition, DatabaseOutputValue, DatabaseSecondaryKeyOptions,
-    Error, InnerKeyValue, KeyDefinition, Result,
+    composite_key, DatabaseInnerKeyValue, DatabaseInnerKeyValueRange, DatabaseKeyDefinition,
+    DatabaseOutputValue, DatabaseSecondaryKeyOptions, Error, InnerKeyValue, Input, KeyDefinition,
+    Result,
 };
+#[cfg(feature = "serde_json")]
+use crate::table_definition::JsonDecoder;
 use crate::table_definition::PrimaryTableDefinition;
 use crate::DatabaseModel;
 use redb::ReadableTable;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 pub trait PrivateReadableTransaction<'db, 'txn> {
     type RedbPrimaryTable: ReadableTable<DatabaseInnerKeyValue, &'static [u8]>;
@@ -25,6 +28,28 @@ pub trait PrivateReadableTransaction<'db, 'txn> {
         secondary_key: &DatabaseKeyDefinition<DatabaseSecondaryKeyOptions>,
     ) -> Result<Self::RedbSecondaryTable>;
 
+    /// Open the raw table `name`, see [`DatabaseBuilder::define_raw`](crate::DatabaseBuilder::define_raw).
+    fn get_raw_table(&'txn self, name: &str) -> Result<Self::RedbPrimaryTable>;
+
+    /// Open the primary table of the model registered under `name` and get its JSON decoder,
+    /// see [`DatabaseBuilder::define_json`](crate::DatabaseBuilder::define_json).
+    #[cfg(feature = "serde_json")]
+    fn get_json_table(&'txn self, name: &str) -> Result<(Self::RedbPrimaryTable, JsonDecoder)> {
+        let (model, decoder) = self
+            .table_definitions()
+            .values()
+            .find_map(|table_definition| match &table_definition.json {
+                Some((json_name, decoder)) if json_name == name => {
+                    Some((table_definition.model.clone(), *decoder))
+                }
+                _ => None,
+            })
+            .ok_or_else(|| Error::TableDefinitionNotFound {
+                table: name.to_string(),
+            })?;
+        Ok((self.get_primary_table(&model)?, decoder))
+    }
+
     fn get_by_primary_key(
         &'txn self,
         model: DatabaseModel,
@@ -36,6 +61,75 @@ pub trait PrivateReadableTransaction<'db, 'txn> {
         Ok(item.map(|item| item.value().into()))
     }
 
+    /// Get the values of `keys`, in the order of `keys`, opening the table of `model` once.
+    fn get_many_by_primary_keys<K: InnerKeyValue>(
+        &'txn self,
+        model: DatabaseModel,
+        keys: impl IntoIterator<Item = K>,
+    ) -> Result<Vec<(DatabaseInnerKeyValue, Option<DatabaseOutputValue>)>> {
+        let table = self.get_primary_table(&model)?;
+        let mut items = vec![];
+        for key in keys {
+            let key = key.database_inner_key_value();
+            let item = table.get(&key)?.map(|item| item.value().into());
+            items.push((key, item));
+        }
+        Ok(items)
+    }
+
+    /// Get the value of `key` in the table of `model` or, if it is not found, in the tables of the
+    /// older versions of the model, from the newest to the oldest.
+    fn get_by_primary_key_with_legacy(
+        &'txn self,
+        model: DatabaseModel,
+        key: impl InnerKeyValue,
+    ) -> Result<Option<DatabaseOutputValue>> {
+        let key = key.database_inner_key_value();
+        let table_name = model.primary_key.unique_table_name.clone();
+        if let Some(value) = self.get_by_primary_key(model, key.clone())? {
+            return Ok(Some(value));
+        }
+
+        let options = &self
+            .table_definitions()
+            .get(table_name.as_str())
+            .ok_or(Error::TableDefinitionNotFound { table: table_name })?
+            .native_model_options;
+        let mut legacy_models: Vec<_> = self
+            .table_definitions()
+            .values()
+            .filter(|table_definition| {
+                table_definition.native_model_options.native_model_id == options.native_model_id
+                    && table_definition.native_model_options.native_model_version
+                        < options.native_model_version
+            })
+            .map(|table_definition| {
+                (
+                    table_definition.native_model_options.native_model_version,
+                    table_definition.model.clone(),
+                )
+            })
+            .collect();
+        legacy_models.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        for (_, legacy_model) in legacy_models {
+            if let Some(value) = self.get_by_primary_key(legacy_model, key.clone())? {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+
+    fn get_raw(
+        &'txn self,
+        name: &str,
+        key: impl InnerKeyValue,
+    ) -> Result<Option<DatabaseOutputValue>> {
+        let table = self.get_raw_table(name)?;
+        let item = table.get(key.database_inner_key_value())?;
+        Ok(item.map(|item| item.value().into()))
+    }
+
     fn get_by_secondary_key(
         &'txn self,
         model: DatabaseModel,
@@ -61,9 +155,238 @@ pub trait PrivateReadableTransaction<'db, 'txn> {
         ))
     }
 
+    /// Same as [`get_by_secondary_key`](Self::get_by_secondary_key) for any secondary key, fails if more
+    /// than one value has the secondary key `key`.
+    fn get_by_secondary_key_checked(
+        &'txn self,
+        model: DatabaseModel,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        key: impl InnerKeyValue,
+    ) -> Result<Option<DatabaseOutputValue>> {
+        let key_name = key_def.database_key().unique_table_name;
+        let mut primary_keys =
+            self.primary_keys_by_secondary_key(model.clone(), key_def, key, None)?;
+        if primary_keys.len() > 1 {
+            return Err(Error::NonUniqueSecondaryKey {
+                key_name,
+                count: primary_keys.len(),
+            });
+        }
+        match primary_keys.pop() {
+            Some(primary_key) => Ok(Some(
+                self.get_by_primary_key(model, primary_key)?
+                    .ok_or(Error::PrimaryKeyNotFound)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    fn exists_by_secondary_key(
+        &'txn self,
+        model: DatabaseModel,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        key: impl InnerKeyValue,
+    ) -> Result<bool> {
+        let primary_keys = self.primary_keys_by_secondary_key(model, key_def, key, Some(1))?;
+        Ok(!primary_keys.is_empty())
+    }
+
+    /// Get the value with the secondary key `key` and the smallest primary key, for any secondary key.
+    fn get_first_by_secondary_key(
+        &'txn self,
+        model: DatabaseModel,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        key: impl InnerKeyValue,
+    ) -> Result<Option<DatabaseOutputValue>> {
+        let primary_keys =
+            self.primary_keys_by_secondary_key(model.clone(), key_def, key, Some(1))?;
+        match primary_keys.into_iter().next() {
+            Some(primary_key) => Ok(Some(
+                self.get_by_primary_key(model, primary_key)?
+                    .ok_or(Error::PrimaryKeyNotFound)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the primary keys of the values with the secondary key `key`, at most `limit`.
+    /// The values are not read.
+    fn primary_keys_by_secondary_key(
+        &'txn self,
+        model: DatabaseModel,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        key: impl InnerKeyValue,
+        limit: Option<usize>,
+    ) -> Result<Vec<DatabaseInnerKeyValue>> {
+        let secondary_key = key_def.database_key();
+        let unique = model
+            .secondary_keys
+            .get(&secondary_key)
+            .ok_or_else(|| Error::SecondaryKeyDefinitionNotFound {
+                table: model.primary_key.unique_table_name.to_string(),
+                key: secondary_key.unique_table_name.clone(),
+            })?
+            .options
+            .unique;
+
+        let table = self.get_secondary_table(&model, &secondary_key)?;
+        let key = key.database_inner_key_value();
+        if unique {
+            return Ok(table
+                .get(key)?
+                .map(|primary_key| primary_key.value())
+                .into_iter()
+                .collect());
+        }
+
+        // The keys of a non-unique secondary key are `<secondary key><primary key>`: keep the entries
+        // that are exactly `key` followed by their primary key, skip the longer keys that start with `key`.
+        let mut primary_keys = vec![];
+        for entry in table.range::<DatabaseInnerKeyValue>(key.clone()..)? {
+            if limit.is_some_and(|limit| primary_keys.len() >= limit) {
+                break;
+            }
+            let (secondary_key_value, primary_key) = entry?;
+            let secondary_key_value = secondary_key_value.value();
+            if !secondary_key_value.as_slice().starts_with(key.as_slice()) {
+                break;
+            }
+            let primary_key = primary_key.value();
+            if composite_key(&key, &primary_key) == secondary_key_value {
+                primary_keys.push(primary_key);
+            }
+        }
+        Ok(primary_keys)
+    }
+
+    fn secondary_top_n<T: Input>(
+        &'txn self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        n: usize,
+        descending: bool,
+    ) -> Result<Vec<T>> {
+        let model = T::native_db_model();
+        let secondary_key = key_def.database_key();
+        let unique = model
+            .secondary_keys
+            .get(&secondary_key)
+            .ok_or_else(|| Error::SecondaryKeyDefinitionNotFound {
+                table: model.primary_key.unique_table_name.to_string(),
+                key: secondary_key.unique_table_name.clone(),
+            })?
+            .options
+            .unique;
+
+        let secondary_table = self.get_secondary_table(&model, &secondary_key)?;
+        // `n` can be much larger than the table, e.g. `usize::MAX`.
+        let mut primary_keys = Vec::with_capacity(n.min(1024));
+        if descending {
+            // The keys of a non-unique secondary key are `<secondary key><primary key>`: the reverse
+            // iteration returns the ties by descending primary key, each group of ties is reversed
+            // to keep them by ascending primary key.
+            let mut group_key = None;
+            let mut group = vec![];
+            for entry in secondary_table.iter()?.rev() {
+                let (key, primary_key) = entry?;
+                let key = key.value();
+                let primary_key = primary_key.value();
+                let value = if unique {
+                    key.as_slice().to_vec()
+                } else {
+                    key.as_slice()[..key.as_slice().len() - primary_key.as_slice().len()].to_vec()
+                };
+                if group_key.as_ref() != Some(&value) {
+                    primary_keys.extend(group.drain(..).rev());
+                    if primary_keys.len() >= n {
+                        break;
+                    }
+                    group_key = Some(value);
+                }
+                group.push(primary_key);
+            }
+            primary_keys.extend(group.drain(..).rev());
+            primary_keys.truncate(n);
+        } else {
+            for entry in secondary_table.iter()?.take(n) {
+                let (_, primary_key) = entry?;
+                primary_keys.push(primary_key.value());
+            }
+        }
+
+        let primary_table = self.get_primary_table(&model)?;
+        let mut items = Vec::with_capacity(primary_keys.len());
+        for primary_key in primary_keys {
+            let value = primary_table
+                .get(primary_key)?
+                .ok_or(Error::PrimaryKeyNotFound)?;
+            items.push(DatabaseOutputValue::from(value.value()).inner());
+        }
+        Ok(items)
+    }
+
+    /// Returns each distinct value of the secondary key and the number of values with it, in key order.
+    fn secondary_group_counts<T: Input>(
+        &'txn self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+    ) -> Result<Vec<(Vec<u8>, u64)>> {
+        let model = T::native_db_model();
+        let secondary_key = key_def.database_key();
+        let unique = model
+            .secondary_keys
+            .get(&secondary_key)
+            .ok_or_else(|| Error::SecondaryKeyDefinitionNotFound {
+                table: model.primary_key.unique_table_name.to_string(),
+                key: secondary_key.unique_table_name.clone(),
+            })?
+            .options
+            .unique;
+
+        let secondary_table = self.get_secondary_table(&model, &secondary_key)?;
+        // The keys of a non-unique secondary key are `<secondary key><primary key>`: the entries with the
+        // same secondary key are not always consecutive, e.g. `a` + `c` is after `ab` + `x`.
+        let mut groups: BTreeMap<Vec<u8>, u64> = BTreeMap::new();
+        for entry in secondary_table.iter()? {
+            let (key, primary_key) = entry?;
+            let key = key.value();
+            let value = if unique {
+                key.as_slice()
+            } else {
+                let len = key.as_slice().len() - primary_key.value().as_slice().len();
+                &key.as_slice()[..len]
+            };
+            *groups.entry(value.to_vec()).or_insert(0) += 1;
+        }
+        Ok(groups.into_iter().collect())
+    }
+
     fn primary_len(&'txn self, model: DatabaseModel) -> Result<u64> {
         let table = self.get_primary_table(&model)?;
         let result = table.len()?;
         Ok(result)
     }
+
+    /// Returns the number of values of each defined model, keyed by the name of its primary table.
+    fn all_primary_len(&'txn self) -> Result<HashMap<String, u64>> {
+        let mut result = HashMap::new();
+        for (table_name, table_definition) in self.table_definitions() {
+            let table = self.get_primary_table(&table_definition.model)?;
+            result.insert(table_name.clone(), table.len()?);
+        }
+        Ok(result)
+    }
+
+    fn primary_range_len(
+        &'txn self,
+        model: DatabaseModel,
+        range: DatabaseInnerKeyValueRange,
+    ) -> Result<u64> {
+        let table = self.get_primary_table(&model)?;
+        let mut result = 0;
+        // The values are not decoded, only the entries of the range are counted.
+        for entry in table.range::<DatabaseInnerKeyValue>(range)? {
+            entry?;
+            result += 1;
+        }
+        Ok(result)
+    }
 }
@@ -1,14 +1,15 @@
 use crate::db_type::{
     DatabaseInnerKeyValue, DatabaseKeyDefinition, DatabaseSecondaryKeyOptions, Error, Result,
 };
-use crate::table_definition::PrimaryTableDefinition;
+use crate::table_definition::{raw_table_name, PrimaryTableDefinition, RedbPrimaryTableDefinition};
 use crate::transaction::internal::private_readable_transaction::PrivateReadableTransaction;
 use crate::DatabaseModel;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 pub struct InternalRTransaction<'db> {
     pub(crate) redb_transaction: redb::ReadTransaction<'db>,
     pub(crate) table_definitions: &'db HashMap<String, PrimaryTableDefinition<'db>>,
+    pub(crate) raw_tables: &'db HashSet<String>,
 }
 
 impl<'db, 'txn> PrivateReadableTransaction<'db, 'txn> for InternalRTransaction<'db>
@@ -20,7 +21,10 @@ where
     type RedbSecondaryTable =
         redb::ReadOnlyTable<'txn, DatabaseInnerKeyValue, DatabaseInnerKeyValue>;
 
-    type RedbTransaction<'db_bis> = redb::ReadTransaction<'db> where Self: 'db_bis;
+    type RedbTransaction<'db_bis>
+        = redb::ReadTransaction<'db>
+    where
+        Self: 'db_bis;
 
     fn table_definitions(&self) -> &HashMap<String, PrimaryTableDefinition> {
         &self.table_definitions
@@ -37,6 +41,17 @@ where
         Ok(table)
     }
 
+    fn get_raw_table(&'txn self, name: &str) -> Result<Self::RedbPrimaryTable> {
+        let table_name = raw_table_name(name);
+        if !self.raw_tables.contains(&table_name) {
+            return Err(Error::TableDefinitionNotFound { table: table_name });
+        }
+        let table = self
+            .redb_transaction
+            .open_table(RedbPrimaryTableDefinition::new(&table_name))?;
+        Ok(table)
+    }
+
     fn get_secondary_table(
         &'txn self,
         model: &DatabaseModel,
@@ -1,19 +1,27 @@
 use crate::db_type::{
-    DatabaseInnerKeyValue, DatabaseInput, DatabaseKeyDefinition, DatabaseKeyValue,
-    DatabaseOutputValue, DatabaseSecondaryKeyOptions, Error, Result,
+    DatabaseInnerKeyValue, DatabaseInnerKeyValueRange, DatabaseInput, DatabaseKeyDefinition,
+    DatabaseKeyValue, DatabaseOutputValue, DatabaseSecondaryKeyOptions, Error, Result,
 };
-use crate::table_definition::PrimaryTableDefinition;
+use crate::sequence;
+use crate::stats::StatsDatabase;
+use crate::table_definition::{raw_table_name, PrimaryTableDefinition, RedbPrimaryTableDefinition};
 use crate::transaction::internal::private_readable_transaction::PrivateReadableTransaction;
+use crate::version::{self, ModifiedRows};
 use crate::watch::WatcherRequest;
 use crate::{DatabaseModel, Input};
 use redb::ReadableTable;
 use redb::TableHandle;
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
+use std::ops::Bound;
 
 pub struct InternalRwTransaction<'db> {
     pub(crate) redb_transaction: redb::WriteTransaction<'db>,
     pub(crate) primary_table_definitions: &'db HashMap<String, PrimaryTableDefinition<'db>>,
+    pub(crate) raw_tables: &'db HashSet<String>,
+    /// The rows written by the transaction, `None` if the version tracking is disabled.
+    pub(crate) modified_rows: Option<RefCell<ModifiedRows>>,
 }
 
 impl<'db, 'txn> PrivateReadableTransaction<'db, 'txn> for InternalRwTransaction<'db>
@@ -24,7 +32,10 @@ where
     type RedbPrimaryTable = redb::Table<'db, 'txn, DatabaseInnerKeyValue, &'static [u8]>;
     type RedbSecondaryTable = redb::Table<'db, 'txn, DatabaseInnerKeyValue, DatabaseInnerKeyValue>;
 
-    type RedbTransaction<'db_bis> = redb::WriteTransaction<'db> where Self: 'db_bis;
+    type RedbTransaction<'db_bis>
+        = redb::WriteTransaction<'db>
+    where
+        Self: 'db_bis;
 
     fn table_definitions(&self) -> &HashMap<String, PrimaryTableDefinition> {
         &self.primary_table_definitions
@@ -41,6 +52,17 @@ where
         Ok(table)
     }
 
+    fn get_raw_table(&'txn self, name: &str) -> Result<Self::RedbPrimaryTable> {
+        let table_name = raw_table_name(name);
+        if !self.raw_tables.contains(&table_name) {
+            return Err(Error::TableDefinitionNotFound { table: table_name });
+        }
+        let table = self
+            .redb_transaction
+            .open_table(RedbPrimaryTableDefinition::new(&table_name))?;
+        Ok(table)
+    }
+
     fn get_secondary_table(
         &'txn self,
         model: &DatabaseModel,
@@ -65,27 +87,36 @@ where
     }
 }
 
-impl<'db> InternalRwTransaction<'db> {
-    pub(crate) fn commit(self) -> Result<()> {
-        self.redb_transaction.commit()?;
-        Ok(())
+/// The opened tables of a model, used to apply several writes without opening the tables again.
+pub(crate) struct RwModelTables<'db, 'txn> {
+    model: DatabaseModel,
+    primary: redb::Table<'db, 'txn, DatabaseInnerKeyValue, &'static [u8]>,
+    secondary: HashMap<
+        DatabaseKeyDefinition<DatabaseSecondaryKeyOptions>,
+        redb::Table<'db, 'txn, DatabaseInnerKeyValue, DatabaseInnerKeyValue>,
+    >,
+    modified_rows: Option<&'txn RefCell<ModifiedRows>>,
+}
+
+impl<'db, 'txn> RwModelTables<'db, 'txn> {
+    pub(crate) fn get(&self, key: &DatabaseInnerKeyValue) -> Result<Option<DatabaseOutputValue>> {
+        let item = self.primary.get(key)?;
+        Ok(item.map(|item| item.value().into()))
     }
 
-    pub(crate) fn concrete_insert(
-        &self,
-        model: DatabaseModel,
+    pub(crate) fn insert(
+        &mut self,
         item: DatabaseInput,
     ) -> Result<(WatcherRequest, DatabaseOutputValue)> {
-        let already_exists;
-        {
-            let mut table = self.get_primary_table(&model)?;
-            already_exists = table
-                .insert(&item.primary_key, item.value.as_slice())?
-                .is_some();
-        }
+        self.model.check_primary_key(&item.primary_key)?;
+
+        let already_exists = self
+            .primary
+            .insert(&item.primary_key, item.value.as_slice())?
+            .is_some();
 
         for (secondary_key_def, _value) in &item.secondary_keys {
-            let mut secondary_table = self.get_secondary_table(&model, secondary_key_def)?;
+            let secondary_table = self.secondary_table(secondary_key_def)?;
             let result = match item.secondary_key_value(secondary_key_def)? {
                 DatabaseKeyValue::Default(value) => {
                     secondary_table.insert(value, &item.primary_key)?
@@ -105,10 +136,11 @@ impl<'db> InternalRwTransaction<'db> {
                 .into());
             }
         }
+        self.record_modified_row(&item.primary_key, true);
 
         Ok((
             WatcherRequest::new(
-                model.primary_key.unique_table_name.clone(),
+                self.model.primary_key.unique_table_name.clone(),
                 item.primary_key,
                 item.secondary_keys,
             ),
@@ -116,19 +148,15 @@ impl<'db> InternalRwTransaction<'db> {
         ))
     }
 
-    pub(crate) fn concrete_remove(
-        &self,
-        model: DatabaseModel,
+    pub(crate) fn remove(
+        &mut self,
         item: DatabaseInput,
     ) -> Result<(WatcherRequest, DatabaseOutputValue)> {
-        let keys = &item.secondary_keys;
-        {
-            let mut table = self.get_primary_table(&model)?;
-            table.remove(&item.primary_key)?;
-        }
+        self.primary.remove(&item.primary_key)?;
+        self.record_modified_row(&item.primary_key, false);
 
-        for (secondary_key_def, _value) in keys {
-            let mut secondary_table = self.get_secondary_table(&model, secondary_key_def)?;
+        for (secondary_key_def, _value) in &item.secondary_keys {
+            let secondary_table = self.secondary_table(secondary_key_def)?;
             match &item.secondary_key_value(secondary_key_def)? {
                 DatabaseKeyValue::Default(value) => {
                     secondary_table.remove(value)?;
@@ -143,7 +171,7 @@ impl<'db> InternalRwTransaction<'db> {
 
         Ok((
             WatcherRequest::new(
-                model.primary_key.unique_table_name.clone(),
+                self.model.primary_key.unique_table_name.clone(),
                 item.primary_key,
                 item.secondary_keys,
             ),
@@ -151,15 +179,325 @@ impl<'db> InternalRwTransaction<'db> {
         ))
     }
 
+    pub(crate) fn update(
+        &mut self,
+        old_item: DatabaseInput,
+        updated_item: DatabaseInput,
+    ) -> Result<(WatcherRequest, DatabaseOutputValue, DatabaseOutputValue)> {
+        let (_, old_binary_value) = self.remove(old_item)?;
+        let (watcher_request, new_binary_value) = self.insert(updated_item)?;
+        Ok((watcher_request, old_binary_value, new_binary_value))
+    }
+
+    /// Checks that the updates `pairs` can be applied in order, before anything is written: the old value
+    /// of each pair is the stored value, and the updated values have a valid primary key and don't take the
+    /// unique secondary key of another value. The unchanged pairs are only checked against the stored value.
+    pub(crate) fn check_updates(&mut self, pairs: &[(DatabaseInput, DatabaseInput)]) -> Result<()> {
+        // The keys written by the previous pairs, they take precedence over the tables.
+        let mut primary: HashMap<DatabaseInnerKeyValue, Option<&[u8]>> = HashMap::new();
+        let mut secondary: HashMap<
+            (
+                &DatabaseKeyDefinition<DatabaseSecondaryKeyOptions>,
+                DatabaseInnerKeyValue,
+            ),
+            Option<&DatabaseInnerKeyValue>,
+        > = HashMap::new();
+        for (old_item, updated_item) in pairs {
+            let stored = match primary.get(&old_item.primary_key) {
+                Some(value) => value.map(|value| value.to_vec()),
+                None => self.get(&old_item.primary_key)?.map(|value| value.0),
+            };
+            match stored {
+                None => {
+                    return Err(Error::KeyNotFound {
+                        key: old_item.primary_key.as_slice().to_vec(),
+                    })
+                }
+                Some(stored) if stored != old_item.value => {
+                    return Err(Error::StaleUpdate {
+                        key: old_item.primary_key.as_slice().to_vec(),
+                    })
+                }
+                Some(_) => {}
+            }
+            if old_item.value == updated_item.value {
+                continue;
+            }
+            self.model.check_primary_key(&updated_item.primary_key)?;
+
+            primary.insert(old_item.primary_key.clone(), None);
+            for secondary_key_def in old_item.secondary_keys.keys() {
+                if let Some(value) = secondary_table_key(old_item, secondary_key_def)? {
+                    secondary.insert((secondary_key_def, value), None);
+                }
+            }
+
+            // Same as `insert`: a value replacing another primary key doesn't check the secondary keys.
+            let already_exists = match primary.get(&updated_item.primary_key) {
+                Some(value) => value.is_some(),
+                None => self.get(&updated_item.primary_key)?.is_some(),
+            };
+            primary.insert(
+                updated_item.primary_key.clone(),
+                Some(updated_item.value.as_slice()),
+            );
+            for secondary_key_def in updated_item.secondary_keys.keys() {
+                let value = match secondary_table_key(updated_item, secondary_key_def)? {
+                    Some(value) => value,
+                    None => continue,
+                };
+                let current = match secondary.get(&(secondary_key_def, value.clone())) {
+                    Some(primary_key) => primary_key.cloned(),
+                    None => self
+                        .secondary_table(secondary_key_def)?
+                        .get(&value)?
+                        .map(|primary_key| primary_key.value()),
+                };
+                if let Some(current) = current {
+                    if current != updated_item.primary_key && !already_exists {
+                        return Err(Error::DuplicateKey {
+                            key_name: secondary_key_def.unique_table_name.to_string(),
+                        });
+                    }
+                }
+                secondary.insert((secondary_key_def, value), Some(&updated_item.primary_key));
+            }
+        }
+        Ok(())
+    }
+
+    fn record_modified_row(&self, primary_key: &DatabaseInnerKeyValue, exists: bool) {
+        if let Some(modified_rows) = self.modified_rows {
+            modified_rows.borrow_mut().insert(
+                (
+                    self.model.primary_key.unique_table_name.clone(),
+                    primary_key.clone(),
+                ),
+                exists,
+            );
+        }
+    }
+
+    fn secondary_table(
+        &mut self,
+        secondary_key_def: &DatabaseKeyDefinition<DatabaseSecondaryKeyOptions>,
+    ) -> Result<&mut redb::Table<'db, 'txn, DatabaseInnerKeyValue, DatabaseInnerKeyValue>> {
+        self.secondary
+            .get_mut(secondary_key_def)
+            .ok_or_else(|| Error::TableDefinitionNotFound {
+                table: secondary_key_def.unique_table_name.to_string(),
+            })
+    }
+}
+
+/// The key of the value `item` in the secondary table `secondary_key_def`, `None` if the optional key is not set.
+fn secondary_table_key(
+    item: &DatabaseInput,
+    secondary_key_def: &DatabaseKeyDefinition<DatabaseSecondaryKeyOptions>,
+) -> Result<Option<DatabaseInnerKeyValue>> {
+    Ok(match item.secondary_key_value(secondary_key_def)? {
+        DatabaseKeyValue::Default(value) => Some(value),
+        DatabaseKeyValue::Optional(value) => value,
+    })
+}
+
+impl<'db> InternalRwTransaction<'db> {
+    pub(crate) fn commit(self) -> Result<()> {
+        self.redb_transaction.commit()?;
+        Ok(())
+    }
+
+    /// Stamps the rows written by the transaction with a new version, see
+    /// [`DatabaseBuilder::set_version_tracking`](crate::DatabaseBuilder::set_version_tracking).
+    pub(crate) fn record_versions(&self) -> Result<()> {
+        match &self.modified_rows {
+            Some(modified_rows) => version::record(&self.redb_transaction, &modified_rows.borrow()),
+            None => Ok(()),
+        }
+    }
+
+    /// The storage usage of the database, including the pages written by this transaction.
+    pub(crate) fn stats(&self) -> Result<StatsDatabase> {
+        Ok(self.redb_transaction.stats()?.into())
+    }
+
+    /// Open the primary table and all the secondary tables of the model.
+    pub(crate) fn open_model_tables<'txn>(
+        &'txn self,
+        model: &DatabaseModel,
+    ) -> Result<RwModelTables<'db, 'txn>> {
+        let primary = self.get_primary_table(model)?;
+        let mut secondary = HashMap::new();
+        let secondary_key_defs = self
+            .primary_table_definitions
+            .get(model.primary_key.unique_table_name.as_str())
+            .ok_or_else(|| Error::TableDefinitionNotFound {
+                table: model.primary_key.unique_table_name.to_string(),
+            })?
+            .secondary_tables
+            .keys();
+        for secondary_key_def in secondary_key_defs {
+            let table = self.get_secondary_table(model, secondary_key_def)?;
+            secondary.insert(secondary_key_def.clone(), table);
+        }
+        Ok(RwModelTables {
+            model: model.clone(),
+            primary,
+            secondary,
+            modified_rows: self.modified_rows.as_ref(),
+        })
+    }
+
+    pub(crate) fn concrete_insert_raw(
+        &self,
+        name: &str,
+        key: DatabaseInnerKeyValue,
+        value: &[u8],
+    ) -> Result<(WatcherRequest, Option<DatabaseOutputValue>)> {
+        let mut table = self.get_raw_table(name)?;
+        let old_value = table.insert(&key, value)?.map(|old| old.value().into());
+        Ok((
+            WatcherRequest::new(raw_table_name(name), key, HashMap::new()),
+            old_value,
+        ))
+    }
+
+    pub(crate) fn concrete_remove_raw(
+        &self,
+        name: &str,
+        key: DatabaseInnerKeyValue,
+    ) -> Result<(WatcherRequest, Option<DatabaseOutputValue>)> {
+        let mut table = self.get_raw_table(name)?;
+        let old_value = table.remove(&key)?.map(|old| old.value().into());
+        Ok((
+            WatcherRequest::new(raw_table_name(name), key, HashMap::new()),
+            old_value,
+        ))
+    }
+
+    pub(crate) fn concrete_next_sequence(&self, model_id: u32) -> Result<u64> {
+        sequence::next_sequence(&self.redb_transaction, model_id)
+    }
+
+    pub(crate) fn concrete_insert(
+        &self,
+        model: DatabaseModel,
+        item: DatabaseInput,
+    ) -> Result<(WatcherRequest, DatabaseOutputValue)> {
+        self.open_model_tables(&model)?.insert(item)
+    }
+
+    /// Index the values of the primary table in the secondary table `secondary_key_def`, at most
+    /// `chunk_size` values with a primary key greater than `after`.
+    ///
+    /// Returns the number of indexed values and the primary key of the last one.
+    pub(crate) fn concrete_backfill_secondary_chunk<T: Input>(
+        &self,
+        model: &DatabaseModel,
+        secondary_key_def: &DatabaseKeyDefinition<DatabaseSecondaryKeyOptions>,
+        after: Option<DatabaseInnerKeyValue>,
+        chunk_size: usize,
+    ) -> Result<(usize, Option<DatabaseInnerKeyValue>)> {
+        // The chunk can be much larger than the table, e.g. `usize::MAX`.
+        let mut items = Vec::with_capacity(chunk_size.min(1024));
+        {
+            let primary_table = self.get_primary_table(model)?;
+            let start = match after {
+                Some(after) => Bound::Excluded(after),
+                None => Bound::Unbounded,
+            };
+            for result in primary_table
+                .range::<DatabaseInnerKeyValue>((start, Bound::Unbounded))?
+                .take(chunk_size)
+            {
+                let (_, value) = result?;
+                items.push(T::native_db_bincode_decode_from_slice(value.value()).to_item());
+            }
+        }
+
+        let mut secondary_table = self.get_secondary_table(model, secondary_key_def)?;
+        for item in &items {
+            let value = match item.secondary_key_value(secondary_key_def)? {
+                DatabaseKeyValue::Default(value) => value,
+                DatabaseKeyValue::Optional(Some(value)) => value,
+                DatabaseKeyValue::Optional(None) => continue,
+            };
+            // The value may be already indexed, if the backfill is resumed.
+            let previous = secondary_table.insert(value, &item.primary_key)?;
+            if let Some(previous) = previous {
+                if previous.value() != item.primary_key {
+                    return Err(Error::DuplicateKey {
+                        key_name: secondary_key_def.unique_table_name.to_string(),
+                    });
+                }
+            }
+        }
+
+        let last_key = items.last().map(|item| item.primary_key.clone());
+        Ok((items.len(), last_key))
+    }
+
+    pub(crate) fn concrete_rebuild_secondary_index<T: Input>(
+        &self,
+        model: &DatabaseModel,
+        secondary_key_def: &DatabaseKeyDefinition<DatabaseSecondaryKeyOptions>,
+    ) -> Result<u64> {
+        let mut secondary_table = self.get_secondary_table(model, secondary_key_def)?;
+        // Clear the secondary table
+        for result in secondary_table.drain::<DatabaseInnerKeyValue>(..)? {
+            result?;
+        }
+
+        let primary_table = self.get_primary_table(model)?;
+        let mut count = 0;
+        for result in primary_table.iter()? {
+            let (_, value) = result?;
+            let item = T::native_db_bincode_decode_from_slice(value.value()).to_item();
+            let value = match item.secondary_key_value(secondary_key_def)? {
+                DatabaseKeyValue::Default(value) => value,
+                DatabaseKeyValue::Optional(Some(value)) => value,
+                DatabaseKeyValue::Optional(None) => continue,
+            };
+            if secondary_table.insert(value, &item.primary_key)?.is_some() {
+                return Err(Error::DuplicateKey {
+                    key_name: secondary_key_def.unique_table_name.to_string(),
+                });
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    pub(crate) fn concrete_remove(
+        &self,
+        model: DatabaseModel,
+        item: DatabaseInput,
+    ) -> Result<(WatcherRequest, DatabaseOutputValue)> {
+        self.open_model_tables(&model)?.remove(item)
+    }
+
     pub(crate) fn concrete_update(
         &self,
         model: DatabaseModel,
         old_item: DatabaseInput,
         updated_item: DatabaseInput,
     ) -> Result<(WatcherRequest, DatabaseOutputValue, DatabaseOutputValue)> {
-        let (_, old_binary_value) = self.concrete_remove(model.clone(), old_item)?;
-        let (watcher_request, new_binary_value) = self.concrete_insert(model, updated_item)?;
-        Ok((watcher_request, old_binary_value, new_binary_value))
+        self.open_model_tables(&model)?
+            .update(old_item, updated_item)
+    }
+
+    pub(crate) fn concrete_primary_range_values(
+        &self,
+        model: &DatabaseModel,
+        range: DatabaseInnerKeyValueRange,
+    ) -> Result<Vec<DatabaseOutputValue>> {
+        let primary_table = self.get_primary_table(model)?;
+        let mut values = vec![];
+        for result in primary_table.range::<DatabaseInnerKeyValue>(range)? {
+            let (_, value) = result?;
+            values.push(DatabaseOutputValue(value.value().to_vec()));
+        }
+        Ok(values)
     }
 
     pub(crate) fn concrete_primary_drain<'a>(
@@ -179,6 +517,18 @@ impl<'db> InternalRwTransaction<'db> {
             key_items.insert(primary_key.value().to_owned());
             items.push(binary_value);
         }
+        if let Some(modified_rows) = &self.modified_rows {
+            let mut modified_rows = modified_rows.borrow_mut();
+            for primary_key in &key_items {
+                modified_rows.insert(
+                    (
+                        model.primary_key.unique_table_name.clone(),
+                        primary_key.clone(),
+                    ),
+                    false,
+                );
+            }
+        }
 
         let secondary_table_names: Vec<&DatabaseKeyDefinition<DatabaseSecondaryKeyOptions>> = self
             .primary_table_definitions
@@ -5,9 +5,13 @@ pub mod query;
 
 mod r_transaction;
 
+mod readable_transaction;
+
 mod rw_transaction;
 
 /// Read-only transaction.
 pub use r_transaction::*;
+/// Read operations of all the transactions.
+pub use readable_transaction::*;
 /// Read-write transaction.
 pub use rw_transaction::*;
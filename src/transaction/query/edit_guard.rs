@@ -0,0 +1,62 @@
+use crate::db_type::{DatabaseInput, Input, Result};
+use crate::transaction::RwTransaction;
+use std::ops::{Deref, DerefMut};
+
+/// A value read by [`RwTransaction::get_mut`](crate::transaction::RwTransaction::get_mut) and written back
+/// when the guard is dropped or [saved](Self::save).
+///
+/// The value is written back only if its encoded bytes changed, like [`RwTransaction::update`](crate::transaction::RwTransaction::update):
+/// the secondary keys are maintained and the watchers receive an `Update` event. A guard dropped without
+/// changes writes nothing and sends no event.
+///
+/// An error can't be returned from `drop`: if the write fails when the guard is dropped, e.g. a unique
+/// secondary key is already used, the error is returned by [`RwTransaction::commit`](crate::transaction::RwTransaction::commit).
+/// Use [`save`](Self::save) to handle the error where it happens.
+pub struct EditGuard<'db, 'txn, T: Input> {
+    pub(crate) txn: &'txn RwTransaction<'db>,
+    pub(crate) old_item: Option<DatabaseInput>,
+    pub(crate) value: T,
+}
+
+impl<'db, 'txn, T: Input> EditGuard<'db, 'txn, T> {
+    /// Write the value back if it changed and return `true` if it was written.
+    pub fn save(mut self) -> Result<bool> {
+        self.write_back()
+    }
+
+    fn write_back(&mut self) -> Result<bool> {
+        let old_item = match self.old_item.take() {
+            Some(old_item) => old_item,
+            None => return Ok(false),
+        };
+        let updated_item = self.value.to_item();
+        if old_item.value == updated_item.value {
+            return Ok(false);
+        }
+        self.txn
+            .concrete_force_update::<T>(old_item, updated_item)?;
+        Ok(true)
+    }
+}
+
+impl<T: Input> Deref for EditGuard<'_, '_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Input> DerefMut for EditGuard<'_, '_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T: Input> Drop for EditGuard<'_, '_, T> {
+    fn drop(&mut self) {
+        if let Err(error) = self.write_back() {
+            self.txn.defer_error(error);
+        }
+    }
+}
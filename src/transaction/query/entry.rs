@@ -0,0 +1,143 @@
+use crate::db_type::{DatabaseInnerKeyValue, Error, Input, Result};
+use crate::transaction::RwTransaction;
+use crate::watch::Event;
+use std::marker::PhantomData;
+
+/// A value of the model `T` that may or may not be in the database, like
+/// [`std::collections::hash_map::Entry`], see [`RwTransaction::entry`](crate::transaction::RwTransaction::entry).
+///
+/// The writes are done in the transaction like [`RwTransaction::insert`](crate::transaction::RwTransaction::insert),
+/// [`update`](crate::transaction::RwTransaction::update) and [`remove`](crate::transaction::RwTransaction::remove),
+/// with their events.
+pub enum Entry<'db, 'txn, T: Input> {
+    Occupied(OccupiedEntry<'db, 'txn, T>),
+    Vacant(VacantEntry<'db, 'txn, T>),
+}
+
+impl<'db, 'txn, T: Input> Entry<'db, 'txn, T> {
+    /// The primary key of the entry.
+    pub fn key(&self) -> DatabaseInnerKeyValue {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key().clone(),
+        }
+    }
+
+    /// Modify the value with `f` if it exists. The value is written back, with an `Update` event,
+    /// only if `f` changed it.
+    pub fn and_modify(self, f: impl FnOnce(&mut T)) -> Result<Self> {
+        match self {
+            Entry::Occupied(mut entry) => {
+                let old_item = entry.value.to_item();
+                f(&mut entry.value);
+                let updated_item = entry.value.to_item();
+                if old_item.value != updated_item.value {
+                    entry
+                        .txn
+                        .concrete_force_update::<T>(old_item, updated_item)?;
+                }
+                Ok(Entry::Occupied(entry))
+            }
+            Entry::Vacant(entry) => Ok(Entry::Vacant(entry)),
+        }
+    }
+
+    /// Returns the value if it exists, otherwise inserts `default` and returns it.
+    pub fn or_insert(self, default: T) -> Result<T> {
+        self.or_insert_with(|| default)
+    }
+
+    /// Returns the value if it exists, otherwise inserts the result of `default` and returns it.
+    pub fn or_insert_with(self, default: impl FnOnce() -> T) -> Result<T> {
+        match self {
+            Entry::Occupied(entry) => Ok(entry.into_value()),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+}
+
+/// An [`Entry`] with a value.
+pub struct OccupiedEntry<'db, 'txn, T: Input> {
+    pub(crate) txn: &'txn RwTransaction<'db>,
+    pub(crate) value: T,
+}
+
+impl<'db, 'txn, T: Input> OccupiedEntry<'db, 'txn, T> {
+    /// The primary key of the value.
+    pub fn key(&self) -> DatabaseInnerKeyValue {
+        self.value.native_db_primary_key()
+    }
+
+    /// The value, as read when the entry was created.
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    pub fn into_value(self) -> T {
+        self.value
+    }
+
+    /// Replace the value by `value` and return the old value. Nothing is written if they are equal.
+    ///
+    /// Returns the error [`PrimaryKeyMismatch`](crate::db_type::Error::PrimaryKeyMismatch) if the primary key
+    /// of `value` is not the key of the entry.
+    pub fn insert(self, value: T) -> Result<T> {
+        let old_item = self.value.to_item();
+        let updated_item = value.to_item();
+        if updated_item.primary_key != old_item.primary_key {
+            return Err(Error::PrimaryKeyMismatch {
+                key: old_item.primary_key.as_slice().to_vec(),
+            });
+        }
+        if old_item.value != updated_item.value {
+            self.txn
+                .concrete_force_update::<T>(old_item, updated_item)?;
+        }
+        Ok(self.value)
+    }
+
+    /// Remove the value and return it.
+    pub fn remove(self) -> Result<T> {
+        let (watcher_request, binary_value) = self
+            .txn
+            .internal
+            .concrete_remove(T::native_db_model(), self.value.to_item())?;
+        let event = Event::new_delete(watcher_request.primary_key.clone(), binary_value);
+        self.txn.batch.borrow_mut().add(watcher_request, event);
+        Ok(self.value)
+    }
+}
+
+/// An [`Entry`] without value.
+pub struct VacantEntry<'db, 'txn, T: Input> {
+    pub(crate) txn: &'txn RwTransaction<'db>,
+    pub(crate) key: DatabaseInnerKeyValue,
+    pub(crate) _marker: PhantomData<T>,
+}
+
+impl<'db, 'txn, T: Input> VacantEntry<'db, 'txn, T> {
+    /// The primary key of the entry.
+    pub fn key(&self) -> &DatabaseInnerKeyValue {
+        &self.key
+    }
+
+    /// Insert `value` and return it.
+    ///
+    /// Returns the error [`PrimaryKeyMismatch`](crate::db_type::Error::PrimaryKeyMismatch) if the primary key
+    /// of `value` is not the key of the entry.
+    pub fn insert(self, value: T) -> Result<T> {
+        let item = value.to_item();
+        if item.primary_key != self.key {
+            return Err(Error::PrimaryKeyMismatch {
+                key: self.key.as_slice().to_vec(),
+            });
+        }
+        let (watcher_request, binary_value) = self
+            .txn
+            .internal
+            .concrete_insert(T::native_db_model(), item)?;
+        let event = Event::new_insert(binary_value);
+        self.txn.batch.borrow_mut().add(watcher_request, event);
+        Ok(value)
+    }
+}
@@ -1,7 +1,10 @@
-use crate::db_type::{DatabaseSecondaryKeyOptions, InnerKeyValue, Input, KeyDefinition, Result};
+use crate::db_type::{
+    DatabaseSecondaryKeyOptions, InnerKeyValue, Input, KeyDefinition, PrimaryKeySource, Result,
+};
 use crate::transaction::internal::private_readable_transaction::PrivateReadableTransaction;
 use crate::transaction::internal::r_transaction::InternalRTransaction;
 use crate::transaction::internal::rw_transaction::InternalRwTransaction;
+use std::collections::HashMap;
 
 /// Get a value from the database.
 pub struct RGet<'db, 'txn> {
@@ -44,6 +47,319 @@ impl RGet<'_, '_> {
         Ok(result.map(|value| value.inner()))
     }
 
+    /// Get the values of several primary keys, in the order of `keys`: `None` for a key without value.
+    ///
+    /// The table is opened once for all the keys. See [`primary_many_map`](Self::primary_many_map)
+    /// to get only the found values, by primary key.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Data { id: 1 })?;
+    ///     rw.commit()?;
+    ///
+    ///     let r = db.r_transaction()?;
+    ///     let values: Vec<Option<Data>> = r.get().primary_many(vec![1u64, 2])?;
+    ///     assert_eq!(values, vec![Some(Data { id: 1 }), None]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn primary_many<T: Input, K: InnerKeyValue>(
+        &self,
+        keys: impl IntoIterator<Item = K>,
+    ) -> Result<Vec<Option<T>>> {
+        let model = T::native_db_model();
+        let items = self.internal.get_many_by_primary_keys(model, keys)?;
+        Ok(items
+            .into_iter()
+            .map(|(_, item)| item.map(|value| value.inner()))
+            .collect())
+    }
+
+    /// Get the values of several primary keys, by their primary key as returned by
+    /// [`keys`](crate::transaction::query::PrimaryScan::keys): the keys without value are omitted.
+    ///
+    /// Use it to build a lookup table from a sparse set of keys, e.g. to join the values of two models.
+    /// See [`primary_many`](Self::primary_many) to get the values in the order of `keys`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Data { id: 1 })?;
+    ///     rw.commit()?;
+    ///
+    ///     let r = db.r_transaction()?;
+    ///     let values = r.get().primary_many_map::<Data, _>(vec![1u64, 2])?;
+    ///     assert_eq!(values.len(), 1);
+    ///     let key = 1u64.database_inner_key_value();
+    ///     assert_eq!(values[key.as_slice()].id, 1);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn primary_many_map<T: Input, K: InnerKeyValue>(
+        &self,
+        keys: impl IntoIterator<Item = K>,
+    ) -> Result<HashMap<Vec<u8>, T>> {
+        let model = T::native_db_model();
+        let items = self.internal.get_many_by_primary_keys(model, keys)?;
+        Ok(items
+            .into_iter()
+            .filter_map(|(key, item)| Some((key.as_slice().to_vec(), item?.inner())))
+            .collect())
+    }
+
+    /// Get a value from the database by primary key, or `T::default()` if there is no value.
+    ///
+    /// Nothing is inserted. The primary key of the default value is the one of `T::default()`, not `key`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize, Default)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Flag {
+    ///     #[primary_key]
+    ///     name: String,
+    ///     enabled: bool,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Flag>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     // Open a read transaction
+    ///     let r = db.r_transaction()?;
+    ///
+    ///     // The flag is disabled until it is inserted
+    ///     let flag: Flag = r.get().primary_or_default("new_ui")?;
+    ///     assert!(!flag.enabled);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn primary_or_default<T: Input + Default>(&self, key: impl InnerKeyValue) -> Result<T> {
+        Ok(self.primary(key)?.unwrap_or_default())
+    }
+
+    /// Get a value of the model `S` by primary key and convert it to `T` with [`From`].
+    ///
+    /// # Compatibility between model versions
+    ///
+    /// Each version of a model has its own table. During a rolling deploy, the new code can read the items
+    /// written by the old code in the table of the old version with `primary_as::<OldVersion, NewVersion>(key)`.
+    /// The old code can't decode a version it doesn't know, so keep writing the old version until all the
+    /// readers are deployed. Use [`migrate`](crate::transaction::RwTransaction::migrate) to move all the
+    /// items to the new version once the deploy is done.
+    ///
+    /// Items are encoded by [`native_model`](https://docs.rs/native_model) with the codec of the model
+    /// (bincode by default). Bincode is not self-describing: an item can only be decoded by a struct that has
+    /// exactly the same fields, so adding a field, even with `#[serde(default)]`, requires a new version of the model.
+    /// To tolerate missing fields without a new version, use a self-describing codec with
+    /// `#[native_model(id=1, version=1, with=<Codec>)]`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct DataV1 {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=2, from=DataV1)]
+    /// #[native_db]
+    /// struct DataV2 {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     name: String,
+    /// }
+    ///
+    /// impl From<DataV1> for DataV2 {
+    ///     fn from(data: DataV1) -> Self {
+    ///         DataV2 { id: data.id, name: String::new() }
+    ///     }
+    /// }
+    ///
+    /// impl From<DataV2> for DataV1 {
+    ///     fn from(data: DataV2) -> Self {
+    ///         DataV1 { id: data.id }
+    ///     }
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<DataV1>()?;
+    ///     builder.define::<DataV2>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     // Open a read transaction
+    ///     let r = db.r_transaction()?;
+    ///
+    ///     // Read an item written with the version 1 as the version 2
+    ///     let _value: Option<DataV2> = r.get().primary_as::<DataV1, DataV2>(1u64)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn primary_as<S: Input, T: From<S>>(&self, key: impl InnerKeyValue) -> Result<Option<T>> {
+        let model = S::native_db_model();
+        let result = self.internal.get_by_primary_key(model, key)?;
+        Ok(result.map(|value| value.inner::<S>().into()))
+    }
+
+    /// Get a value by primary key, from the table of `T` or, if it is not found there, from the tables
+    /// of the older versions of the model, and upgrade it to `T` in memory.
+    ///
+    /// The tables of the older versions are searched from the newest to the oldest. Each stored value
+    /// keeps the version it was written with: it is upgraded by the `from` chain of
+    /// [`native_model`](https://docs.rs/native_model) (`#[native_model(id=1, version=2, from=DataV1)]`
+    /// and `From<DataV1> for DataV2`). The upgraded value is not written back: use
+    /// [`RwTransaction::upgrade`](crate::transaction::RwTransaction::upgrade) to move it to the table of `T`.
+    /// Unlike [`migrate`](crate::transaction::RwTransaction::migrate), the hot tables can evolve
+    /// without moving all the values at once.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct DataV1 {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    /// #[native_model(id=1, version=2, from=DataV1)]
+    /// #[native_db]
+    /// struct DataV2 {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     name: String,
+    /// }
+    ///
+    /// impl From<DataV1> for DataV2 {
+    ///     fn from(data: DataV1) -> Self {
+    ///         DataV2 { id: data.id, name: String::new() }
+    ///     }
+    /// }
+    ///
+    /// impl From<DataV2> for DataV1 {
+    ///     fn from(data: DataV2) -> Self {
+    ///         DataV1 { id: data.id }
+    ///     }
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<DataV1>()?;
+    ///     builder.define::<DataV2>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(DataV1 { id: 1 })?;
+    ///     rw.commit()?;
+    ///
+    ///     // Open a read transaction
+    ///     let r = db.r_transaction()?;
+    ///
+    ///     // The item is still stored with the version 1
+    ///     let value: Option<DataV2> = r.get().primary_upgrade(1u64)?;
+    ///     assert_eq!(value, Some(DataV2 { id: 1, name: String::new() }));
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn primary_upgrade<T: Input>(&self, key: impl InnerKeyValue) -> Result<Option<T>> {
+        let model = T::native_db_model();
+        let result = self.internal.get_by_primary_key_with_legacy(model, key)?;
+        Ok(result.map(|value| value.inner()))
+    }
+
+    /// Get a value from the database by its typed primary key `<Model>KeySource`.
+    ///
+    /// The key is encoded by the same code as the one used on insert, see [`PrimaryKeySource`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     name: String,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     // Open a read transaction
+    ///     let r = db.r_transaction()?;
+    ///
+    ///     // Get a value by typed primary key
+    ///     let _value: Option<Data> = r.get().primary_by::<Data>(&DataKeySource { id: 1 })?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn primary_by<T: PrimaryKeySource>(&self, key_source: &T::KeySource) -> Result<Option<T>> {
+        let model = T::native_db_model();
+        let key = T::native_db_key_source_primary_key(key_source);
+        let result = self.internal.get_by_primary_key(model, key)?;
+        Ok(result.map(|value| value.inner()))
+    }
+
     /// Get a value from the database by secondary key.
     ///
     /// /!\ The secondary key **must** be [`unique`](crate::DatabaseBuilder#unique) else this method will return an error [`SecondaryKeyConstraintMismatch`](crate::db_type::Error::SecondaryKeyConstraintMismatch).
@@ -89,6 +405,209 @@ impl RGet<'_, '_> {
         let result = self.internal.get_by_secondary_key(model, key_def, key)?;
         Ok(result.map(|value| value.inner()))
     }
+
+    /// Get the bytes of the key `key` in the raw table `name`, see [`DatabaseBuilder::define_raw`](crate::DatabaseBuilder::define_raw).
+    pub fn raw(&self, name: &str, key: impl InnerKeyValue) -> Result<Option<Vec<u8>>> {
+        let result = self.internal.get_raw(name, key)?;
+        Ok(result.map(|value| value.0))
+    }
+
+    /// Get the value with the secondary key `key`, for a secondary key that is expected to be unique but is not
+    /// declared `unique`, e.g. because the existing data predates the constraint.
+    ///
+    /// Unlike [`secondary`](Self::secondary), it works with the non-unique secondary keys and returns the error
+    /// [`NonUniqueSecondaryKey`](crate::db_type::Error::NonUniqueSecondaryKey) with the number of matching values
+    /// if more than one value has the secondary key `key`, instead of silently taking the first one.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct User {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     #[secondary_key]
+    ///     email: String,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<User>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(User { id: 1, email: "a@example.com".to_string() })?;
+    ///     rw.insert(User { id: 2, email: "a@example.com".to_string() })?;
+    ///     rw.commit()?;
+    ///
+    ///     // Open a read transaction
+    ///     let r = db.r_transaction()?;
+    ///     let result = r.get().secondary_unique::<User>(UserKey::email, "a@example.com");
+    ///     assert!(matches!(result, Err(db_type::Error::NonUniqueSecondaryKey { count: 2, .. })));
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn secondary_unique<T: Input>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        key: impl InnerKeyValue,
+    ) -> Result<Option<T>> {
+        let model = T::native_db_model();
+        let result = self
+            .internal
+            .get_by_secondary_key_checked(model, key_def, key)?;
+        Ok(result.map(|value| value.inner()))
+    }
+
+    /// Get the first value with the secondary key `key`: the one with the smallest primary key.
+    ///
+    /// Works with unique and non-unique secondary keys. The entries of a non-unique secondary key are sorted by
+    /// the secondary key then by the primary key, so the first value is stable as long as the values with the
+    /// key don't change. Only this value is read: it is cheaper than collecting all the values with the key
+    /// with [`scan().secondary()`](crate::transaction::query::RScan::secondary) to keep the first one.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Product {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     #[secondary_key]
+    ///     category: String,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Product>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Product { id: 2, category: "fruit".to_string() })?;
+    ///     rw.insert(Product { id: 1, category: "fruit".to_string() })?;
+    ///     rw.commit()?;
+    ///
+    ///     // Open a read transaction
+    ///     let r = db.r_transaction()?;
+    ///
+    ///     // Any fruit: the one with the smallest id
+    ///     let product: Option<Product> = r.get().secondary_first(ProductKey::category, "fruit")?;
+    ///     assert_eq!(product.unwrap().id, 1);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn secondary_first<T: Input>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        key: impl InnerKeyValue,
+    ) -> Result<Option<T>> {
+        let model = T::native_db_model();
+        let result = self
+            .internal
+            .get_first_by_secondary_key(model, key_def, key)?;
+        Ok(result.map(|value| value.inner()))
+    }
+
+    /// Returns `true` if at least one value has the secondary key `key`.
+    ///
+    /// Works with unique and non-unique secondary keys. The values are not read and the lookup stops
+    /// at the first match, so it is cheaper than scanning or counting the values with the key.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct User {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     #[secondary_key]
+    ///     role: String,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<User>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     // Open a read transaction
+    ///     let r = db.r_transaction()?;
+    ///
+    ///     // Is there an admin?
+    ///     let _exists: bool = r.get().secondary_exists::<User>(UserKey::role, "admin")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn secondary_exists<T: Input>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        key: impl InnerKeyValue,
+    ) -> Result<bool> {
+        let model = T::native_db_model();
+        self.internal.exists_by_secondary_key(model, key_def, key)
+    }
+
+    /// Get the primary keys of the values with the secondary key `key`, without reading the values.
+    ///
+    /// Works with unique and non-unique secondary keys, the primary keys are returned in ascending order.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Order {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     #[secondary_key]
+    ///     customer: String,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Order>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     // Open a read transaction
+    ///     let r = db.r_transaction()?;
+    ///
+    ///     // Get the ids of the orders of a customer
+    ///     let _ids: Vec<Vec<u8>> = r.get().secondary_primary_keys::<Order>(OrderKey::customer, "alice")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn secondary_primary_keys<T: Input>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        key: impl InnerKeyValue,
+    ) -> Result<Vec<Vec<u8>>> {
+        let model = T::native_db_model();
+        let primary_keys = self
+            .internal
+            .primary_keys_by_secondary_key(model, key_def, key, None)?;
+        Ok(primary_keys
+            .into_iter()
+            .map(|primary_key| primary_key.as_slice().to_vec())
+            .collect())
+    }
 }
 
 pub struct RwGet<'db, 'txn> {
@@ -105,6 +624,72 @@ impl RwGet<'_, '_> {
         Ok(result.map(|value| value.inner()))
     }
 
+    /// Get the values of several primary keys, in the order of `keys`.
+    ///
+    /// Same as [`RGet::primary_many()`](struct.RGet.html#method.primary_many).
+    pub fn primary_many<T: Input, K: InnerKeyValue>(
+        &self,
+        keys: impl IntoIterator<Item = K>,
+    ) -> Result<Vec<Option<T>>> {
+        let model = T::native_db_model();
+        let items = self.internal.get_many_by_primary_keys(model, keys)?;
+        Ok(items
+            .into_iter()
+            .map(|(_, item)| item.map(|value| value.inner()))
+            .collect())
+    }
+
+    /// Get the values of several primary keys, by their primary key.
+    ///
+    /// Same as [`RGet::primary_many_map()`](struct.RGet.html#method.primary_many_map).
+    pub fn primary_many_map<T: Input, K: InnerKeyValue>(
+        &self,
+        keys: impl IntoIterator<Item = K>,
+    ) -> Result<HashMap<Vec<u8>, T>> {
+        let model = T::native_db_model();
+        let items = self.internal.get_many_by_primary_keys(model, keys)?;
+        Ok(items
+            .into_iter()
+            .filter_map(|(key, item)| Some((key.as_slice().to_vec(), item?.inner())))
+            .collect())
+    }
+
+    /// Get a value from the database by primary key, or `T::default()` if there is no value.
+    ///
+    /// Same as [`RGet::primary_or_default()`](struct.RGet.html#method.primary_or_default).
+    pub fn primary_or_default<T: Input + Default>(&self, key: impl InnerKeyValue) -> Result<T> {
+        Ok(self.primary(key)?.unwrap_or_default())
+    }
+
+    /// Get a value of the model `S` by primary key and convert it to `T` with [`From`].
+    ///
+    /// Same as [`RGet::primary_as()`](struct.RGet.html#method.primary_as).
+    pub fn primary_as<S: Input, T: From<S>>(&self, key: impl InnerKeyValue) -> Result<Option<T>> {
+        let model = S::native_db_model();
+        let result = self.internal.get_by_primary_key(model, key)?;
+        Ok(result.map(|value| value.inner::<S>().into()))
+    }
+
+    /// Get a value by primary key and upgrade it to `T` in memory, from the tables of the older versions
+    /// of the model if needed.
+    ///
+    /// Same as [`RGet::primary_upgrade()`](struct.RGet.html#method.primary_upgrade).
+    pub fn primary_upgrade<T: Input>(&self, key: impl InnerKeyValue) -> Result<Option<T>> {
+        let model = T::native_db_model();
+        let result = self.internal.get_by_primary_key_with_legacy(model, key)?;
+        Ok(result.map(|value| value.inner()))
+    }
+
+    /// Get a value from the database by its typed primary key `<Model>KeySource`.
+    ///
+    /// Same as [`RGet::primary_by()`](struct.RGet.html#method.primary_by).
+    pub fn primary_by<T: PrimaryKeySource>(&self, key_source: &T::KeySource) -> Result<Option<T>> {
+        let model = T::native_db_model();
+        let key = T::native_db_key_source_primary_key(key_source);
+        let result = self.internal.get_by_primary_key(model, key)?;
+        Ok(result.map(|value| value.inner()))
+    }
+
     /// Get a value from the database by secondary key.
     ///
     /// Same as [`RGet::secondary()`](struct.RGet.html#method.secondary).
@@ -117,4 +702,72 @@ impl RwGet<'_, '_> {
         let result = self.internal.get_by_secondary_key(model, key_def, key)?;
         Ok(result.map(|value| value.inner()))
     }
+
+    /// Get the bytes of the key `key` in the raw table `name`.
+    ///
+    /// Same as [`RGet::raw()`](struct.RGet.html#method.raw).
+    pub fn raw(&self, name: &str, key: impl InnerKeyValue) -> Result<Option<Vec<u8>>> {
+        let result = self.internal.get_raw(name, key)?;
+        Ok(result.map(|value| value.0))
+    }
+
+    /// Get the value with the secondary key `key`, fails if more than one value has it.
+    ///
+    /// Same as [`RGet::secondary_unique()`](struct.RGet.html#method.secondary_unique).
+    pub fn secondary_unique<T: Input>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        key: impl InnerKeyValue,
+    ) -> Result<Option<T>> {
+        let model = T::native_db_model();
+        let result = self
+            .internal
+            .get_by_secondary_key_checked(model, key_def, key)?;
+        Ok(result.map(|value| value.inner()))
+    }
+
+    /// Get the first value with the secondary key `key`: the one with the smallest primary key.
+    ///
+    /// Same as [`RGet::secondary_first()`](struct.RGet.html#method.secondary_first).
+    pub fn secondary_first<T: Input>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        key: impl InnerKeyValue,
+    ) -> Result<Option<T>> {
+        let model = T::native_db_model();
+        let result = self
+            .internal
+            .get_first_by_secondary_key(model, key_def, key)?;
+        Ok(result.map(|value| value.inner()))
+    }
+
+    /// Returns `true` if at least one value has the secondary key `key`.
+    ///
+    /// Same as [`RGet::secondary_exists()`](struct.RGet.html#method.secondary_exists).
+    pub fn secondary_exists<T: Input>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        key: impl InnerKeyValue,
+    ) -> Result<bool> {
+        let model = T::native_db_model();
+        self.internal.exists_by_secondary_key(model, key_def, key)
+    }
+
+    /// Get the primary keys of the values with the secondary key `key`, without reading the values.
+    ///
+    /// Same as [`RGet::secondary_primary_keys()`](struct.RGet.html#method.secondary_primary_keys).
+    pub fn secondary_primary_keys<T: Input>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        key: impl InnerKeyValue,
+    ) -> Result<Vec<Vec<u8>>> {
+        let model = T::native_db_model();
+        let primary_keys = self
+            .internal
+            .primary_keys_by_secondary_key(model, key_def, key, None)?;
+        Ok(primary_keys
+            .into_iter()
+            .map(|primary_key| primary_key.as_slice().to_vec())
+            .collect())
+    }
 }
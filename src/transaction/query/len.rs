@@ -1,7 +1,12 @@
-use crate::db_type::{DatabaseSecondaryKeyOptions, Input, KeyDefinition, Result};
+use crate::db_type::{
+    DatabaseInnerKeyValueRange, DatabaseSecondaryKeyOptions, InnerKeyValue, Input, KeyDefinition,
+    Result,
+};
 use crate::transaction::internal::private_readable_transaction::PrivateReadableTransaction;
 use crate::transaction::internal::r_transaction::InternalRTransaction;
 use crate::transaction::internal::rw_transaction::InternalRwTransaction;
+use std::collections::HashMap;
+use std::ops::RangeBounds;
 
 /// Get the number of values in the database.
 pub struct RLen<'db, 'txn> {
@@ -44,6 +49,49 @@ impl RLen<'_, '_> {
         Ok(result)
     }
 
+    /// Get the number of values with a primary key in the `range`.
+    ///
+    /// The values are not decoded. The bounds of the range are the same as
+    /// [`PrimaryScan::range()`](crate::transaction::query::PrimaryScan::range).
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     timestamp: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     // Open a read transaction
+    ///     let r = db.r_transaction()?;
+    ///
+    ///     // Get the number of values between two timestamps
+    ///     let _number: u64 = r.len().primary_range::<Data, _, _>(1_000u64..2_000u64)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn primary_range<T: Input, TR: InnerKeyValue, R: RangeBounds<TR>>(
+        &self,
+        range: R,
+    ) -> Result<u64> {
+        let model = T::native_db_model();
+        let result = self
+            .internal
+            .primary_range_len(model, DatabaseInnerKeyValueRange::new(range))?;
+        Ok(result)
+    }
+
     /// **TODO: needs to be implemented**
     ///
     /// Get the number of values by secondary key.
@@ -58,6 +106,92 @@ impl RLen<'_, '_> {
     ) -> Result<Option<T>> {
         todo!()
     }
+
+    /// Get each distinct value of the secondary key `key_def` and the number of values with it, in key order.
+    ///
+    /// The values are the bytes of the secondary key. The secondary table is scanned once and the values
+    /// of the model are not decoded. Use it e.g. for the counts of a faceted search. The values without
+    /// the secondary key, `None` for an [`optional`](crate::DatabaseBuilder::define#optional) key or
+    /// empty for a [`sparse`](crate::DatabaseBuilder::define#sparse) key, are not indexed so not counted.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     #[secondary_key]
+    ///     category: String,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Data { id: 1, category: "book".to_string() })?;
+    ///     rw.insert(Data { id: 2, category: "game".to_string() })?;
+    ///     rw.insert(Data { id: 3, category: "book".to_string() })?;
+    ///     rw.commit()?;
+    ///
+    ///     // Open a read transaction
+    ///     let r = db.r_transaction()?;
+    ///
+    ///     let counts = r.len().secondary_group_counts::<Data>(DataKey::category)?;
+    ///     assert_eq!(counts, vec![(b"book".to_vec(), 2), (b"game".to_vec(), 1)]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn secondary_group_counts<T: Input>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+    ) -> Result<Vec<(Vec<u8>, u64)>> {
+        self.internal.secondary_group_counts::<T>(key_def)
+    }
+
+    /// Get the number of values of every defined model, keyed by the name of its primary table
+    /// (see [`DatabaseKeyDefinition::unique_table_name`](crate::db_type::DatabaseKeyDefinition::unique_table_name)).
+    ///
+    /// All the counts are taken from the same transaction, so they are consistent with each other
+    /// even if other transactions write concurrently. The values are not decoded.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     // Open a read transaction
+    ///     let r = db.r_transaction()?;
+    ///
+    ///     let counts = r.len().all()?;
+    ///     let _number: u64 = counts[Data::native_db_model().primary_key.unique_table_name()];
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn all(&self) -> Result<HashMap<String, u64>> {
+        self.internal.all_primary_len()
+    }
 }
 
 pub struct RwLen<'db, 'txn> {
@@ -74,6 +208,20 @@ impl RwLen<'_, '_> {
         Ok(result)
     }
 
+    /// Get the number of values with a primary key in the `range`.
+    ///
+    /// Same as [`RLen::primary_range()`](struct.RLen.html#method.primary_range).
+    pub fn primary_range<T: Input, TR: InnerKeyValue, R: RangeBounds<TR>>(
+        &self,
+        range: R,
+    ) -> Result<u64> {
+        let model = T::native_db_model();
+        let result = self
+            .internal
+            .primary_range_len(model, DatabaseInnerKeyValueRange::new(range))?;
+        Ok(result)
+    }
+
     /// Get the number of values by secondary key.
     ///
     /// Same as [`RLen::secondary()`](struct.RLen.html#method.secondary).
@@ -83,4 +231,21 @@ impl RwLen<'_, '_> {
     ) -> Result<Option<T>> {
         todo!()
     }
+
+    /// Get each distinct value of the secondary key and the number of values with it.
+    ///
+    /// Same as [`RLen::secondary_group_counts()`](struct.RLen.html#method.secondary_group_counts).
+    pub fn secondary_group_counts<T: Input>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+    ) -> Result<Vec<(Vec<u8>, u64)>> {
+        self.internal.secondary_group_counts::<T>(key_def)
+    }
+
+    /// Get the number of values of every defined model.
+    ///
+    /// Same as [`RLen::all()`](struct.RLen.html#method.all).
+    pub fn all(&self) -> Result<HashMap<String, u64>> {
+        self.internal.all_primary_len()
+    }
 }
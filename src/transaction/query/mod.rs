@@ -1,9 +1,15 @@
 mod drain;
+mod edit_guard;
+mod entry;
 mod get;
 mod len;
 mod scan;
+mod typed_table;
 
 pub use drain::*;
+pub use edit_guard::*;
+pub use entry::*;
 pub use get::*;
 pub use len::*;
 pub use scan::*;
+pub use typed_table::*;
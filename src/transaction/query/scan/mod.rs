@@ -1,10 +1,14 @@
 mod primary_scan;
+#[cfg(feature = "serde_json")]
+mod primary_scan_json;
 mod secondary_scan;
 
 use crate::db_type::{
     DatabaseInnerKeyValue, DatabaseSecondaryKeyOptions, Input, KeyDefinition, Result,
 };
 pub use primary_scan::*;
+#[cfg(feature = "serde_json")]
+pub use primary_scan_json::*;
 pub use secondary_scan::*;
 
 use crate::transaction::internal::private_readable_transaction::PrivateReadableTransaction;
@@ -28,6 +32,21 @@ impl<'txn> RScan<'_, 'txn> {
         Ok(out)
     }
 
+    /// Get the values of the model registered under `name` as JSON, without knowing its type,
+    /// see [`DatabaseBuilder::define_json`](crate::DatabaseBuilder::define_json).
+    ///
+    /// Returns [`TableDefinitionNotFound`](crate::db_type::Error::TableDefinitionNotFound) if no model
+    /// is registered under `name`.
+    #[cfg(feature = "serde_json")]
+    pub fn primary_json(
+        &self,
+        name: &str,
+    ) -> Result<PrimaryScanJson<redb::ReadOnlyTable<'txn, DatabaseInnerKeyValue, &'static [u8]>>>
+    {
+        let (table, decoder) = self.internal.get_json_table(name)?;
+        Ok(PrimaryScanJson::new(table, decoder))
+    }
+
     /// Get a values from the database by secondary key.
     pub fn secondary<T: Input>(
         &self,
@@ -46,6 +65,50 @@ impl<'txn> RScan<'_, 'txn> {
         let out = SecondaryScan::new(primary_table, secondary_table);
         Ok(out)
     }
+
+    /// Get the first `n` values by secondary key, from the highest key if `descending` is `true`,
+    /// from the lowest key otherwise.
+    ///
+    /// The values with the same secondary key are returned by ascending primary key, in both orders.
+    /// Numeric keys are encoded in big-endian, so they are ordered by value: use it for a leaderboard.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Player {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     #[secondary_key]
+    ///     score: u32,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Player>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     // Open a read transaction
+    ///     let r = db.r_transaction()?;
+    ///
+    ///     // Get the 10 best scores
+    ///     let _top: Vec<Player> = r.scan().secondary_top_n(PlayerKey::score, 10, true)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn secondary_top_n<T: Input>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        n: usize,
+        descending: bool,
+    ) -> Result<Vec<T>> {
+        self.internal.secondary_top_n(key_def, n, descending)
+    }
 }
 
 pub struct RwScan<'db, 'txn> {
@@ -62,6 +125,18 @@ impl<'db, 'txn> RwScan<'db, 'txn> {
         Ok(out)
     }
 
+    /// Get the values of the model registered under `name` as JSON.
+    ///
+    /// Same as [`RScan::primary_json()`](struct.RScan.html#method.primary_json).
+    #[cfg(feature = "serde_json")]
+    pub fn primary_json(
+        &self,
+        name: &str,
+    ) -> Result<PrimaryScanJson<redb::Table<'db, 'txn, DatabaseInnerKeyValue, &'static [u8]>>> {
+        let (table, decoder) = self.internal.get_json_table(name)?;
+        Ok(PrimaryScanJson::new(table, decoder))
+    }
+
     pub fn secondary<T: Input>(
         &self,
         key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
@@ -79,4 +154,16 @@ impl<'db, 'txn> RwScan<'db, 'txn> {
         let out = SecondaryScan::new(primary_table, secondary_table);
         Ok(out)
     }
+
+    /// Get the first `n` values by secondary key.
+    ///
+    /// Same as [`RScan::secondary_top_n()`](struct.RScan.html#method.secondary_top_n).
+    pub fn secondary_top_n<T: Input>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        n: usize,
+        descending: bool,
+    ) -> Result<Vec<T>> {
+        self.internal.secondary_top_n(key_def, n, descending)
+    }
 }
@@ -1,7 +1,11 @@
-use crate::db_type::{unwrap_item, DatabaseInnerKeyValue, DatabaseInnerKeyValueRange, Input};
+use crate::db_type::{
+    unwrap_item, DatabaseInnerKeyValue, DatabaseInnerKeyValueRange, Error, Input, Result,
+};
 use crate::InnerKeyValue;
 use std::marker::PhantomData;
-use std::ops::RangeBounds;
+use std::ops::{Bound, RangeBounds};
+use std::panic;
+use std::thread::{self, JoinHandle};
 
 /// Scan values from the database.
 pub struct PrimaryScan<PrimaryTable, T: Input>
@@ -63,6 +67,104 @@ where
         }
     }
 
+    /// Iterate over all values, reading and decoding them by batches of `buffer` values.
+    ///
+    /// While the values of a batch are consumed, the next batch is read and decoded in another thread:
+    /// the decoding overlaps the processing of the values, e.g. for an export of a large table. The
+    /// values are returned in the same order as [`all`](Self::all), which stays the default: it decodes
+    /// each value when it is consumed and doesn't start a thread.
+    ///
+    /// Up to two batches are in memory: the one being consumed and the one being decoded, about
+    /// `2 × buffer ×` the average size of a value, for the encoded and the decoded values. A thread is
+    /// started for each batch, so `buffer` should be large enough to amortize it, e.g. a few thousands.
+    ///
+    /// The iteration ends at the first entry that can't be read from the storage, like [`all`](Self::all).
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     // Open a read transaction
+    ///     let r = db.r_transaction()?;
+    ///
+    ///     // Export all values, by batches of 1000
+    ///     for _value in r.scan().primary::<Data>()?.all_buffered(1000) {
+    ///         // ...
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn all_buffered(&self, buffer: usize) -> PrimaryScanBufferedIterator<T>
+    where
+        T: Send + 'static,
+    {
+        let range = self
+            .primary_table
+            .range::<DatabaseInnerKeyValue>(..)
+            .unwrap();
+        PrimaryScanBufferedIterator {
+            range,
+            buffer: buffer.max(1),
+            values: Vec::new().into_iter(),
+            decoding: None,
+            done: false,
+        }
+    }
+
+    /// Iterate over all primary keys, without decoding the values.
+    ///
+    /// The keys are returned as they are stored: the encoded bytes of the primary key.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///     
+    ///     // Open a read transaction
+    ///     let r = db.r_transaction()?;
+    ///     
+    ///     // Get all primary keys
+    ///     let _keys: Vec<Vec<u8>> = r.scan().primary::<Data>()?.keys().collect();
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn keys(&self) -> PrimaryScanKeysIterator<'_> {
+        let range = self
+            .primary_table
+            .range::<DatabaseInnerKeyValue>(..)
+            .unwrap();
+        PrimaryScanKeysIterator { range }
+    }
+
     /// Iterate over all values in a range.
     ///
     /// # Example
@@ -104,6 +206,98 @@ where
         }
     }
 
+    /// Get at most `limit` values after the primary key `after`, in the order of the primary keys:
+    /// a page of values for the key-based pagination.
+    ///
+    /// `after` is the encoded primary key, as returned by [`keys`](Self::keys) and [`Page::next`]: pass
+    /// `None` to get the first page, then the `next` key of the previous page. The scan seeks directly to
+    /// `after` and decodes only the values of the page.
+    ///
+    /// Prefer it to a count-based offset like `all().skip(offset).take(limit)`: the skipped values are
+    /// still read one by one, so the cost of a page grows with its offset. A key also stays valid when
+    /// values are inserted or removed before it, while an offset shifts the pages.
+    ///
+    /// `limit` must be greater than 0, otherwise the error [`InvalidPageLimit`](crate::db_type::Error::InvalidPageLimit)
+    /// is returned. Use `usize::MAX` to get all the values after `after`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     for id in 0..5 {
+    ///         rw.insert(Data { id })?;
+    ///     }
+    ///     rw.commit()?;
+    ///
+    ///     // Open a read transaction
+    ///     let r = db.r_transaction()?;
+    ///
+    ///     // Read the values by pages of 2
+    ///     let mut after = None;
+    ///     let mut ids = vec![];
+    ///     loop {
+    ///         let page = r.scan().primary::<Data>()?.page(after.as_deref(), 2)?;
+    ///         ids.extend(page.values.iter().map(|data| data.id));
+    ///         match page.next {
+    ///             Some(next) => after = Some(next),
+    ///             None => break,
+    ///         }
+    ///     }
+    ///     assert_eq!(ids, vec![0, 1, 2, 3, 4]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn page(&self, after: Option<&[u8]>, limit: usize) -> Result<Page<T>> {
+        if limit == 0 {
+            return Err(Error::InvalidPageLimit);
+        }
+        let start = match after {
+            Some(after) => Bound::Excluded(DatabaseInnerKeyValue::new(after.to_vec())),
+            None => Bound::Unbounded,
+        };
+        let mut range = self
+            .primary_table
+            .range::<DatabaseInnerKeyValue>((start, Bound::Unbounded))?;
+        // The limit can be much larger than the table, e.g. `usize::MAX`.
+        let mut values = Vec::with_capacity(limit.min(1024));
+        let mut last_key = None;
+        while values.len() < limit {
+            match range.next() {
+                Some(entry) => {
+                    let (key, value) = entry?;
+                    values.push(T::native_db_bincode_decode_from_slice(value.value()));
+                    last_key = Some(key.value().as_slice().to_vec());
+                }
+                None => break,
+            }
+        }
+        // Check that there is a next page without decoding its first value.
+        let next = match range.next() {
+            Some(entry) => {
+                entry?;
+                last_key
+            }
+            None => None,
+        };
+        Ok(Page { values, next })
+    }
+
     /// Iterate over all values starting with a prefix.
     ///
     /// # Example
@@ -150,6 +344,16 @@ where
     }
 }
 
+/// A page of values returned by [`PrimaryScan::page`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    /// The values of the page, in the order of the primary keys.
+    pub values: Vec<T>,
+    /// The encoded primary key of the last value, to pass as `after` to get the next page.
+    /// `None` if it is the last page.
+    pub next: Option<Vec<u8>>,
+}
+
 pub struct PrimaryScanIterator<'a, T: Input> {
     pub(crate) range: redb::Range<'a, DatabaseInnerKeyValue, &'static [u8]>,
     pub(crate) _marker: PhantomData<T>,
@@ -164,6 +368,17 @@ impl<'a, T: Input> Iterator for PrimaryScanIterator<'a, T> {
             _ => None,
         }
     }
+
+    // Skip the values without decoding them, e.g. for `skip`.
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        for _ in 0..n {
+            match self.range.next() {
+                Some(Ok(_)) => {}
+                _ => return None,
+            }
+        }
+        self.next()
+    }
 }
 impl<'a, T: Input> DoubleEndedIterator for PrimaryScanIterator<'a, T> {
     fn next_back(&mut self) -> Option<Self::Item> {
@@ -174,6 +389,86 @@ impl<'a, T: Input> DoubleEndedIterator for PrimaryScanIterator<'a, T> {
     }
 }
 
+pub struct PrimaryScanBufferedIterator<'a, T: Input> {
+    pub(crate) range: redb::Range<'a, DatabaseInnerKeyValue, &'static [u8]>,
+    pub(crate) buffer: usize,
+    // The decoded values of the current batch.
+    pub(crate) values: std::vec::IntoIter<T>,
+    // The decoding of the next batch, `None` at the end of the range.
+    pub(crate) decoding: Option<JoinHandle<Vec<T>>>,
+    // `true` once the range is exhausted or has failed, the failed entry can't be skipped.
+    pub(crate) done: bool,
+}
+
+impl<'a, T: Input + Send + 'static> PrimaryScanBufferedIterator<'a, T> {
+    /// Read the next batch and decode it in another thread.
+    fn read_ahead(&mut self) {
+        // The buffer can be much larger than the table, e.g. `usize::MAX`.
+        let mut encoded = Vec::with_capacity(self.buffer.min(1024));
+        while !self.done && encoded.len() < self.buffer {
+            match self.range.next() {
+                Some(Ok((_, v))) => encoded.push(v.value().to_vec()),
+                Some(Err(_)) | None => self.done = true,
+            }
+        }
+        self.decoding = if encoded.is_empty() {
+            None
+        } else {
+            Some(thread::spawn(move || {
+                encoded
+                    .iter()
+                    .map(|value| T::native_db_bincode_decode_from_slice(value.as_slice()))
+                    .collect()
+            }))
+        };
+    }
+}
+
+impl<'a, T: Input + Send + 'static> Iterator for PrimaryScanBufferedIterator<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(value) = self.values.next() {
+            return Some(value);
+        }
+        if self.decoding.is_none() {
+            self.read_ahead();
+        }
+        let decoding = self.decoding.take()?;
+        self.read_ahead();
+        // A value that can't be decoded panics, as with the other iterators.
+        let values = decoding
+            .join()
+            .unwrap_or_else(|err| panic::resume_unwind(err));
+        self.values = values.into_iter();
+        self.values.next()
+    }
+}
+
+pub struct PrimaryScanKeysIterator<'a> {
+    pub(crate) range: redb::Range<'a, DatabaseInnerKeyValue, &'static [u8]>,
+}
+
+impl<'a> Iterator for PrimaryScanKeysIterator<'a> {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.range.next() {
+            Some(Ok((k, _))) => Some(k.value().as_slice().to_vec()),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> DoubleEndedIterator for PrimaryScanKeysIterator<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self.range.next_back() {
+            Some(Ok((k, _))) => Some(k.value().as_slice().to_vec()),
+            _ => None,
+        }
+    }
+}
+
 pub struct PrimaryScanIteratorStartWith<'a, T: Input> {
     pub(crate) range: redb::Range<'a, DatabaseInnerKeyValue, &'static [u8]>,
     pub(crate) start_with: DatabaseInnerKeyValue,
@@ -0,0 +1,62 @@
+use crate::db_type::{DatabaseInnerKeyValue, Result};
+use crate::table_definition::JsonDecoder;
+
+/// Scan the values of a model as JSON, see [`RScan::primary_json`](crate::transaction::query::RScan::primary_json).
+pub struct PrimaryScanJson<PrimaryTable>
+where
+    PrimaryTable: redb::ReadableTable<DatabaseInnerKeyValue, &'static [u8]>,
+{
+    pub(crate) primary_table: PrimaryTable,
+    pub(crate) decoder: JsonDecoder,
+}
+
+impl<PrimaryTable> PrimaryScanJson<PrimaryTable>
+where
+    PrimaryTable: redb::ReadableTable<DatabaseInnerKeyValue, &'static [u8]>,
+{
+    pub(crate) fn new(primary_table: PrimaryTable, decoder: JsonDecoder) -> Self {
+        Self {
+            primary_table,
+            decoder,
+        }
+    }
+
+    /// Iterate over all values, ordered by primary key.
+    ///
+    /// Each item is an error if the value can't be converted to JSON, e.g. a map with non-string keys.
+    pub fn all(&self) -> PrimaryScanJsonIterator<'_> {
+        let range = self
+            .primary_table
+            .range::<DatabaseInnerKeyValue>(..)
+            .unwrap();
+        PrimaryScanJsonIterator {
+            range,
+            decoder: self.decoder,
+        }
+    }
+}
+
+pub struct PrimaryScanJsonIterator<'a> {
+    pub(crate) range: redb::Range<'a, DatabaseInnerKeyValue, &'static [u8]>,
+    pub(crate) decoder: JsonDecoder,
+}
+
+impl<'a> Iterator for PrimaryScanJsonIterator<'a> {
+    type Item = Result<serde_json::Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.range.next() {
+            Some(Ok((_, v))) => Some((self.decoder)(v.value())),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> DoubleEndedIterator for PrimaryScanJsonIterator<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self.range.next_back() {
+            Some(Ok((_, v))) => Some((self.decoder)(v.value())),
+            _ => None,
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::db_type::{unwrap_item, DatabaseInnerKeyValue, DatabaseInnerKeyValueRange, Input};
 use crate::InnerKeyValue;
 use redb;
+use std::cmp::Ordering;
 use std::marker::PhantomData;
 use std::ops::RangeBounds;
 
@@ -125,6 +126,59 @@ where
         }
     }
 
+    /// Get the values with a secondary key in the `range`, sorted by `compare`.
+    ///
+    /// The secondary keys are stored in byte order: the values of the range are collected,
+    /// then sorted with a stable sort, so use it on bounded result sets. Same bounds as [`range`](Self::range).
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     #[secondary_key]
+    ///     name: String,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     // Open a read transaction
+    ///     let r = db.r_transaction()?;
+    ///
+    ///     // Get the values with a name from "file" to "filf" excluded, sorted by name length then name:
+    ///     // "file2" before "file10"
+    ///     let _values: Vec<Data> = r
+    ///         .scan()
+    ///         .secondary(DataKey::name)?
+    ///         .range_sorted_by("file".."filf", |a: &Data, b: &Data| {
+    ///             a.name.len().cmp(&b.name.len()).then_with(|| a.name.cmp(&b.name))
+    ///         });
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn range_sorted_by<TR: InnerKeyValue, R: RangeBounds<TR>, F>(
+        &self,
+        range: R,
+        compare: F,
+    ) -> Vec<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut values: Vec<T> = self.range(range).collect();
+        values.sort_by(compare);
+        values
+    }
+
     /// Iterate over all values by secondary key.
     ///
     /// Anatomy of a secondary key it is a `enum` with the following structure: `<table_name>Key::<name>`.
@@ -0,0 +1,300 @@
+use crate::db_type::{DatabaseInnerKeyValue, DatabaseInput, InnerKeyValue, Input, Result};
+use crate::transaction::internal::rw_transaction::RwModelTables;
+use crate::transaction::query::PrimaryScan;
+use crate::transaction::{RTransaction, RwTransaction};
+use crate::watch::Event;
+use std::marker::PhantomData;
+
+/// Access the values of the model `T` without repeating the type on each call.
+///
+/// Created with [`RTransaction::of()`](crate::transaction::RTransaction::of).
+///
+/// # Example
+/// ```rust
+/// use native_db::*;
+/// use native_model::{native_model, Model};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[native_model(id=1, version=1)]
+/// #[native_db]
+/// struct Data {
+///     #[primary_key]
+///     id: u64,
+/// }
+///
+/// fn main() -> Result<(), db_type::Error> {
+///     let mut builder = DatabaseBuilder::new();
+///     builder.define::<Data>()?;
+///     let db = builder.create_in_memory()?;
+///
+///     // Open a read transaction
+///     let r = db.r_transaction()?;
+///     let data = r.of::<Data>();
+///
+///     let _value: Option<Data> = data.get(1u64)?;
+///     let _values: Vec<Data> = data.scan()?.all().collect();
+///     let _len: u64 = data.len()?;
+///     Ok(())
+/// }
+/// ```
+pub struct RTypedTable<'db, 'txn, T: Input> {
+    pub(crate) txn: &'txn RTransaction<'db>,
+    pub(crate) _marker: PhantomData<T>,
+}
+
+impl<'db, 'txn, T: Input> RTypedTable<'db, 'txn, T> {
+    /// Get a value by primary key.
+    ///
+    /// Same as [`RGet::primary()`](crate::transaction::query::RGet::primary).
+    pub fn get(&self, key: impl InnerKeyValue) -> Result<Option<T>> {
+        self.txn.get().primary(key)
+    }
+
+    /// Scan the values by primary key.
+    ///
+    /// Same as [`RScan::primary()`](crate::transaction::query::RScan::primary).
+    pub fn scan(
+        &self,
+    ) -> Result<PrimaryScan<redb::ReadOnlyTable<'txn, DatabaseInnerKeyValue, &'static [u8]>, T>>
+    {
+        self.txn.scan().primary()
+    }
+
+    /// Get the number of values.
+    ///
+    /// Same as [`RLen::primary()`](crate::transaction::query::RLen::primary).
+    pub fn len(&self) -> Result<u64> {
+        self.txn.len().primary::<T>()
+    }
+
+    /// Returns `true` if there is no value.
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+}
+
+/// Access and modify the values of the model `T` without repeating the type on each call.
+///
+/// Created with [`RwTransaction::of()`](crate::transaction::RwTransaction::of).
+///
+/// # Example
+/// ```rust
+/// use native_db::*;
+/// use native_model::{native_model, Model};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, Clone)]
+/// #[native_model(id=1, version=1)]
+/// #[native_db]
+/// struct Data {
+///     #[primary_key]
+///     id: u64,
+///     name: String,
+/// }
+///
+/// fn main() -> Result<(), db_type::Error> {
+///     let mut builder = DatabaseBuilder::new();
+///     builder.define::<Data>()?;
+///     let db = builder.create_in_memory()?;
+///
+///     // Open a read-write transaction
+///     let rw = db.rw_transaction()?;
+///     let data = rw.of::<Data>();
+///
+///     let old = Data { id: 1, name: "old".to_string() };
+///     data.insert(old.clone())?;
+///     data.update(old, Data { id: 1, name: "new".to_string() })?;
+///     let value = data.get(1u64)?.unwrap();
+///     data.remove(value)?;
+///
+///     rw.commit()?;
+///     Ok(())
+/// }
+/// ```
+pub struct RwTypedTable<'db, 'txn, T: Input> {
+    pub(crate) txn: &'txn RwTransaction<'db>,
+    pub(crate) _marker: PhantomData<T>,
+}
+
+impl<'db, 'txn, T: Input> RwTypedTable<'db, 'txn, T> {
+    /// Get a value by primary key.
+    ///
+    /// Same as [`RGet::primary()`](crate::transaction::query::RGet::primary).
+    pub fn get(&self, key: impl InnerKeyValue) -> Result<Option<T>> {
+        self.txn.get().primary(key)
+    }
+
+    /// Scan the values by primary key.
+    ///
+    /// Same as [`RScan::primary()`](crate::transaction::query::RScan::primary).
+    pub fn scan(
+        &self,
+    ) -> Result<PrimaryScan<redb::Table<'db, 'txn, DatabaseInnerKeyValue, &'static [u8]>, T>> {
+        self.txn.scan().primary()
+    }
+
+    /// Get the number of values.
+    ///
+    /// Same as [`RLen::primary()`](crate::transaction::query::RLen::primary).
+    pub fn len(&self) -> Result<u64> {
+        self.txn.len().primary::<T>()
+    }
+
+    /// Returns `true` if there is no value.
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Insert a value.
+    ///
+    /// Same as [`RwTransaction::insert()`](crate::transaction::RwTransaction::insert).
+    pub fn insert(&self, item: T) -> Result<DatabaseInnerKeyValue> {
+        self.txn.insert(item)
+    }
+
+    /// Update a value.
+    ///
+    /// Same as [`RwTransaction::update()`](crate::transaction::RwTransaction::update).
+    pub fn update(&self, old_item: T, updated_item: T) -> Result<bool> {
+        self.txn.update(old_item, updated_item)
+    }
+
+    /// Update a value, even if it is unchanged.
+    ///
+    /// Same as [`RwTransaction::force_update()`](crate::transaction::RwTransaction::force_update).
+    pub fn force_update(&self, old_item: T, updated_item: T) -> Result<()> {
+        self.txn.force_update(old_item, updated_item)
+    }
+
+    /// Remove a value.
+    ///
+    /// Same as [`RwTransaction::remove()`](crate::transaction::RwTransaction::remove).
+    pub fn remove(&self, item: T) -> Result<T> {
+        self.txn.remove(item)
+    }
+}
+
+/// The tables of the model `T`, opened once for many operations.
+///
+/// Created with [`RwTransaction::tables_for()`](crate::transaction::RwTransaction::tables_for).
+/// [`RwTransaction`](crate::transaction::RwTransaction) opens the tables of the model on each operation,
+/// `RwOpenedTable` keeps the primary and secondary tables open until it is dropped. Use it when a
+/// transaction does many operations on the same model.
+///
+/// The tables of several models can be opened at the same time, but while `RwOpenedTable<T>` is alive
+/// the other operations of the transaction on the model `T` fail because its tables are already open.
+///
+/// # Example
+/// ```rust
+/// use native_db::*;
+/// use native_model::{native_model, Model};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[native_model(id=1, version=1)]
+/// #[native_db]
+/// struct A {
+///     #[primary_key]
+///     id: u64,
+/// }
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[native_model(id=2, version=1)]
+/// #[native_db]
+/// struct B {
+///     #[primary_key]
+///     id: u64,
+/// }
+///
+/// fn main() -> Result<(), db_type::Error> {
+///     let mut builder = DatabaseBuilder::new();
+///     builder.define::<A>()?;
+///     builder.define::<B>()?;
+///     let db = builder.create_in_memory()?;
+///
+///     // Open a read-write transaction
+///     let rw = db.rw_transaction()?;
+///     {
+///         let mut table_a = rw.tables_for::<A>()?;
+///         let mut table_b = rw.tables_for::<B>()?;
+///         for id in 0..1000 {
+///             table_a.insert(A { id })?;
+///             table_b.insert(B { id })?;
+///         }
+///     }
+///     rw.commit()?;
+///     Ok(())
+/// }
+/// ```
+pub struct RwOpenedTable<'db, 'txn, T: Input> {
+    pub(crate) txn: &'txn RwTransaction<'db>,
+    pub(crate) tables: RwModelTables<'db, 'txn>,
+    pub(crate) _marker: PhantomData<T>,
+}
+
+impl<'db, 'txn, T: Input> RwOpenedTable<'db, 'txn, T> {
+    /// Get a value by primary key.
+    ///
+    /// Same as [`RGet::primary()`](crate::transaction::query::RGet::primary).
+    pub fn get(&self, key: impl InnerKeyValue) -> Result<Option<T>> {
+        let result = self.tables.get(&key.database_inner_key_value())?;
+        Ok(result.map(|value| value.inner()))
+    }
+
+    /// Insert a value.
+    ///
+    /// Same as [`RwTransaction::insert()`](crate::transaction::RwTransaction::insert).
+    pub fn insert(&mut self, item: T) -> Result<DatabaseInnerKeyValue> {
+        let (watcher_request, binary_value) = self.tables.insert(item.to_item())?;
+        let primary_key = watcher_request.primary_key.clone();
+        let event = Event::new_insert(binary_value);
+        self.txn.batch.borrow_mut().add(watcher_request, event);
+        Ok(primary_key)
+    }
+
+    /// Update a value.
+    ///
+    /// Same as [`RwTransaction::update()`](crate::transaction::RwTransaction::update).
+    pub fn update(&mut self, old_item: T, updated_item: T) -> Result<bool> {
+        let old_item = old_item.to_item();
+        let updated_item = updated_item.to_item();
+        if old_item.value == updated_item.value {
+            let stored = self.tables.get(&updated_item.primary_key)?;
+            if matches!(stored, Some(stored) if stored.0 == updated_item.value) {
+                return Ok(false);
+            }
+        }
+        self.concrete_force_update(old_item, updated_item)?;
+        Ok(true)
+    }
+
+    /// Update a value, even if it is unchanged.
+    ///
+    /// Same as [`RwTransaction::force_update()`](crate::transaction::RwTransaction::force_update).
+    pub fn force_update(&mut self, old_item: T, updated_item: T) -> Result<()> {
+        self.concrete_force_update(old_item.to_item(), updated_item.to_item())
+    }
+
+    fn concrete_force_update(
+        &mut self,
+        old_item: DatabaseInput,
+        updated_item: DatabaseInput,
+    ) -> Result<()> {
+        let (watcher_request, old_binary_value, new_binary_value) =
+            self.tables.update(old_item, updated_item)?;
+        let event = Event::new_update(old_binary_value, new_binary_value);
+        self.txn.batch.borrow_mut().add(watcher_request, event);
+        Ok(())
+    }
+
+    /// Remove a value.
+    ///
+    /// Same as [`RwTransaction::remove()`](crate::transaction::RwTransaction::remove).
+    pub fn remove(&mut self, item: T) -> Result<T> {
+        let (watcher_request, binary_value) = self.tables.remove(item.to_item())?;
+        let event = Event::new_delete(watcher_request.primary_key.clone(), binary_value.clone());
+        self.txn.batch.borrow_mut().add(watcher_request, event);
+        Ok(binary_value.inner())
+    }
+}
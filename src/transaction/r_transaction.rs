@@ -1,8 +1,18 @@
+use crate::db_type::Input;
 use crate::transaction::internal::r_transaction::InternalRTransaction;
 use crate::transaction::query::RGet;
 use crate::transaction::query::RLen;
 use crate::transaction::query::RScan;
+use crate::transaction::query::RTypedTable;
+use std::marker::PhantomData;
 
+/// A read-only transaction, created by [`Database::r_transaction`](crate::Database::r_transaction).
+///
+/// The transaction reads a snapshot of the database: the values written by the commits done after it
+/// started are not visible. While the transaction is open, redb can't reuse the pages of the values
+/// removed or updated after the snapshot, so the file grows with the writes until it is closed.
+/// Don't keep a read transaction open indefinitely, e.g. in a long-running report: open a new one for
+/// each batch of reads and [`close`](Self::close) it, or drop it, as soon as possible.
 pub struct RTransaction<'db> {
     pub(crate) internal: InternalRTransaction<'db>,
 }
@@ -28,4 +38,46 @@ impl<'db> RTransaction<'db> {
             internal: &self.internal,
         }
     }
+
+    /// Access the values of the model `T`, see [`RTypedTable`](crate::transaction::query::RTypedTable).
+    pub fn of<'txn, T: Input>(&'txn self) -> RTypedTable<'db, 'txn, T> {
+        RTypedTable {
+            txn: self,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Close the transaction and release its snapshot, so redb can reuse the pages freed since it started.
+    ///
+    /// Same as dropping the transaction, but explicit.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let r = db.r_transaction()?;
+    ///     let count = r.len().primary::<Data>()?;
+    ///     r.close();
+    ///     assert_eq!(count, 0);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn close(self) {
+        drop(self);
+    }
 }
@@ -0,0 +1,158 @@
+use crate::db_type::{DatabaseSecondaryKeyOptions, InnerKeyValue, Input, KeyDefinition, Result};
+use crate::transaction::query::{RwGet, RwLen, RwScan};
+use crate::transaction::{RTransaction, RwTransaction};
+
+/// The read operations shared by [`RTransaction`], [`RwTransaction`] and [`RwReadOnlyTransaction`].
+///
+/// Use it to write functions that only read the database and accept any transaction,
+/// e.g. a function called before and after writes in the same read-write transaction.
+///
+/// # Example
+/// ```rust
+/// use native_db::*;
+/// use native_db::transaction::ReadableTransaction;
+/// use native_model::{native_model, Model};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[native_model(id=1, version=1)]
+/// #[native_db]
+/// struct Data {
+///     #[primary_key]
+///     id: u64,
+/// }
+///
+/// fn count(txn: &impl ReadableTransaction) -> Result<u64, db_type::Error> {
+///     txn.len_primary::<Data>()
+/// }
+///
+/// fn main() -> Result<(), db_type::Error> {
+///     let mut builder = DatabaseBuilder::new();
+///     builder.define::<Data>()?;
+///     let db = builder.create_in_memory()?;
+///
+///     let rw = db.rw_transaction()?;
+///     rw.insert(Data { id: 1 })?;
+///     assert_eq!(count(&rw.as_read_only())?, 1);
+///     rw.commit()?;
+///
+///     // Open a read transaction
+///     let r = db.r_transaction()?;
+///     assert_eq!(count(&r)?, 1);
+///     Ok(())
+/// }
+/// ```
+pub trait ReadableTransaction {
+    /// Get a value by its primary key, see [`RGet::primary()`](crate::transaction::query::RGet::primary).
+    fn get_primary<T: Input>(&self, key: impl InnerKeyValue) -> Result<Option<T>>;
+
+    /// Get a value by its secondary key, see [`RGet::secondary()`](crate::transaction::query::RGet::secondary).
+    fn get_secondary<T: Input>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        key: impl InnerKeyValue,
+    ) -> Result<Option<T>>;
+
+    /// Get all the values of the model `T`, ordered by primary key.
+    fn scan_primary_all<T: Input>(&self) -> Result<Vec<T>>;
+
+    /// Get the number of values of the model `T`, see [`RLen::primary()`](crate::transaction::query::RLen::primary).
+    fn len_primary<T: Input>(&self) -> Result<u64>;
+}
+
+impl ReadableTransaction for RTransaction<'_> {
+    fn get_primary<T: Input>(&self, key: impl InnerKeyValue) -> Result<Option<T>> {
+        self.get().primary(key)
+    }
+
+    fn get_secondary<T: Input>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        key: impl InnerKeyValue,
+    ) -> Result<Option<T>> {
+        self.get().secondary(key_def, key)
+    }
+
+    fn scan_primary_all<T: Input>(&self) -> Result<Vec<T>> {
+        Ok(self.scan().primary::<T>()?.all().collect())
+    }
+
+    fn len_primary<T: Input>(&self) -> Result<u64> {
+        self.len().primary::<T>()
+    }
+}
+
+impl ReadableTransaction for RwTransaction<'_> {
+    fn get_primary<T: Input>(&self, key: impl InnerKeyValue) -> Result<Option<T>> {
+        self.get().primary(key)
+    }
+
+    fn get_secondary<T: Input>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        key: impl InnerKeyValue,
+    ) -> Result<Option<T>> {
+        self.get().secondary(key_def, key)
+    }
+
+    fn scan_primary_all<T: Input>(&self) -> Result<Vec<T>> {
+        Ok(self.scan().primary::<T>()?.all().collect())
+    }
+
+    fn len_primary<T: Input>(&self) -> Result<u64> {
+        self.len().primary::<T>()
+    }
+}
+
+/// A read-only view of a [`RwTransaction`], created with [`RwTransaction::as_read_only()`].
+///
+/// The reads see the writes already done in the read-write transaction, see [`RwTransaction`].
+/// The view can't write: pass it to the code that must only read.
+pub struct RwReadOnlyTransaction<'db, 'txn> {
+    pub(crate) txn: &'txn RwTransaction<'db>,
+}
+
+impl<'db, 'txn> RwReadOnlyTransaction<'db, 'txn> {
+    /// Get a value from the database.
+    ///
+    /// Same as [`RTransaction::get()`](struct.RTransaction.html#method.get).
+    pub fn get(&self) -> RwGet<'db, 'txn> {
+        self.txn.get()
+    }
+
+    /// Get values from the database.
+    ///
+    /// Same as [`RTransaction::scan()`](struct.RTransaction.html#method.scan).
+    pub fn scan(&self) -> RwScan<'db, 'txn> {
+        self.txn.scan()
+    }
+
+    /// Get the number of values in the database.
+    ///
+    /// Same as [`RTransaction::len()`](struct.RTransaction.html#method.len).
+    pub fn len(&self) -> RwLen<'db, 'txn> {
+        self.txn.len()
+    }
+}
+
+impl ReadableTransaction for RwReadOnlyTransaction<'_, '_> {
+    fn get_primary<T: Input>(&self, key: impl InnerKeyValue) -> Result<Option<T>> {
+        self.txn.get_primary(key)
+    }
+
+    fn get_secondary<T: Input>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        key: impl InnerKeyValue,
+    ) -> Result<Option<T>> {
+        self.txn.get_secondary(key_def, key)
+    }
+
+    fn scan_primary_all<T: Input>(&self) -> Result<Vec<T>> {
+        self.txn.scan_primary_all()
+    }
+
+    fn len_primary<T: Input>(&self) -> Result<u64> {
+        self.txn.len_primary::<T>()
+    }
+}
@@ -1,19 +1,115 @@
-use crate::db_type::{Input, Result};
+use crate::audit::AuditLog;
+use crate::database_builder::SlowCommit;
+use crate::db_type::{
+    DatabaseInnerKeyValue, DatabaseInnerKeyValueRange, DatabaseInput, DatabaseOutputValue,
+    DatabaseSecondaryKeyOptions, Error, InnerKeyValue, Input, KeyDefinition, Result,
+};
+use crate::stats::CommitMetrics;
+use crate::transaction::internal::private_readable_transaction::PrivateReadableTransaction;
 use crate::transaction::internal::rw_transaction::InternalRwTransaction;
+use crate::transaction::query::EditGuard;
 use crate::transaction::query::RwDrain;
 use crate::transaction::query::RwGet;
 use crate::transaction::query::RwLen;
+use crate::transaction::query::RwOpenedTable;
 use crate::transaction::query::RwScan;
+use crate::transaction::query::RwTypedTable;
+use crate::transaction::query::{Entry, OccupiedEntry, VacantEntry};
+use crate::transaction::RwReadOnlyTransaction;
 use crate::watch;
-use crate::watch::Event;
-use std::cell::RefCell;
+use crate::watch::{Event, Origin};
+use crate::write_gate::WriteGateGuard;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::RangeBounds;
 use std::sync::{Arc, RwLock};
+use std::time::Instant;
 
+/// A read-write transaction.
+///
+/// The reads of the transaction ([`get`](Self::get), [`scan`](Self::scan), [`len`](Self::len))
+/// see the writes already done in the same transaction, even before [`commit`](Self::commit):
+/// an inserted value is returned, an updated value is returned with its new content and
+/// a removed value is not returned anymore. The other transactions see these writes only after the commit.
+///
+/// # Atomicity
+///
+/// A transaction can write the values of many models: all the writes are committed together or none
+/// of them. If the transaction is dropped without [`commit`](Self::commit), e.g. because a write failed,
+/// nothing is written and no event is sent to the watchers. On commit, the events of all the models
+/// are sent as a single batch, in the order of the operations.
+/// [`Database::write`](crate::Database::write) commits only if all the writes succeed.
+///
+/// ```rust
+/// use native_db::*;
+/// use native_model::{native_model, Model};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[native_model(id=1, version=1)]
+/// #[native_db]
+/// struct Order {
+///     #[primary_key]
+///     id: u64,
+///     sku: String,
+/// }
+///
+/// #[derive(Serialize, Deserialize, Clone)]
+/// #[native_model(id=2, version=1)]
+/// #[native_db]
+/// struct Stock {
+///     #[primary_key]
+///     sku: String,
+///     quantity: u32,
+/// }
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[native_model(id=3, version=1)]
+/// #[native_db]
+/// struct AuditLog {
+///     #[primary_key]
+///     id: u64,
+///     message: String,
+/// }
+///
+/// fn main() -> Result<(), db_type::Error> {
+///     let mut builder = DatabaseBuilder::new();
+///     builder.define::<Order>()?;
+///     builder.define::<Stock>()?;
+///     builder.define::<AuditLog>()?;
+///     let db = builder.create_in_memory()?;
+///
+///     db.write(|rw| {
+///         rw.insert(Stock { sku: "apple".to_string(), quantity: 10 })
+///     })?;
+///
+///     // Create the order, decrement the stock and write the audit log: all or nothing.
+///     db.write(|rw| {
+///         rw.insert(Order { id: 1, sku: "apple".to_string() })?;
+///         let stock: Stock = rw.get().primary("apple")?.unwrap();
+///         let updated = Stock { quantity: stock.quantity - 1, ..stock.clone() };
+///         rw.update(stock, updated)?;
+///         rw.insert(AuditLog { id: 1, message: "order 1".to_string() })?;
+///         Ok::<(), db_type::Error>(())
+///     })?;
+///     Ok(())
+/// }
+/// ```
 pub struct RwTransaction<'db> {
     pub(crate) watcher: &'db Arc<RwLock<watch::Watchers>>,
     pub(crate) batch: RefCell<watch::Batch>,
     pub(crate) internal: InternalRwTransaction<'db>,
+    pub(crate) slow_commit: Option<&'db SlowCommit>,
+    pub(crate) max_size_bytes: Option<u64>,
+    pub(crate) audit_log: Option<&'db AuditLog>,
+    /// See [`disable_audit_log`](Self::disable_audit_log).
+    pub(crate) audit_log_disabled: Cell<bool>,
+    /// First error of a write that couldn't be returned, see [`EditGuard`](crate::transaction::query::EditGuard).
+    pub(crate) deferred_error: RefCell<Option<Error>>,
+    // Declared last: released after the redb transaction is committed or aborted.
+    pub(crate) _write_guard: WriteGateGuard<'db>,
 }
 
 impl<'db> RwTransaction<'db> {
@@ -44,48 +140,830 @@ impl<'db> RwTransaction<'db> {
         }
     }
 
+    /// A read-only view of the transaction, see [`RwReadOnlyTransaction`](crate::transaction::RwReadOnlyTransaction)
+    /// and [`ReadableTransaction`](crate::transaction::ReadableTransaction).
+    pub fn as_read_only<'txn>(&'txn self) -> RwReadOnlyTransaction<'db, 'txn> {
+        RwReadOnlyTransaction { txn: self }
+    }
+
+    /// Open the tables of the model `T` once for many operations, see [`RwOpenedTable`](crate::transaction::query::RwOpenedTable).
+    pub fn tables_for<'txn, T: Input>(&'txn self) -> Result<RwOpenedTable<'db, 'txn, T>> {
+        let tables = self.internal.open_model_tables(&T::native_db_model())?;
+        Ok(RwOpenedTable {
+            txn: self,
+            tables,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Don't send the events of the model `T` to the watchers when the transaction is committed.
+    ///
+    /// The events of `T` already done in the transaction and the next ones are discarded. The events of the
+    /// other models are sent as usual. Use it when a transaction rewrites many values, e.g. a maintenance
+    /// migration, to avoid flooding the watchers.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     // Open a read transaction
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.suppress_notifications::<Data>();
+    ///     for id in 0..1000 {
+    ///         rw.insert(Data { id })?;
+    ///     }
+    ///     rw.commit()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn suppress_notifications<T: Input>(&self) {
+        let table_name = T::native_db_model().primary_key.unique_table_name;
+        self.batch.borrow_mut().suppress(table_name);
+    }
+
+    /// Don't write the entries of the audit log for the writes of this transaction, e.g. for a bulk load.
+    ///
+    /// See [`DatabaseBuilder::set_audit_log`](crate::DatabaseBuilder::set_audit_log). The writes already
+    /// done in the transaction are not audited either: the entries are written when the transaction is committed.
+    pub fn disable_audit_log(&self) {
+        self.audit_log_disabled.set(true);
+    }
+
+    /// Returns the next value of the sequence of the model `T` and advances it.
+    ///
+    /// Use it to generate the primary keys of the model. The sequence advances only if the
+    /// transaction is committed. See [`Database::configure_sequence`](crate::Database::configure_sequence)
+    /// to set the start and the step of the sequence.
+    pub fn next_sequence<T: Input>(&self) -> Result<u64> {
+        self.internal.concrete_next_sequence(T::native_model_id())
+    }
+
+    /// Access and modify the values of the model `T`, see [`RwTypedTable`](crate::transaction::query::RwTypedTable).
+    pub fn of<'txn, T: Input>(&'txn self) -> RwTypedTable<'db, 'txn, T> {
+        RwTypedTable {
+            txn: self,
+            _marker: PhantomData,
+        }
+    }
+
     /// Get all values from the database.
     ///
-    /// Same as [`RTransaction::drain()`](struct.RTransaction.html#method.drain).
-    pub fn drain<'txn>(&'txn self) -> RwDrain<'db, 'txn> {
-        RwDrain {
-            internal: &self.internal,
+    /// Same as [`RTransaction::drain()`](struct.RTransaction.html#method.drain).
+    pub fn drain<'txn>(&'txn self) -> RwDrain<'db, 'txn> {
+        RwDrain {
+            internal: &self.internal,
+        }
+    }
+}
+
+impl<'db, 'txn> RwTransaction<'db> {
+    /// Commit the transaction.
+    /// All changes will be applied to the database. If the commit fails, the transaction will be aborted. The
+    /// database will be unchanged.
+    ///
+    /// Returns the error of an [`EditGuard`](crate::transaction::query::EditGuard) that failed to write its
+    /// value back when it was dropped, without committing.
+    ///
+    /// The events are sent to the watchers only after the redb commit succeeds, when the changes are
+    /// visible to the next read transactions. If the redb commit fails, the events are discarded:
+    /// the watchers never receive the changes that were not persisted.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     let db = builder.create_in_memory()?;
+    ///     
+    ///     // Open a read transaction
+    ///     let rw = db.rw_transaction()?;
+    ///     // Do some stuff..
+    ///     rw.commit()?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn commit(self) -> Result<()> {
+        self.commit_inner(false)?;
+        Ok(())
+    }
+
+    /// Same as [`commit`](Self::commit), and returns the [`CommitMetrics`](crate::CommitMetrics) of the commit.
+    ///
+    /// Use it to tune the number of writes per transaction, e.g. the chunk size of
+    /// [`insert_many`](Self::insert_many): compare the duration and the growth of the allocated pages
+    /// between commits of different sizes. The storage usage is read from redb before the commit,
+    /// which is as costly as [`Database::redb_database_stats`](crate::Database::redb_database_stats):
+    /// use [`commit`](Self::commit) when the metrics are not needed.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Data { id: 1 })?;
+    ///     rw.insert(Data { id: 2 })?;
+    ///     let metrics = rw.commit_with_metrics()?;
+    ///     assert_eq!(metrics.writes, 2);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn commit_with_metrics(self) -> Result<CommitMetrics> {
+        self.commit_inner(true)
+    }
+
+    fn commit_inner(self, with_stats: bool) -> Result<CommitMetrics> {
+        if let Some(error) = self.deferred_error.take() {
+            return Err(error);
+        }
+        if let Some(audit_log) = self.audit_log {
+            if !self.audit_log_disabled.get() {
+                audit_log.write_batch(&self.internal, &self.batch.borrow())?;
+            }
+        }
+        self.internal.record_versions()?;
+        let stats = if with_stats || self.max_size_bytes.is_some() {
+            Some(self.internal.stats()?)
+        } else {
+            None
+        };
+        if let (Some(max_size), Some(stats)) = (self.max_size_bytes, &stats) {
+            // On failure, the transaction is dropped and aborted.
+            let size = stats.allocated_pages * stats.page_size as u64;
+            if size > max_size {
+                return Err(Error::DatabaseFull { size, max_size });
+            }
+        }
+        // A bounded watcher that can't lose events fails the commit before anything is written.
+        watch::check_fail_commit(&self.watcher, &self.batch.borrow())?;
+        let start = Instant::now();
+        // On failure, the batch is dropped with the transaction: no event is sent.
+        self.internal.commit()?;
+        // Send batch to watchers after commit succeeds
+        let batch = self.batch.into_inner();
+        let events = batch.notified_len();
+        let result = watch::push_batch(Arc::clone(&self.watcher), batch);
+        let duration = start.elapsed();
+        if let Some(slow_commit) = self.slow_commit {
+            slow_commit.check(duration, events);
+        }
+        result?;
+        Ok(CommitMetrics {
+            duration,
+            writes: events,
+            allocated_pages: stats.as_ref().map_or(0, |stats| stats.allocated_pages),
+            page_size: stats.as_ref().map_or(0, |stats| stats.page_size),
+        })
+    }
+}
+
+impl<'db, 'txn> RwTransaction<'db> {
+    /// Insert a value into the database.
+    ///
+    /// Returns the primary key of the inserted value, as written in the database.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///     
+    ///     // Open a read transaction
+    ///     let rw = db.rw_transaction()?;
+    ///
+    ///     // Insert a value
+    ///     let key = rw.insert(Data { id: 1 })?;
+    ///     assert_eq!(key, 1u64.database_inner_key_value());
+    ///
+    ///     // /!\ Don't forget to commit the transaction
+    ///     rw.commit()?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn insert<T: Input>(&self, item: T) -> Result<DatabaseInnerKeyValue> {
+        let (watcher_request, binary_value) = self
+            .internal
+            .concrete_insert(T::native_db_model(), item.to_item())?;
+        let primary_key = watcher_request.primary_key.clone();
+        let event = Event::new_insert(binary_value);
+        self.batch.borrow_mut().add(watcher_request, event);
+        Ok(primary_key)
+    }
+
+    /// Insert many values of the model `T`.
+    ///
+    /// Same as [`insert`](Self::insert) for each value, but the tables of the model are opened only once.
+    /// The watchers receive the `Insert` events in the order of `items`. Returns the number of inserted values.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     // Open a read transaction
+    ///     let rw = db.rw_transaction()?;
+    ///
+    ///     // Insert many values
+    ///     let count = rw.insert_many((0..100).map(|id| Data { id }))?;
+    ///     assert_eq!(count, 100);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn insert_many<T: Input>(&self, items: impl IntoIterator<Item = T>) -> Result<u64> {
+        let mut tables = self.tables_for::<T>()?;
+        let mut count = 0;
+        for item in items {
+            tables.insert(item)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Replace all the values of the model `T` by `items`.
+    ///
+    /// The values of the table are removed and `items` are inserted in this transaction: the read transactions
+    /// see either the old values, before the commit, or the new values, after the commit, never a mix.
+    /// The old values are removed before the new ones are inserted, so a new value can take the unique
+    /// secondary key of an old value.
+    ///
+    /// The watchers receive the changes by primary key: an `Update` event for a key with an old and a new value,
+    /// if the value changed, an `Insert` event for a new key, in the order of `items`, then a `Delete` event for
+    /// each old key without new value. Returns the number of inserted values.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert_many((0..10).map(|id| Data { id }))?;
+    ///     rw.commit()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.replace_table((5..8).map(|id| Data { id }))?;
+    ///     rw.commit()?;
+    ///
+    ///     let r = db.r_transaction()?;
+    ///     assert_eq!(r.len().primary::<Data>()?, 3);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn replace_table<T: Input>(&self, items: impl IntoIterator<Item = T>) -> Result<u64> {
+        let model = T::native_db_model();
+        // Collect the values before removing them, the table can't be iterated while it is modified.
+        let old_values = self.internal.concrete_primary_range_values(
+            &model,
+            DatabaseInnerKeyValueRange::new::<DatabaseInnerKeyValue>(..),
+        )?;
+        let mut tables = self.internal.open_model_tables(&model)?;
+        let mut removed_keys = Vec::with_capacity(old_values.len());
+        let mut removed = HashMap::with_capacity(old_values.len());
+        for old_value in old_values {
+            let (watcher_request, old_value) = tables.remove(old_value.inner::<T>().to_item())?;
+            removed_keys.push(watcher_request.primary_key.clone());
+            removed.insert(
+                watcher_request.primary_key.clone(),
+                (watcher_request, old_value),
+            );
+        }
+
+        let mut batch = self.batch.borrow_mut();
+        let mut count = 0;
+        for item in items {
+            let (watcher_request, new_value) = tables.insert(item.to_item())?;
+            match removed.remove(&watcher_request.primary_key) {
+                Some((_, old_value)) if old_value.0 == new_value.0 => {}
+                Some((_, old_value)) => {
+                    batch.add(watcher_request, Event::new_update(old_value, new_value));
+                }
+                None => batch.add(watcher_request, Event::new_insert(new_value)),
+            }
+            count += 1;
+        }
+        for key in removed_keys {
+            if let Some((watcher_request, old_value)) = removed.remove(&key) {
+                batch.add(watcher_request, Event::new_delete(key, old_value));
+            }
+        }
+        Ok(count)
+    }
+
+    /// Insert the bytes `value` with the key `key` in the raw table `name`, or replace the value of `key`.
+    ///
+    /// The value is stored as is, without serialization. Returns the replaced value, if any.
+    /// The watchers receive an `Insert` event, or an `Update` event if a value is replaced:
+    /// use [`Insert::inner_raw`](crate::watch::Insert::inner_raw) to read the bytes.
+    ///
+    /// Returns the error [`TableDefinitionNotFound`](crate::db_type::Error::TableDefinitionNotFound)
+    /// if the raw table is not defined with [`DatabaseBuilder::define_raw`](crate::DatabaseBuilder::define_raw).
+    pub fn insert_raw(
+        &self,
+        name: &str,
+        key: impl InnerKeyValue,
+        value: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        let (watcher_request, old_value) =
+            self.internal
+                .concrete_insert_raw(name, key.database_inner_key_value(), value)?;
+        let new_value = DatabaseOutputValue(value.to_vec());
+        let event = match &old_value {
+            Some(old_value) => Event::new_update(old_value.clone(), new_value),
+            None => Event::new_insert(new_value),
+        };
+        self.batch.borrow_mut().add(watcher_request, event);
+        Ok(old_value.map(|old_value| old_value.0))
+    }
+
+    /// Remove the value of the key `key` from the raw table `name`.
+    ///
+    /// Returns the removed value, if any. The watchers receive a `Delete` event if a value is removed.
+    pub fn remove_raw(&self, name: &str, key: impl InnerKeyValue) -> Result<Option<Vec<u8>>> {
+        let (watcher_request, old_value) = self
+            .internal
+            .concrete_remove_raw(name, key.database_inner_key_value())?;
+        if let Some(old_value) = &old_value {
+            let event = Event::new_delete(watcher_request.primary_key.clone(), old_value.clone());
+            self.batch.borrow_mut().add(watcher_request, event);
+        }
+        Ok(old_value.map(|old_value| old_value.0))
+    }
+
+    /// Remove a value from the database.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///     
+    ///     // Open a read transaction
+    ///     let rw = db.rw_transaction()?;
+    ///
+    ///     // Remove a value
+    ///     let old_value = rw.remove(Data { id: 1 })?;
+    ///
+    ///     // /!\ Don't forget to commit the transaction
+    ///     rw.commit()?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn remove<T: Input>(&self, item: T) -> Result<T> {
+        let (watcher_request, binary_value) = self
+            .internal
+            .concrete_remove(T::native_db_model(), item.to_item())?;
+        let event = Event::new_delete(watcher_request.primary_key.clone(), binary_value.clone());
+        self.batch.borrow_mut().add(watcher_request, event);
+        Ok(binary_value.inner())
+    }
+
+    /// Remove the value of the model `T` with the primary key `key`, without the whole value.
+    ///
+    /// The value is read first to find its secondary keys: it costs one extra read compared to
+    /// [`remove`](Self::remove). The value is removed with its secondary keys and a `Delete` event is sent
+    /// to the watchers, like [`remove`](Self::remove). Returns the removed value, or `None` if there is
+    /// no value with the primary key `key`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     #[secondary_key]
+    ///     name: String,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Data { id: 1, name: "a".to_string() })?;
+    ///
+    ///     // Remove a value by its primary key
+    ///     let old_value: Option<Data> = rw.remove_by_key(1u64)?;
+    ///     assert_eq!(old_value.unwrap().name, "a");
+    ///
+    ///     // /!\ Don't forget to commit the transaction
+    ///     rw.commit()?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn remove_by_key<T: Input>(&self, key: impl InnerKeyValue) -> Result<Option<T>> {
+        match self.get().primary::<T>(key)? {
+            Some(item) => Ok(Some(self.remove(item)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Remove all the values of the model `T` with a primary key in the `range`.
+    ///
+    /// Each value is removed with its secondary keys and a `Delete` event is sent to the watchers,
+    /// like [`remove`](Self::remove). Returns the number of removed values.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     timestamp: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///     
+    ///     // Open a read transaction
+    ///     let rw = db.rw_transaction()?;
+    ///
+    ///     // Remove all the values before a cutoff
+    ///     let _removed: u64 = rw.remove_range::<Data, _, _>(..1_700_000_000u64)?;
+    ///
+    ///     // /!\ Don't forget to commit the transaction
+    ///     rw.commit()?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn remove_range<T: Input, TR: InnerKeyValue, R: RangeBounds<TR>>(
+        &self,
+        range: R,
+    ) -> Result<u64> {
+        let model = T::native_db_model();
+        // Collect the values before removing them, the range can't be iterated while the table is modified.
+        let values = self
+            .internal
+            .concrete_primary_range_values(&model, DatabaseInnerKeyValueRange::new(range))?;
+        let mut count = 0;
+        for value in values {
+            self.remove(value.inner::<T>())?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Remove all the values of the model `T` with a secondary key `key_def` in the `range`,
+    /// e.g. the values created before a cutoff for a retention policy.
+    ///
+    /// Same bounds as [`SecondaryScan::range`](crate::transaction::query::SecondaryScan::range). Each value
+    /// is removed with all its secondary keys and a `Delete` event is sent to the watchers, like
+    /// [`remove`](Self::remove). Returns the number of removed values.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Order {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     #[secondary_key]
+    ///     created_at: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Order>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Order { id: 1, created_at: 100 })?;
+    ///     rw.insert(Order { id: 2, created_at: 200 })?;
+    ///
+    ///     // Remove all the orders created before a cutoff
+    ///     let removed = rw.remove_secondary_range::<Order, _, _>(OrderKey::created_at, ..150u64)?;
+    ///     assert_eq!(removed, 1);
+    ///
+    ///     // /!\ Don't forget to commit the transaction
+    ///     rw.commit()?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn remove_secondary_range<T: Input, TR: InnerKeyValue, R: RangeBounds<TR>>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        range: R,
+    ) -> Result<u64> {
+        // Collect the values before removing them, the tables can't be modified while they are scanned.
+        let values: Vec<T> = self.scan().secondary(key_def)?.range(range).collect();
+        let mut count = 0;
+        for value in values {
+            self.remove(value)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Insert a value, or replace the value with the same primary key if it exists.
+    ///
+    /// Returns the replaced value, if any. The secondary keys of the replaced value are updated.
+    /// The watchers receive an `Insert` event if the value did not exist, an `Update` event if it was replaced,
+    /// both with the origin [`Origin::Upsert`](crate::watch::Origin::Upsert).
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     name: String,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     // Open a read transaction
+    ///     let rw = db.rw_transaction()?;
+    ///
+    ///     let old = rw.upsert(Data { id: 1, name: "a".to_string() })?;
+    ///     assert!(old.is_none());
+    ///     let old = rw.upsert(Data { id: 1, name: "b".to_string() })?;
+    ///     assert_eq!(old.unwrap().name, "a");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn upsert<T: Input>(&self, item: T) -> Result<Option<T>> {
+        let model = T::native_db_model();
+        let old_item: Option<T> = self.get().primary(item.native_db_primary_key())?;
+        if let Some(old_item) = old_item {
+            let (watcher_request, old_binary_value, new_binary_value) = self
+                .internal
+                .concrete_update(model, old_item.to_item(), item.to_item())?;
+            let event = Event::new_update(old_binary_value.clone(), new_binary_value)
+                .with_origin(Origin::Upsert);
+            self.batch.borrow_mut().add(watcher_request, event);
+            Ok(Some(old_binary_value.inner()))
+        } else {
+            let (watcher_request, binary_value) =
+                self.internal.concrete_insert(model, item.to_item())?;
+            let event = Event::new_insert(binary_value).with_origin(Origin::Upsert);
+            self.batch.borrow_mut().add(watcher_request, event);
+            Ok(None)
+        }
+    }
+
+    /// Insert `item`, or if a value with the same primary key exists, replace it by `merge(existing, item)`.
+    ///
+    /// Same as [`upsert`](Self::upsert) but the stored value combines the existing value and the incoming one,
+    /// e.g. to add counters, keep the latest value of each field or merge sets. The secondary keys are
+    /// computed from the merged value. The watchers receive an `Insert` event with `item` if the value did
+    /// not exist, an `Update` event with the merged value otherwise, both with the origin
+    /// [`Origin::Upsert`](crate::watch::Origin::Upsert). Returns the replaced value, if any.
+    ///
+    /// Returns the error [`PrimaryKeyMismatch`](crate::db_type::Error::PrimaryKeyMismatch) if the merged
+    /// value has another primary key.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Counter {
+    ///     #[primary_key]
+    ///     name: String,
+    ///     count: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Counter>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let add = |existing: &Counter, incoming: &Counter| Counter {
+    ///         name: existing.name.clone(),
+    ///         count: existing.count + incoming.count,
+    ///     };
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.upsert_with(Counter { name: "visits".to_string(), count: 1 }, add)?;
+    ///     rw.upsert_with(Counter { name: "visits".to_string(), count: 2 }, add)?;
+    ///
+    ///     let counter: Counter = rw.get().primary("visits")?.unwrap();
+    ///     assert_eq!(counter.count, 3);
+    ///     rw.commit()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn upsert_with<T: Input>(
+        &self,
+        item: T,
+        merge: impl FnOnce(&T, &T) -> T,
+    ) -> Result<Option<T>> {
+        let model = T::native_db_model();
+        let old_item: Option<T> = self.get().primary(item.native_db_primary_key())?;
+        if let Some(old_item) = old_item {
+            let merged_item = merge(&old_item, &item).to_item();
+            let old_item = old_item.to_item();
+            if merged_item.primary_key != old_item.primary_key {
+                return Err(Error::PrimaryKeyMismatch {
+                    key: old_item.primary_key.as_slice().to_vec(),
+                });
+            }
+            let (watcher_request, old_binary_value, new_binary_value) = self
+                .internal
+                .concrete_update(model, old_item, merged_item)?;
+            let event = Event::new_update(old_binary_value.clone(), new_binary_value)
+                .with_origin(Origin::Upsert);
+            self.batch.borrow_mut().add(watcher_request, event);
+            Ok(Some(old_binary_value.inner()))
+        } else {
+            let (watcher_request, binary_value) =
+                self.internal.concrete_insert(model, item.to_item())?;
+            let event = Event::new_insert(binary_value).with_origin(Origin::Upsert);
+            self.batch.borrow_mut().add(watcher_request, event);
+            Ok(None)
         }
     }
-}
 
-impl<'db, 'txn> RwTransaction<'db> {
-    /// Commit the transaction.
-    /// All changes will be applied to the database. If the commit fails, the transaction will be aborted. The
-    /// database will be unchanged.
+    /// Insert a value, or replace the value with the same primary key, only if the stored value is different.
+    ///
+    /// The encoded value is compared with the stored value: if they are identical, nothing is written and
+    /// the watchers receive no event. Use it for an idempotent ingestion, e.g. the replays of an
+    /// at-least-once message stream. Otherwise, same as [`upsert`](Self::upsert): the watchers receive an
+    /// `Insert` or an `Update` event, with the origin [`Origin::Upsert`](crate::watch::Origin::Upsert).
+    ///
+    /// Returns `true` if the value has been inserted or updated, `false` if it was unchanged.
     ///
     /// # Example
     /// ```rust
     /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     name: String,
+    /// }
     ///
     /// fn main() -> Result<(), db_type::Error> {
     ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
     ///     let db = builder.create_in_memory()?;
-    ///     
-    ///     // Open a read transaction
+    ///
     ///     let rw = db.rw_transaction()?;
-    ///     // Do some stuff..
+    ///     assert!(rw.insert_if_changed(Data { id: 1, name: "a".to_string() })?);
+    ///     // The same message is received again
+    ///     assert!(!rw.insert_if_changed(Data { id: 1, name: "a".to_string() })?);
+    ///     assert!(rw.insert_if_changed(Data { id: 1, name: "b".to_string() })?);
     ///     rw.commit()?;
-    ///
     ///     Ok(())
     /// }
     /// ```
-    pub fn commit(self) -> Result<()> {
-        self.internal.commit()?;
-        // Send batch to watchers after commit succeeds
-        let batch = self.batch.into_inner();
-        watch::push_batch(Arc::clone(&self.watcher), batch)?;
-        Ok(())
+    pub fn insert_if_changed<T: Input>(&self, item: T) -> Result<bool> {
+        let model = T::native_db_model();
+        let new_item = item.to_item();
+        let old_value = self
+            .internal
+            .get_by_primary_key(model.clone(), new_item.primary_key.clone())?;
+        if let Some(old_value) = old_value {
+            if old_value.0 == new_item.value {
+                return Ok(false);
+            }
+            let old_item = old_value.inner::<T>().to_item();
+            let (watcher_request, old_binary_value, new_binary_value) =
+                self.internal.concrete_update(model, old_item, new_item)?;
+            let event =
+                Event::new_update(old_binary_value, new_binary_value).with_origin(Origin::Upsert);
+            self.batch.borrow_mut().add(watcher_request, event);
+        } else {
+            let (watcher_request, binary_value) = self.internal.concrete_insert(model, new_item)?;
+            let event = Event::new_insert(binary_value).with_origin(Origin::Upsert);
+            self.batch.borrow_mut().add(watcher_request, event);
+        }
+        Ok(true)
     }
-}
 
-impl<'db, 'txn> RwTransaction<'db> {
-    /// Insert a value into the database.
+    /// Update a value in the database.
+    ///
+    /// That allow to update all keys (primary and secondary) of the value.
+    ///
+    /// If `old_item`, `updated_item` and the stored value are serialized to the same bytes, nothing is written
+    /// and no [`Update`](crate::watch::Event::Update) event is sent. Returns `false` in this case, `true` if the
+    /// value has been written: when `old_item` is out of date, e.g. the value was removed or modified since it was
+    /// read, `updated_item` is written even if it equals `old_item`. Use [`force_update`](Self::force_update) to
+    /// write the value anyway.
     ///
     /// # Example
     /// ```rust
@@ -109,8 +987,8 @@ impl<'db, 'txn> RwTransaction<'db> {
     ///     // Open a read transaction
     ///     let rw = db.rw_transaction()?;
     ///
-    ///     // Insert a value
-    ///     rw.insert(Data { id: 1 })?;
+    ///     // Remove a value
+    ///     rw.update(Data { id: 1 }, Data { id: 2 })?;
     ///
     ///     // /!\ Don't forget to commit the transaction
     ///     rw.commit()?;
@@ -118,16 +996,57 @@ impl<'db, 'txn> RwTransaction<'db> {
     ///     Ok(())
     /// }
     /// ```
-    pub fn insert<T: Input>(&self, item: T) -> Result<()> {
-        let (watcher_request, binary_value) = self
-            .internal
-            .concrete_insert(T::native_db_model(), item.to_item())?;
-        let event = Event::new_insert(binary_value);
+    pub fn update<T: Input>(&self, old_item: T, updated_item: T) -> Result<bool> {
+        let old_item = old_item.to_item();
+        let updated_item = updated_item.to_item();
+        if old_item.value == updated_item.value {
+            let stored = self
+                .internal
+                .get_by_primary_key(T::native_db_model(), updated_item.primary_key.clone())?;
+            if matches!(stored, Some(stored) if stored.0 == updated_item.value) {
+                return Ok(false);
+            }
+        }
+        self.concrete_force_update::<T>(old_item, updated_item)?;
+        Ok(true)
+    }
+
+    /// Update a value in the database, even if it is serialized to the same bytes as `old_item`.
+    ///
+    /// Same as [`update`](Self::update) but the value is always written and an
+    /// [`Update`](crate::watch::Event::Update) event is always sent.
+    pub fn force_update<T: Input>(&self, old_item: T, updated_item: T) -> Result<()> {
+        self.concrete_force_update::<T>(old_item.to_item(), updated_item.to_item())
+    }
+
+    pub(crate) fn concrete_force_update<T: Input>(
+        &self,
+        old_item: DatabaseInput,
+        updated_item: DatabaseInput,
+    ) -> Result<()> {
+        let (watcher_request, old_binary_value, new_binary_value) =
+            self.internal
+                .concrete_update(T::native_db_model(), old_item, updated_item)?;
+        let event = Event::new_update(old_binary_value, new_binary_value);
         self.batch.borrow_mut().add(watcher_request, event);
         Ok(())
     }
 
-    /// Remove a value from the database.
+    /// Update many values of the model `T`, given as `(old_item, updated_item)` pairs.
+    ///
+    /// Same as [`update`](Self::update) for each pair, but the tables of the model are opened only once.
+    /// The watchers receive the `Update` events in the order of `pairs`, the unchanged values are skipped.
+    ///
+    /// All the pairs are checked before anything is written, if a pair fails nothing is written and no event
+    /// of the call is sent to the watchers:
+    /// - [`KeyNotFound`](crate::db_type::Error::KeyNotFound) if there is no value with the primary key of `old_item`,
+    /// - [`StaleUpdate`](crate::db_type::Error::StaleUpdate) if the stored value is not `old_item`, e.g. it was
+    ///   modified since `old_item` was read,
+    /// - [`DuplicateKey`](crate::db_type::Error::DuplicateKey) if `updated_item` takes the unique secondary key
+    ///   of another value.
+    ///
+    /// Only a storage error during the writes can leave a part of the pairs written in the transaction:
+    /// drop the transaction without committing it in this case.
     ///
     /// # Example
     /// ```rust
@@ -135,43 +1054,216 @@ impl<'db, 'txn> RwTransaction<'db> {
     /// use native_model::{native_model, Model};
     /// use serde::{Deserialize, Serialize};
     ///
-    /// #[derive(Serialize, Deserialize)]
+    /// #[derive(Serialize, Deserialize, Clone)]
     /// #[native_model(id=1, version=1)]
     /// #[native_db]
     /// struct Data {
     ///     #[primary_key]
     ///     id: u64,
+    ///     value: u64,
     /// }
     ///
     /// fn main() -> Result<(), db_type::Error> {
     ///     let mut builder = DatabaseBuilder::new();
     ///     builder.define::<Data>()?;
     ///     let db = builder.create_in_memory()?;
-    ///     
-    ///     // Open a read transaction
+    ///
     ///     let rw = db.rw_transaction()?;
+    ///     rw.insert_many((0..100).map(|id| Data { id, value: 0 }))?;
     ///
-    ///     // Remove a value
-    ///     let old_value = rw.remove(Data { id: 1 })?;
+    ///     // Update many values
+    ///     rw.update_many((0..100).map(|id| {
+    ///         (Data { id, value: 0 }, Data { id, value: id % 2 })
+    ///     }))?;
     ///
-    ///     // /!\ Don't forget to commit the transaction
+    ///     // The value 1 is not 0 anymore: nothing is written
+    ///     let result = rw.update_many((0..2).map(|id| {
+    ///         (Data { id, value: 0 }, Data { id, value: 2 })
+    ///     }));
+    ///     assert!(matches!(result, Err(db_type::Error::StaleUpdate { .. })));
     ///     rw.commit()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn update_many<T: Input>(&self, pairs: impl IntoIterator<Item = (T, T)>) -> Result<()> {
+        let batch_len = self.batch.borrow().len();
+        let result = self.concrete_update_many::<T>(pairs);
+        if result.is_err() {
+            self.batch.borrow_mut().truncate(batch_len);
+        }
+        result
+    }
+
+    fn concrete_update_many<T: Input>(
+        &self,
+        pairs: impl IntoIterator<Item = (T, T)>,
+    ) -> Result<()> {
+        let pairs: Vec<_> = pairs
+            .into_iter()
+            .map(|(old_item, updated_item)| (old_item.to_item(), updated_item.to_item()))
+            .collect();
+        let mut tables = self.internal.open_model_tables(&T::native_db_model())?;
+        tables.check_updates(&pairs)?;
+        for (old_item, updated_item) in pairs {
+            if old_item.value == updated_item.value {
+                continue;
+            }
+            let (watcher_request, old_binary_value, new_binary_value) =
+                tables.update(old_item, updated_item)?;
+            let event = Event::new_update(old_binary_value, new_binary_value);
+            self.batch.borrow_mut().add(watcher_request, event);
+        }
+        Ok(())
+    }
+
+    /// Modify the value with the primary key `key` in place with `f`, e.g. increment a counter.
+    ///
+    /// Same as [`get`](Self::get) then [`update`](Self::update): the value is read, modified by `f`
+    /// and written back if it changed, with an [`Update`](crate::watch::Event::Update) event.
+    /// Returns the modified value, or `None` if there is no value with the primary key `key`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct PageViews {
+    ///     #[primary_key]
+    ///     page: String,
+    ///     count: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<PageViews>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(PageViews { page: "home".to_string(), count: 0 })?;
     ///
+    ///     // Increment the counter
+    ///     let views = rw.modify("home", |views: &mut PageViews| views.count += 1)?;
+    ///     assert_eq!(views.unwrap().count, 1);
+    ///     rw.commit()?;
     ///     Ok(())
     /// }
     /// ```
-    pub fn remove<T: Input>(&self, item: T) -> Result<T> {
-        let (watcher_request, binary_value) = self
-            .internal
-            .concrete_remove(T::native_db_model(), item.to_item())?;
-        let event = Event::new_delete(binary_value.clone());
-        self.batch.borrow_mut().add(watcher_request, event);
-        Ok(binary_value.inner())
+    pub fn modify<T: Input>(
+        &self,
+        key: impl InnerKeyValue,
+        f: impl FnOnce(&mut T),
+    ) -> Result<Option<T>> {
+        let mut value: T = match self.get().primary(key)? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let old_item = value.to_item();
+        f(&mut value);
+        let updated_item = value.to_item();
+        if old_item.value != updated_item.value {
+            self.concrete_force_update::<T>(old_item, updated_item)?;
+        }
+        Ok(Some(value))
     }
 
-    /// Update a value in the database.
+    /// Replace the value with the primary key `key` by `new` only if it is `expected`, and return `true` if
+    /// the swap is applied. `None` means that there is no value with the primary key `key`:
+    /// - `None` → `Some`: insert `new` if the key is absent.
+    /// - `Some` → `Some`: update the value if it is `expected`.
+    /// - `Some` → `None`: remove the value if it is `expected`.
     ///
-    /// That allow to update all keys (primary and secondary) of the value.
+    /// The values are compared by their encoded bytes. The comparison and the write are atomic: redb runs one
+    /// read-write transaction at a time and this transaction reads its own writes, so no other writer can change
+    /// the value in between. The writes are done like [`insert`](Self::insert), [`update`](Self::update) and
+    /// [`remove`](Self::remove), with their events. Nothing is written if `expected` and `new` are equal.
+    ///
+    /// Returns the error [`PrimaryKeyMismatch`](crate::db_type::Error::PrimaryKeyMismatch) if the primary key
+    /// of `expected` or `new` is not `key`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize, Clone)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Lock {
+    ///     #[primary_key]
+    ///     name: String,
+    ///     owner: String,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Lock>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let lock = Lock { name: "job".to_string(), owner: "worker_1".to_string() };
+    ///     let rw = db.rw_transaction()?;
+    ///     // Take the lock if it is free
+    ///     assert!(rw.compare_and_swap("job", None, Some(lock.clone()))?);
+    ///     let other = Lock { name: "job".to_string(), owner: "worker_2".to_string() };
+    ///     assert!(!rw.compare_and_swap("job", None, Some(other))?);
+    ///     // Release the lock
+    ///     assert!(rw.compare_and_swap("job", Some(lock), None)?);
+    ///     rw.commit()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn compare_and_swap<T: Input>(
+        &self,
+        key: impl InnerKeyValue,
+        expected: Option<T>,
+        new: Option<T>,
+    ) -> Result<bool> {
+        let key = key.database_inner_key_value();
+        let expected = expected.map(|value| value.to_item());
+        let new = new.map(|value| value.to_item());
+        if expected
+            .iter()
+            .chain(new.iter())
+            .any(|item| item.primary_key != key)
+        {
+            return Err(Error::PrimaryKeyMismatch {
+                key: key.as_slice().to_vec(),
+            });
+        }
+
+        let current = self.get().primary::<T>(key)?.map(|value| value.to_item());
+        if current.as_ref().map(|item| &item.value) != expected.as_ref().map(|item| &item.value) {
+            return Ok(false);
+        }
+        match (current, new) {
+            (None, None) => {}
+            (None, Some(new)) => {
+                let (watcher_request, binary_value) =
+                    self.internal.concrete_insert(T::native_db_model(), new)?;
+                let event = Event::new_insert(binary_value);
+                self.batch.borrow_mut().add(watcher_request, event);
+            }
+            (Some(old), Some(new)) => {
+                if old.value != new.value {
+                    self.concrete_force_update::<T>(old, new)?;
+                }
+            }
+            (Some(old), None) => {
+                let (watcher_request, binary_value) =
+                    self.internal.concrete_remove(T::native_db_model(), old)?;
+                let event = Event::new_delete(watcher_request.primary_key.clone(), binary_value);
+                self.batch.borrow_mut().add(watcher_request, event);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Get the entry of the primary key `key`, to insert or modify the value in place like
+    /// [`HashMap::entry`](std::collections::HashMap::entry), see [`Entry`](crate::transaction::query::Entry).
     ///
     /// # Example
     /// ```rust
@@ -182,37 +1274,269 @@ impl<'db, 'txn> RwTransaction<'db> {
     /// #[derive(Serialize, Deserialize)]
     /// #[native_model(id=1, version=1)]
     /// #[native_db]
-    /// struct Data {
+    /// struct PageViews {
+    ///     #[primary_key]
+    ///     page: String,
+    ///     count: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<PageViews>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     for _ in 0..2 {
+    ///         // Insert the counter if it is absent, otherwise increment it
+    ///         rw.entry::<PageViews>("home")?
+    ///             .and_modify(|views| views.count += 1)?
+    ///             .or_insert_with(|| PageViews { page: "home".to_string(), count: 1 })?;
+    ///     }
+    ///     let views: PageViews = rw.get().primary("home")?.unwrap();
+    ///     assert_eq!(views.count, 2);
+    ///     rw.commit()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn entry<T: Input>(&self, key: impl InnerKeyValue) -> Result<Entry<'db, '_, T>> {
+        let key = key.database_inner_key_value();
+        let entry = match self.get().primary(key.clone())? {
+            Some(value) => Entry::Occupied(OccupiedEntry { txn: self, value }),
+            None => Entry::Vacant(VacantEntry {
+                txn: self,
+                key,
+                _marker: PhantomData,
+            }),
+        };
+        Ok(entry)
+    }
+
+    /// Get the value with the primary key `key` behind a guard that writes it back when it is dropped,
+    /// see [`EditGuard`](crate::transaction::query::EditGuard).
+    ///
+    /// Same as [`modify`](Self::modify) when the changes don't fit in a closure.
+    /// Returns `None` if there is no value with the primary key `key`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct PageViews {
+    ///     #[primary_key]
+    ///     page: String,
+    ///     count: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<PageViews>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(PageViews { page: "home".to_string(), count: 0 })?;
+    ///
+    ///     if let Some(mut views) = rw.get_mut::<PageViews>("home")? {
+    ///         views.count += 1;
+    ///         views.save()?;
+    ///     }
+    ///     rw.commit()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn get_mut<T: Input>(
+        &self,
+        key: impl InnerKeyValue,
+    ) -> Result<Option<EditGuard<'db, '_, T>>> {
+        let value: T = match self.get().primary(key)? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        Ok(Some(EditGuard {
+            txn: self,
+            old_item: Some(value.to_item()),
+            value,
+        }))
+    }
+
+    /// Keep the first error that couldn't be returned to the caller, it is returned by [`commit`](Self::commit).
+    pub(crate) fn defer_error(&self, error: Error) {
+        let mut deferred_error = self.deferred_error.borrow_mut();
+        if deferred_error.is_none() {
+            *deferred_error = Some(error);
+        }
+    }
+
+    /// Scan all the values of the model `T` and update or remove them as they are visited, according to
+    /// the [`ScanAction`] returned by `f`. Returns the number of updated and removed values.
+    ///
+    /// A redb table can't be modified while it is iterated, so the primary keys are collected first, without
+    /// the values, then each value is read, passed to `f` and the action is applied before reading the next one.
+    /// The values are visited in the order of the primary keys, as they are when they are visited: `f` sees the
+    /// changes done on the previous values. The values inserted during the scan, e.g. by an update that changes
+    /// the primary key, are not visited.
+    ///
+    /// The actions are applied like [`update`](Self::update) and [`remove`](Self::remove): the secondary keys
+    /// are maintained and the watchers receive an `Update` or a `Delete` event per changed value.
+    /// An update that doesn't change the value is skipped. If an action fails, the error is returned and the
+    /// actions already applied stay in the transaction: drop the transaction without committing it to abort the scan.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_db::transaction::ScanAction;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Session {
     ///     #[primary_key]
     ///     id: u64,
+    ///     expires_at: u64,
+    ///     refreshed: bool,
     /// }
     ///
     /// fn main() -> Result<(), db_type::Error> {
     ///     let mut builder = DatabaseBuilder::new();
-    ///     builder.define::<Data>()?;
+    ///     builder.define::<Session>()?;
     ///     let db = builder.create_in_memory()?;
-    ///     
-    ///     // Open a read transaction
+    ///
     ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Session { id: 1, expires_at: 100, refreshed: false })?;
+    ///     rw.insert(Session { id: 2, expires_at: 200, refreshed: false })?;
     ///
-    ///     // Remove a value
-    ///     rw.update(Data { id: 1 }, Data { id: 2 })?;
+    ///     // Remove the expired sessions and refresh the others
+    ///     let changed = rw.scan_modify(|session: Session| {
+    ///         if session.expires_at < 150 {
+    ///             ScanAction::Delete
+    ///         } else {
+    ///             ScanAction::Update(Session { refreshed: true, ..session })
+    ///         }
+    ///     })?;
+    ///     assert_eq!(changed, 2);
     ///
     ///     // /!\ Don't forget to commit the transaction
     ///     rw.commit()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn scan_modify<T: Input>(&self, mut f: impl FnMut(T) -> ScanAction<T>) -> Result<u64> {
+        // Collect the primary keys only, the table can't be modified while it is iterated.
+        let keys: Vec<Vec<u8>> = self.scan().primary::<T>()?.keys().collect();
+        let mut count = 0;
+        for key in keys {
+            let value: T = match self.get().primary(DatabaseInnerKeyValue::new(key))? {
+                Some(value) => value,
+                None => continue,
+            };
+            let old_item = value.to_item();
+            match f(value) {
+                ScanAction::Keep => {}
+                ScanAction::Update(updated) => {
+                    let updated_item = updated.to_item();
+                    if old_item.value != updated_item.value {
+                        self.concrete_force_update::<T>(old_item, updated_item)?;
+                        count += 1;
+                    }
+                }
+                ScanAction::Delete => {
+                    let (watcher_request, binary_value) = self
+                        .internal
+                        .concrete_remove(T::native_db_model(), old_item)?;
+                    let event =
+                        Event::new_delete(watcher_request.primary_key.clone(), binary_value);
+                    self.batch.borrow_mut().add(watcher_request, event);
+                    count += 1;
+                }
+            }
+        }
+        Ok(count)
+    }
+
+    /// Move the value with the primary key `key` from the table of the older version `Old` to the table
+    /// of `T`, upgraded by the `from` chain of the model: the write-back of
+    /// [`get().primary_upgrade()`](crate::transaction::query::RwGet::primary_upgrade).
+    ///
+    /// Returns the value of `T` if it is already in the table of `T`. Otherwise the value of `Old` is removed,
+    /// with its secondary keys, and the upgraded value is inserted, with a `Delete` and an `Insert` events.
+    /// Returns `None` if there is no value with the primary key `key` in both tables.
+    /// `Old` is required to remove the secondary keys of the old value.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct DataV1 {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    /// #[native_model(id=1, version=2, from=DataV1)]
+    /// #[native_db]
+    /// struct DataV2 {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     name: String,
+    /// }
+    ///
+    /// impl From<DataV1> for DataV2 {
+    ///     fn from(data: DataV1) -> Self {
+    ///         DataV2 { id: data.id, name: String::new() }
+    ///     }
+    /// }
+    ///
+    /// impl From<DataV2> for DataV1 {
+    ///     fn from(data: DataV2) -> Self {
+    ///         DataV1 { id: data.id }
+    ///     }
+    /// }
     ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<DataV1>()?;
+    ///     builder.define::<DataV2>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(DataV1 { id: 1 })?;
+    ///
+    ///     // Upgrade the item on access
+    ///     let value = rw.upgrade::<DataV1, DataV2>(1u64)?;
+    ///     assert_eq!(value, Some(DataV2 { id: 1, name: String::new() }));
+    ///     assert_eq!(rw.len().primary::<DataV1>()?, 0);
+    ///     rw.commit()?;
     ///     Ok(())
     /// }
     /// ```
-    pub fn update<T: Input>(&self, old_item: T, updated_item: T) -> Result<()> {
-        let (watcher_request, old_binary_value, new_binary_value) = self.internal.concrete_update(
-            T::native_db_model(),
-            old_item.to_item(),
-            updated_item.to_item(),
-        )?;
-        let event = Event::new_update(old_binary_value, new_binary_value);
+    pub fn upgrade<Old: Input, T: Input>(&self, key: impl InnerKeyValue) -> Result<Option<T>> {
+        let key = key.database_inner_key_value();
+        if let Some(value) = self.get().primary::<T>(key.clone())? {
+            return Ok(Some(value));
+        }
+        let old: Old = match self.get().primary(key)? {
+            Some(old) => old,
+            None => return Ok(None),
+        };
+        let value = T::native_db_bincode_decode_from_slice(&old.native_db_bincode_encode_to_vec());
+        self.remove(old)?;
+        let (watcher_request, binary_value) = self
+            .internal
+            .concrete_insert(T::native_db_model(), value.to_item())?;
+        let event = Event::new_insert(binary_value);
         self.batch.borrow_mut().add(watcher_request, event);
-        Ok(())
+        Ok(Some(value))
     }
 
     /// Convert all values from the database.
@@ -360,3 +1684,14 @@ impl<'db, 'txn> RwTransaction<'db> {
         self.internal.migrate::<T>()
     }
 }
+
+/// The action applied to a value by [`RwTransaction::scan_modify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanAction<T> {
+    /// Leave the value unchanged.
+    Keep,
+    /// Replace the value, like [`RwTransaction::update`].
+    Update(T),
+    /// Remove the value, like [`RwTransaction::remove`].
+    Delete,
+}
@@ -0,0 +1,173 @@
+use crate::db_type::{DatabaseInnerKeyValue, Error, Input, Result};
+use crate::Database;
+use redb::ReadableTable;
+use std::collections::HashMap;
+
+/// Last version committed, under the key [`LAST_VERSION_KEY`].
+pub(crate) const VERSION_TABLE: redb::TableDefinition<&str, u64> =
+    redb::TableDefinition::new("native_db_version");
+
+const LAST_VERSION_KEY: &str = "last";
+
+/// Version of each row: `table name, 0, primary key -> version`.
+pub(crate) const MODIFIED_TABLE: redb::TableDefinition<&[u8], u64> =
+    redb::TableDefinition::new("native_db_modified");
+
+/// Index of the rows by version: `table name, 0, version, primary key -> ()`.
+pub(crate) const MODIFIED_INDEX_TABLE: redb::TableDefinition<&[u8], ()> =
+    redb::TableDefinition::new("native_db_modified_index");
+
+/// The rows written by a read-write transaction: `(table name, primary key) -> still exists`.
+pub(crate) type ModifiedRows = HashMap<(String, DatabaseInnerKeyValue), bool>;
+
+/// Values returned by [`Database::modified_since`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModifiedSince<T> {
+    /// The values inserted or updated after the requested version, ordered by version.
+    pub values: Vec<T>,
+    /// The last committed version, to pass to the next call of [`Database::modified_since`].
+    pub version: u64,
+}
+
+fn row_key(table_name: &str, primary_key: &[u8]) -> Vec<u8> {
+    let mut key = Vec::with_capacity(table_name.len() + 1 + primary_key.len());
+    key.extend_from_slice(table_name.as_bytes());
+    key.push(0);
+    key.extend_from_slice(primary_key);
+    key
+}
+
+fn index_key(table_name: &str, version: u64, primary_key: &[u8]) -> Vec<u8> {
+    let mut key = Vec::with_capacity(table_name.len() + 9 + primary_key.len());
+    key.extend_from_slice(table_name.as_bytes());
+    key.push(0);
+    key.extend_from_slice(&version.to_be_bytes());
+    key.extend_from_slice(primary_key);
+    key
+}
+
+fn read_last_version(table: &impl ReadableTable<&'static str, u64>) -> Result<u64> {
+    Ok(table
+        .get(LAST_VERSION_KEY)?
+        .map(|value| value.value())
+        .unwrap_or(0))
+}
+
+/// Stamps the rows written by the transaction `rw` with a new version.
+pub(crate) fn record(rw: &redb::WriteTransaction, modified: &ModifiedRows) -> Result<()> {
+    if modified.is_empty() {
+        return Ok(());
+    }
+    let mut version_table = rw.open_table(VERSION_TABLE)?;
+    let version = read_last_version(&version_table)? + 1;
+    version_table.insert(LAST_VERSION_KEY, version)?;
+
+    let mut modified_table = rw.open_table(MODIFIED_TABLE)?;
+    let mut index_table = rw.open_table(MODIFIED_INDEX_TABLE)?;
+    for ((table_name, primary_key), exists) in modified {
+        let row_key = row_key(table_name, primary_key.as_slice());
+        let old_version = modified_table
+            .remove(row_key.as_slice())?
+            .map(|value| value.value());
+        if let Some(old_version) = old_version {
+            let old_index_key = index_key(table_name, old_version, primary_key.as_slice());
+            index_table.remove(old_index_key.as_slice())?;
+        }
+        if *exists {
+            modified_table.insert(row_key.as_slice(), version)?;
+            let index_key = index_key(table_name, version, primary_key.as_slice());
+            index_table.insert(index_key.as_slice(), ())?;
+        }
+    }
+    Ok(())
+}
+
+impl Database<'_> {
+    /// Returns the values of the model `T` inserted or updated after the version `since`, and the
+    /// last committed version.
+    ///
+    /// Requires [`DatabaseBuilder::set_version_tracking`](crate::DatabaseBuilder::set_version_tracking),
+    /// otherwise returns the error [`VersionTrackingDisabled`](crate::db_type::Error::VersionTrackingDisabled).
+    /// Each commit that writes values gets the next version, starting at `1`: for an incremental sync, call
+    /// `modified_since(0)` first, then pass the returned [`version`](ModifiedSince::version) to the next call.
+    /// The values and the version are read in the same read transaction, so no write is missed.
+    ///
+    /// The removed values are not returned, use the [watchers](crate::Database::watch) or a tombstone
+    /// field to propagate the removals.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.set_version_tracking(true);
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Data { id: 1 })?;
+    ///     rw.commit()?;
+    ///     let synced = db.modified_since::<Data>(0)?;
+    ///     assert_eq!(synced.values.len(), 1);
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Data { id: 2 })?;
+    ///     rw.commit()?;
+    ///     let changes = db.modified_since::<Data>(synced.version)?;
+    ///     assert_eq!(changes.values.len(), 1);
+    ///     assert_eq!(changes.values[0].id, 2);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn modified_since<T: Input>(&self, since: u64) -> Result<ModifiedSince<T>> {
+        if !self.version_tracking {
+            return Err(Error::VersionTrackingDisabled);
+        }
+        let model = T::native_db_model();
+        let table_name = model.primary_key.unique_table_name.as_str();
+        let table_definition = self
+            .primary_table_definitions
+            .get(table_name)
+            .ok_or_else(|| Error::TableDefinitionNotFound {
+                table: table_name.to_string(),
+            })?;
+
+        let r = self.instance.begin_read()?;
+        let version = match r.open_table(VERSION_TABLE) {
+            Ok(table) => read_last_version(&table)?,
+            Err(redb::TableError::TableDoesNotExist(_)) => 0,
+            Err(err) => return Err(err.into()),
+        };
+        let mut values = vec![];
+        if since >= version {
+            return Ok(ModifiedSince { values, version });
+        }
+
+        let index_table = r.open_table(MODIFIED_INDEX_TABLE)?;
+        let primary_table = r.open_table(table_definition.redb)?;
+        let start = index_key(table_name, since + 1, &[]);
+        // The table name is followed by 0, all the keys of the table are lower than the name followed by 1.
+        let mut end = table_name.as_bytes().to_vec();
+        end.push(1);
+        let prefix_len = table_name.len() + 9;
+        for result in index_table.range(start.as_slice()..end.as_slice())? {
+            let (key, _) = result?;
+            let primary_key = DatabaseInnerKeyValue::new(key.value()[prefix_len..].to_vec());
+            if let Some(value) = primary_table.get(&primary_key)? {
+                values.push(T::native_db_bincode_decode_from_slice(value.value()));
+            }
+        }
+        Ok(ModifiedSince { values, version })
+    }
+}
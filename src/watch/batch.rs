@@ -1,16 +1,83 @@
 use crate::watch::{Event, WatcherRequest};
+use std::collections::{HashSet, VecDeque};
 use std::fmt::Debug;
 
 #[derive(Clone)]
-pub struct Batch(Vec<(WatcherRequest, Event)>);
+pub struct Batch {
+    // In the order of the operations of the transaction.
+    events: VecDeque<(WatcherRequest, Event)>,
+    // Tables whose events are not sent to the watchers.
+    suppressed_tables: HashSet<String>,
+    // Keep the events of the suppressed tables, for the audit log.
+    keep_suppressed: bool,
+}
 
 impl Batch {
     pub(crate) fn new() -> Self {
-        Self(Vec::new())
+        Self {
+            events: VecDeque::new(),
+            suppressed_tables: HashSet::new(),
+            keep_suppressed: false,
+        }
+    }
+
+    /// A batch that keeps the events of the suppressed tables: they are not sent to the watchers but
+    /// they are written to the audit log, see [`DatabaseBuilder::set_audit_log`](crate::DatabaseBuilder::set_audit_log).
+    pub(crate) fn new_audited() -> Self {
+        Self {
+            keep_suppressed: true,
+            ..Self::new()
+        }
     }
 
     pub(crate) fn add(&mut self, watcher_request: WatcherRequest, event: Event) {
-        self.0.push((watcher_request, event));
+        if !self.keep_suppressed && self.suppressed_tables.contains(&watcher_request.table_name) {
+            return;
+        }
+        self.events.push_back((watcher_request, event));
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// The number of events sent to the watchers.
+    pub(crate) fn notified_len(&self) -> usize {
+        if self.suppressed_tables.is_empty() {
+            return self.events.len();
+        }
+        self.events
+            .iter()
+            .filter(|(watcher_request, _)| {
+                !self.suppressed_tables.contains(&watcher_request.table_name)
+            })
+            .count()
+    }
+
+    /// All the events of the transaction, including the ones of the suppressed tables if they are kept.
+    pub(crate) fn events(&self) -> impl Iterator<Item = &(WatcherRequest, Event)> {
+        self.events.iter()
+    }
+
+    /// The events sent to the watchers, without the ones of the suppressed tables.
+    pub(crate) fn notified(&self) -> impl Iterator<Item = &(WatcherRequest, Event)> {
+        self.events.iter().filter(move |(watcher_request, _)| {
+            !self.suppressed_tables.contains(&watcher_request.table_name)
+        })
+    }
+
+    /// Discard the events added after the batch had `len` events.
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.events.truncate(len);
+    }
+
+    /// Discard the events of `table_name`, the ones already added and the next ones.
+    pub(crate) fn suppress(&mut self, table_name: String) {
+        if !self.keep_suppressed {
+            self.events
+                .retain(|(watcher_request, _)| watcher_request.table_name != table_name);
+        }
+        self.suppressed_tables.insert(table_name);
     }
 }
 
@@ -18,14 +85,19 @@ impl<'a> Iterator for Batch {
     type Item = (WatcherRequest, Event);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.pop()
+        loop {
+            let (watcher_request, event) = self.events.pop_front()?;
+            if !self.suppressed_tables.contains(&watcher_request.table_name) {
+                return Some((watcher_request, event));
+            }
+        }
     }
 }
 
 impl Debug for Batch {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "[")?;
-        for (watcher_request, event) in &self.0 {
+        for (watcher_request, event) in &self.events {
             write!(f, "({:?}, {:?}), ", watcher_request.primary_key, event)?;
         }
         write!(f, "]")
@@ -0,0 +1,215 @@
+use crate::watch::Event;
+use std::collections::VecDeque;
+use std::sync::mpsc::{RecvError, RecvTimeoutError, TryRecvError};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// What a commit does when the channel of a bounded watcher is full, see
+/// [`WatchGet::primary_bounded`](crate::watch::query::WatchGet::primary_bounded).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FullPolicy {
+    /// The commit waits until the receiver makes room for the event. The next read-write transactions
+    /// wait too: don't write to the database from the thread that receives the events.
+    Block,
+    /// The event is dropped, see [`BoundedReceiver::dropped`].
+    DropEvent,
+    /// The commit fails with the error [`WatcherFull`](crate::db_type::Error::WatcherFull) before anything
+    /// is written if the channel has not enough room for all the events of the transaction.
+    FailCommit,
+}
+
+struct State {
+    events: VecDeque<Event>,
+    dropped: u64,
+    receiver_alive: bool,
+    sender_alive: bool,
+}
+
+pub(crate) struct BoundedChannel {
+    state: Mutex<State>,
+    capacity: usize,
+    policy: FullPolicy,
+    /// Notified when an event is sent or the sender is dropped.
+    not_empty: Condvar,
+    /// Notified when an event is received or the receiver is dropped.
+    not_full: Condvar,
+}
+
+impl BoundedChannel {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // The queue stays valid if a thread panicked while holding the mutex.
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// The sending side of a bounded watcher, the receiver is disconnected when it is dropped.
+pub(crate) struct BoundedSender(Arc<BoundedChannel>);
+
+impl BoundedSender {
+    pub(crate) fn policy(&self) -> FullPolicy {
+        self.0.policy
+    }
+
+    /// Number of events sent and not received yet.
+    pub(crate) fn len(&self) -> usize {
+        self.0.lock().events.len()
+    }
+
+    /// Returns `true` if `count` events can be sent without reaching the capacity.
+    pub(crate) fn has_room(&self, count: usize) -> bool {
+        let state = self.0.lock();
+        !state.receiver_alive || state.events.len() + count <= self.0.capacity
+    }
+
+    /// Sends `event` according to the policy of the channel, returns it back if the receiver is dropped.
+    ///
+    /// With [`FullPolicy::FailCommit`], the room is checked before the commit: if the channel is full anyway,
+    /// e.g. the watcher was added during the commit, the event is dropped.
+    pub(crate) fn send(&self, event: Event) -> Result<(), Event> {
+        let channel = &self.0;
+        let mut state = channel.lock();
+        if channel.policy == FullPolicy::Block {
+            while state.receiver_alive && state.events.len() >= channel.capacity {
+                state = channel
+                    .not_full
+                    .wait(state)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+            }
+        }
+        if !state.receiver_alive {
+            return Err(event);
+        }
+        if state.events.len() >= channel.capacity {
+            state.dropped += 1;
+        } else {
+            state.events.push_back(event);
+            channel.not_empty.notify_one();
+        }
+        Ok(())
+    }
+}
+
+impl Drop for BoundedSender {
+    fn drop(&mut self) {
+        self.0.lock().sender_alive = false;
+        self.0.not_empty.notify_all();
+    }
+}
+
+/// Receiver of a bounded watcher, created with [`WatchGet::primary_bounded`](crate::watch::query::WatchGet::primary_bounded)
+/// or [`WatchScanPrimary::all_bounded`](crate::watch::query::WatchScanPrimary::all_bounded).
+///
+/// The methods are the same as [`std::sync::mpsc::Receiver`], with the feature `tokio` too. Once the watcher is
+/// [unwatched](crate::Database::unwatch), the receiver gets the remaining events and is then disconnected.
+pub struct BoundedReceiver(Arc<BoundedChannel>);
+
+impl BoundedReceiver {
+    /// Block until an event is received, returns an error once the watcher is removed and all the events are received.
+    pub fn recv(&self) -> Result<Event, RecvError> {
+        let channel = &self.0;
+        let mut state = channel.lock();
+        loop {
+            if let Some(event) = state.events.pop_front() {
+                channel.not_full.notify_one();
+                return Ok(event);
+            }
+            if !state.sender_alive {
+                return Err(RecvError);
+            }
+            state = channel
+                .not_empty
+                .wait(state)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+
+    /// Returns an event if there is one, without blocking.
+    pub fn try_recv(&self) -> Result<Event, TryRecvError> {
+        let mut state = self.0.lock();
+        match state.events.pop_front() {
+            Some(event) => {
+                self.0.not_full.notify_one();
+                Ok(event)
+            }
+            None if state.sender_alive => Err(TryRecvError::Empty),
+            None => Err(TryRecvError::Disconnected),
+        }
+    }
+
+    /// Same as [`recv`](Self::recv), but gives up after `timeout`.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<Event, RecvTimeoutError> {
+        let channel = &self.0;
+        let deadline = match Instant::now().checked_add(timeout) {
+            Some(deadline) => deadline,
+            None => return self.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        let mut state = channel.lock();
+        loop {
+            if let Some(event) = state.events.pop_front() {
+                channel.not_full.notify_one();
+                return Ok(event);
+            }
+            if !state.sender_alive {
+                return Err(RecvTimeoutError::Disconnected);
+            }
+            let remaining = deadline
+                .checked_duration_since(Instant::now())
+                .ok_or(RecvTimeoutError::Timeout)?;
+            state = channel
+                .not_empty
+                .wait_timeout(state, remaining)
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .0;
+        }
+    }
+
+    /// Number of events sent and not received yet.
+    pub fn len(&self) -> usize {
+        self.0.lock().events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.lock().events.is_empty()
+    }
+
+    /// Maximum number of events waiting to be received.
+    pub fn capacity(&self) -> usize {
+        self.0.capacity
+    }
+
+    /// Number of events dropped because the channel was full, with the policy [`FullPolicy::DropEvent`].
+    pub fn dropped(&self) -> u64 {
+        self.0.lock().dropped
+    }
+}
+
+impl Drop for BoundedReceiver {
+    fn drop(&mut self) {
+        self.0.lock().receiver_alive = false;
+        self.0.not_full.notify_all();
+    }
+}
+
+pub(crate) fn bounded_channel(
+    capacity: usize,
+    policy: FullPolicy,
+) -> (BoundedSender, BoundedReceiver) {
+    let channel = Arc::new(BoundedChannel {
+        state: Mutex::new(State {
+            events: VecDeque::new(),
+            dropped: 0,
+            receiver_alive: true,
+            sender_alive: true,
+        }),
+        capacity: capacity.max(1),
+        policy,
+        not_empty: Condvar::new(),
+        not_full: Condvar::new(),
+    });
+    (
+        BoundedSender(Arc::clone(&channel)),
+        BoundedReceiver(channel),
+    )
+}
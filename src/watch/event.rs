@@ -1,7 +1,14 @@
-use crate::db_type::{DatabaseOutputValue, Input};
+use crate::db_type::{DatabaseInnerKeyValue, DatabaseKeyValue, DatabaseOutputValue, Input};
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
-#[derive(Clone)]
+/// A change sent to the watchers.
+///
+/// Events carry the encoded values rather than the decoded models, so they can be serialized
+/// with serde, e.g. to forward them to another node, and decoded with
+/// [`Insert::inner`](struct.Insert.html#method.inner) on the other side by a program that
+/// defines the same models.
+#[derive(Clone, Serialize, Deserialize)]
 pub enum Event {
     Insert(Insert),
     Update(Update),
@@ -10,7 +17,11 @@ pub enum Event {
 
 impl Event {
     pub(crate) fn new_insert(value: DatabaseOutputValue) -> Self {
-        Self::Insert(Insert(value))
+        Self::Insert(Insert {
+            value,
+            seq: 0,
+            origin: Origin::Insert,
+        })
     }
 
     pub(crate) fn new_update(
@@ -20,11 +31,113 @@ impl Event {
         Self::Update(Update {
             old: old_value,
             new: new_value,
+            seq: 0,
+            origin: Origin::Update,
+        })
+    }
+
+    pub(crate) fn new_delete(key: DatabaseInnerKeyValue, value: DatabaseOutputValue) -> Self {
+        Self::Delete(Delete {
+            key,
+            value: Some(value),
+            seq: 0,
         })
     }
 
-    pub(crate) fn new_delete(value: DatabaseOutputValue) -> Self {
-        Self::Delete(Delete(value))
+    /// Returns the event sent to a watcher registered with
+    /// [`Watch::deletes_keys_only`](crate::watch::query::Watch::deletes_keys_only):
+    /// a `Delete` event without its value.
+    pub(crate) fn for_keys_only_deletes(&self) -> Self {
+        match self {
+            Event::Delete(event) => Event::Delete(Delete {
+                key: event.key.clone(),
+                value: None,
+                seq: event.seq,
+            }),
+            _ => self.clone(),
+        }
+    }
+
+    /// Set the origin of an `Insert` or an `Update` event, a `Delete` event is left unchanged.
+    pub(crate) fn with_origin(mut self, origin: Origin) -> Self {
+        match &mut self {
+            Event::Insert(event) => event.origin = origin,
+            Event::Update(event) => event.origin = origin,
+            Event::Delete(_) => {}
+        }
+        self
+    }
+
+    pub(crate) fn set_seq(&mut self, seq: u64) {
+        match self {
+            Event::Insert(event) => event.seq = seq,
+            Event::Update(event) => event.seq = seq,
+            Event::Delete(event) => event.seq = seq,
+        }
+    }
+
+    /// Sequence number assigned to the event when it is dispatched to the watchers.
+    ///
+    /// The sequence number is global to the database and strictly increasing, so a gap between
+    /// two events received by a watcher that listens to all values means that events were missed.
+    /// Watchers with a filter only receive a subset of the events, so gaps are expected for them.
+    pub fn seq(&self) -> u64 {
+        match self {
+            Event::Insert(event) => event.seq,
+            Event::Update(event) => event.seq,
+            Event::Delete(event) => event.seq,
+        }
+    }
+
+    /// Returns the value before and after the change: `(None, Some(new))` for an `Insert`,
+    /// `(Some(old), Some(new))` for an `Update` and `(Some(old), None)` for a `Delete`.
+    ///
+    /// Use it to apply any event to another store with a single code path, e.g. a change log.
+    /// The `Delete` events of a watcher registered with
+    /// [`Watch::deletes_keys_only`](crate::watch::query::Watch::deletes_keys_only) have no value:
+    /// they return `(None, None)`, use [`Delete::key`] instead.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     name: String,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     #[allow(unused_mut)]
+    ///     let (mut recv, _) = db.watch().get().primary::<Data>(1u64)?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Data { id: 1, name: "a".to_string() })?;
+    ///     rw.remove(Data { id: 1, name: "a".to_string() })?;
+    ///     rw.commit()?;
+    ///
+    ///     let (old, new) = recv.try_recv().unwrap().images::<Data>();
+    ///     assert_eq!((old, new), (None, Some(Data { id: 1, name: "a".to_string() })));
+    ///     let (old, new) = recv.try_recv().unwrap().images::<Data>();
+    ///     assert_eq!((old, new), (Some(Data { id: 1, name: "a".to_string() }), None));
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn images<T: Input>(&self) -> (Option<T>, Option<T>) {
+        match self {
+            Event::Insert(event) => (None, Some(event.inner())),
+            Event::Update(event) => (Some(event.inner_old()), Some(event.inner_new())),
+            Event::Delete(event) => (event.value.as_ref().map(|value| value.inner()), None),
+        }
     }
 }
 
@@ -38,19 +151,54 @@ impl Debug for Event {
     }
 }
 
-#[derive(Clone)]
-pub struct Insert(pub(crate) DatabaseOutputValue);
+/// The write operation that produced an [`Insert`] or an [`Update`] event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Origin {
+    /// [`RwTransaction::insert`](crate::transaction::RwTransaction::insert).
+    Insert,
+    /// [`RwTransaction::upsert`](crate::transaction::RwTransaction::upsert): an `Insert` event if the
+    /// value did not exist, an `Update` event if it was replaced. Also used by
+    /// [`upsert_with`](crate::transaction::RwTransaction::upsert_with) and
+    /// [`insert_if_changed`](crate::transaction::RwTransaction::insert_if_changed).
+    Upsert,
+    /// [`RwTransaction::update`](crate::transaction::RwTransaction::update).
+    Update,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Insert {
+    pub(crate) value: DatabaseOutputValue,
+    pub(crate) seq: u64,
+    pub(crate) origin: Origin,
+}
 
 impl Insert {
     pub fn inner<T: Input>(&self) -> T {
-        self.0.inner()
+        self.value.inner()
+    }
+
+    /// The bytes of the value, for the events of a [raw table](crate::DatabaseBuilder::define_raw).
+    pub fn inner_raw(&self) -> &[u8] {
+        &self.value.0
+    }
+
+    /// See [`Event::seq`](enum.Event.html#method.seq).
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    /// The operation that inserted the value: [`Origin::Insert`] or [`Origin::Upsert`].
+    pub fn origin(&self) -> Origin {
+        self.origin
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Update {
     pub(crate) old: DatabaseOutputValue,
     pub(crate) new: DatabaseOutputValue,
+    pub(crate) seq: u64,
+    pub(crate) origin: Origin,
 }
 
 impl Update {
@@ -60,13 +208,146 @@ impl Update {
     pub fn inner_new<T: Input>(&self) -> T {
         self.new.inner()
     }
+
+    /// See [`Insert::inner_raw`](struct.Insert.html#method.inner_raw).
+    pub fn inner_old_raw(&self) -> &[u8] {
+        &self.old.0
+    }
+
+    /// See [`Insert::inner_raw`](struct.Insert.html#method.inner_raw).
+    pub fn inner_new_raw(&self) -> &[u8] {
+        &self.new.0
+    }
+
+    /// See [`Event::seq`](enum.Event.html#method.seq).
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    /// The operation that updated the value: [`Origin::Update`] or [`Origin::Upsert`].
+    pub fn origin(&self) -> Origin {
+        self.origin
+    }
+
+    /// Returns the secondary keys changed by the update, ordered by name: the name of the secondary key,
+    /// its old value and its new value, as the bytes of the key.
+    ///
+    /// The secondary keys are computed from [`inner_old`](Self::inner_old) and [`inner_new`](Self::inner_new),
+    /// the unchanged secondary keys are not returned. The value is `None` for an optional key without value.
+    /// Use it to update an external index, e.g. a search index, without reindexing the whole value.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_db::watch::Event;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     #[secondary_key]
+    ///     name: String,
+    ///     #[secondary_key]
+    ///     city: String,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     #[allow(unused_mut)]
+    ///     let (mut recv, _) = db.watch().get().primary::<Data>(1u64)?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     let old = Data { id: 1, name: "a".to_string(), city: "Paris".to_string() };
+    ///     rw.insert(Data { id: 1, name: "a".to_string(), city: "Paris".to_string() })?;
+    ///     rw.update(old, Data { id: 1, name: "b".to_string(), city: "Paris".to_string() })?;
+    ///     rw.commit()?;
+    ///
+    ///     let _insert = recv.try_recv().unwrap();
+    ///     if let Event::Update(update) = recv.try_recv().unwrap() {
+    ///         let changes = update.secondary_key_changes::<Data>();
+    ///         assert_eq!(changes, vec![("name", Some(b"a".to_vec()), Some(b"b".to_vec()))]);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn secondary_key_changes<T: Input>(&self) -> Vec<SecondaryKeyChange> {
+        let old_keys = self.inner_old::<T>().native_db_secondary_keys();
+        let mut new_keys = self.inner_new::<T>().native_db_secondary_keys();
+        let mut changes: Vec<_> = old_keys
+            .into_iter()
+            .filter_map(|(key_def, old_value)| {
+                let old_value = key_value_bytes(old_value);
+                let new_value = new_keys.remove(&key_def).and_then(key_value_bytes);
+                if old_value == new_value {
+                    None
+                } else {
+                    Some((key_def.name(), old_value, new_value))
+                }
+            })
+            .collect();
+        changes.sort_by_key(|(name, _, _)| *name);
+        changes
+    }
 }
 
-#[derive(Clone)]
-pub struct Delete(pub(crate) DatabaseOutputValue);
+/// A secondary key changed by an update: its name, its old value and its new value,
+/// see [`Update::secondary_key_changes`].
+pub type SecondaryKeyChange = (&'static str, Option<Vec<u8>>, Option<Vec<u8>>);
+
+pub(crate) fn key_value_bytes(value: DatabaseKeyValue) -> Option<Vec<u8>> {
+    match value {
+        DatabaseKeyValue::Default(value) | DatabaseKeyValue::Optional(Some(value)) => {
+            Some(value.as_slice().to_vec())
+        }
+        DatabaseKeyValue::Optional(None) => None,
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Delete {
+    pub(crate) key: DatabaseInnerKeyValue,
+    pub(crate) value: Option<DatabaseOutputValue>,
+    pub(crate) seq: u64,
+}
 
 impl Delete {
+    /// # Panics
+    ///
+    /// Panics if the watcher is registered with [`Watch::deletes_keys_only`](crate::watch::query::Watch::deletes_keys_only),
+    /// use [`key`](Self::key) instead.
     pub fn inner<T: Input>(&self) -> T {
-        self.0.inner()
+        self.value().inner()
+    }
+
+    /// See [`Insert::inner_raw`](struct.Insert.html#method.inner_raw).
+    ///
+    /// # Panics
+    ///
+    /// Same as [`inner`](Self::inner).
+    pub fn inner_raw(&self) -> &[u8] {
+        &self.value().0
+    }
+
+    /// The primary key of the deleted value.
+    pub fn key(&self) -> &DatabaseInnerKeyValue {
+        &self.key
+    }
+
+    fn value(&self) -> &DatabaseOutputValue {
+        self.value
+            .as_ref()
+            .expect("the deleted value is not sent to a watcher registered with deletes_keys_only")
+    }
+
+    /// See [`Event::seq`](enum.Event.html#method.seq).
+    pub fn seq(&self) -> u64 {
+        self.seq
     }
 }
@@ -1,6 +1,7 @@
 use crate::db_type::{
     DatabaseInnerKeyValue, DatabaseKeyDefinition, DatabaseSecondaryKeyOptions, KeyDefinition,
 };
+use std::collections::HashSet;
 
 #[derive(Eq, PartialEq, Clone)]
 pub(crate) struct TableFilter {
@@ -12,6 +13,7 @@ pub(crate) struct TableFilter {
 pub(crate) enum KeyFilter {
     Primary(Option<DatabaseInnerKeyValue>),
     PrimaryStartWith(DatabaseInnerKeyValue),
+    PrimarySet(HashSet<DatabaseInnerKeyValue>),
     Secondary(
         DatabaseKeyDefinition<DatabaseSecondaryKeyOptions>,
         Option<DatabaseInnerKeyValue>,
@@ -40,6 +42,16 @@ impl TableFilter {
         }
     }
 
+    pub(crate) fn new_primary_set(
+        table_name: String,
+        keys: HashSet<DatabaseInnerKeyValue>,
+    ) -> Self {
+        Self {
+            table_name,
+            key_filter: KeyFilter::PrimarySet(keys),
+        }
+    }
+
     pub(crate) fn new_secondary<K: KeyDefinition<DatabaseSecondaryKeyOptions>>(
         table_name: String,
         key_def: &K,
@@ -0,0 +1,30 @@
+use crate::watch::Watchers;
+use std::sync::{Arc, RwLock};
+
+/// Removes a watcher when it is dropped, like [`unwatch`](crate::Database::unwatch).
+///
+/// Created with [`WatchGet::primary_guarded`](crate::watch::query::WatchGet::primary_guarded).
+/// Keep the guard alive as long as the events are needed: once it is dropped, the receiver
+/// gets the remaining events and is then disconnected.
+pub struct WatchGuard {
+    pub(crate) watchers: Arc<RwLock<Watchers>>,
+    pub(crate) id: u64,
+}
+
+impl WatchGuard {
+    /// The id of the watcher.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl Drop for WatchGuard {
+    fn drop(&mut self) {
+        // A poisoned lock means that a thread panicked while sending the events, remove the watcher anyway.
+        let mut watchers = match self.watchers.write() {
+            Ok(watchers) => watchers,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        watchers.remove_sender(self.id);
+    }
+}
@@ -1,16 +1,26 @@
 mod batch;
+mod bounded;
 mod event;
 mod filter;
+mod guard;
 pub mod query;
 mod request;
 mod sender;
+#[cfg(feature = "futures")]
+mod stream;
 
 pub(crate) use batch::*;
+pub use bounded::*;
 pub use event::*;
 pub(crate) use filter::*;
+pub use guard::*;
 pub(crate) use request::*;
 pub(crate) use sender::*;
+#[cfg(feature = "futures")]
+pub use stream::*;
 
+use std::collections::{HashMap, HashSet};
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::{Arc, RwLock, TryLockError};
 use thiserror::Error;
 
@@ -30,6 +40,15 @@ pub enum WatchEventError {
     SendError(#[from] tokio::sync::mpsc::error::SendError<Event>),
 }
 
+/// The events waiting to be received by a watcher, returned by [`Database::watcher_backlog`](crate::Database::watcher_backlog).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WatcherBacklog {
+    /// Number of events sent and not received yet.
+    Pending(usize),
+    /// The watcher has an unbounded channel, whose length is not known by the database.
+    NotMeasured,
+}
+
 #[cfg(not(feature = "tokio"))]
 pub type MpscSender<T> = std::sync::mpsc::Sender<T>;
 #[cfg(not(feature = "tokio"))]
@@ -40,6 +59,15 @@ pub type MpscSender<T> = tokio::sync::mpsc::UnboundedSender<T>;
 #[cfg(feature = "tokio")]
 pub type MpscReceiver<T> = tokio::sync::mpsc::UnboundedReceiver<T>;
 
+/// Sends the events of the batch to the watchers, in the order of the batch.
+///
+/// The channels are unbounded so a send never blocks, except for a bounded watcher with the policy
+/// [`FullPolicy::Block`] that waits for its receiver. Each watcher is independent: if the send to
+/// a watcher fails (its receiver has been dropped), the events are still sent to the other watchers
+/// and the first error is returned once the whole batch has been dispatched.
+///
+/// The watchers of each event are collected first and the lock is released before the predicates and
+/// the callbacks are called, so they can [`watch`](crate::Database::watch) or [`unwatch`](crate::Database::unwatch).
 pub(crate) fn push_batch(
     senders: Arc<RwLock<Watchers>>,
     batch: Batch,
@@ -48,13 +76,186 @@ pub(crate) fn push_batch(
         TryLockError::Poisoned(_) => WatchEventError::TryLockErrorPoisoned,
         TryLockError::WouldBlock => WatchEventError::TryLockErrorWouldBlock,
     })?;
+    let dispatch: Vec<_> = batch
+        .into_iter()
+        .map(|(watcher_request, mut event)| {
+            event.set_seq(watchers.next_seq());
+            (event, watchers.find_senders(&watcher_request))
+        })
+        .collect();
+    drop(watchers);
 
-    for (watcher_request, event) in batch {
-        for sender in watchers.find_senders(&watcher_request) {
-            let sender = sender.lock().unwrap();
-            sender.send(event.clone())?;
+    let mut first_error = None;
+    let mut panicked_watchers = HashSet::new();
+    for (event, event_watchers) in dispatch {
+        for watcher in event_watchers {
+            if panicked_watchers.contains(&watcher.id) {
+                continue;
+            }
+            if let Some(predicate) = &watcher.predicate {
+                match panic::catch_unwind(AssertUnwindSafe(|| predicate(&event))) {
+                    Ok(true) => {}
+                    Ok(false) => continue,
+                    Err(_) => {
+                        panicked_watchers.insert(watcher.id);
+                        continue;
+                    }
+                }
+            }
+            let event = if watcher.keys_only_deletes {
+                event.for_keys_only_deletes()
+            } else {
+                event.clone()
+            };
+            match &watcher.sender {
+                EventSink::Channel(sender) => {
+                    // The sender is the only data guarded by the mutex and it stays valid if a previous
+                    // holder panicked, so a poisoned mutex is recovered instead of breaking the watcher.
+                    let sender = sender
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner());
+                    if let Err(err) = sender.send(event) {
+                        first_error.get_or_insert(err.into());
+                    }
+                }
+                EventSink::Callback(callback) => {
+                    if panic::catch_unwind(AssertUnwindSafe(|| callback(event))).is_err() {
+                        panicked_watchers.insert(watcher.id);
+                    }
+                }
+                EventSink::Bounded(sender) => {
+                    if let Err(event) = sender.send(event) {
+                        first_error.get_or_insert(send_error(event));
+                    }
+                }
+            }
         }
     }
 
+    // A watcher whose predicate or callback panicked is dropped, its receiver is disconnected.
+    if !panicked_watchers.is_empty() {
+        let mut watchers = senders
+            .write()
+            .map_err(|_| WatchEventError::TryLockErrorPoisoned)?;
+        for id in panicked_watchers {
+            watchers.remove_sender(id);
+        }
+    }
+
+    match first_error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(feature = "tokio"))]
+fn send_error(event: Event) -> WatchEventError {
+    WatchEventError::SendError(std::sync::mpsc::SendError(event))
+}
+
+#[cfg(feature = "tokio")]
+fn send_error(event: Event) -> WatchEventError {
+    WatchEventError::SendError(tokio::sync::mpsc::error::SendError(event))
+}
+
+/// Checks that the bounded watchers with the policy [`FullPolicy::FailCommit`] have room for all
+/// their events of the batch, before the commit.
+pub(crate) fn check_fail_commit(
+    senders: &RwLock<Watchers>,
+    batch: &Batch,
+) -> Result<(), crate::db_type::Error> {
+    let watchers = senders
+        .read()
+        .map_err(|_| WatchEventError::TryLockErrorPoisoned)?;
+    if !watchers.has_fail_commit() {
+        return Ok(());
+    }
+    let dispatch: Vec<_> = batch
+        .notified()
+        .map(|(watcher_request, event)| (event, watchers.find_senders(watcher_request)))
+        .collect();
+    drop(watchers);
+
+    let mut counts: HashMap<u64, (Arc<BoundedSender>, usize)> = HashMap::new();
+    for (event, event_watchers) in dispatch {
+        for watcher in event_watchers {
+            let sender = match &watcher.sender {
+                EventSink::Bounded(sender) if sender.policy() == FullPolicy::FailCommit => sender,
+                _ => continue,
+            };
+            if let Some(predicate) = &watcher.predicate {
+                // A panicking predicate removes the watcher during the dispatch, it receives no event.
+                if !panic::catch_unwind(AssertUnwindSafe(|| predicate(event))).unwrap_or(false) {
+                    continue;
+                }
+            }
+            counts
+                .entry(watcher.id)
+                .or_insert_with(|| (Arc::clone(sender), 0))
+                .1 += 1;
+        }
+    }
+    for (id, (sender, count)) in counts {
+        if !sender.has_room(count) {
+            return Err(crate::db_type::Error::WatcherFull { id });
+        }
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db_type::{DatabaseOutputValue, InnerKeyValue};
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_push_batch_poisoned_sender() {
+        #[allow(unused_mut)]
+        let (sender, mut receiver) = {
+            #[cfg(not(feature = "tokio"))]
+            {
+                std::sync::mpsc::channel()
+            }
+            #[cfg(feature = "tokio")]
+            {
+                tokio::sync::mpsc::unbounded_channel()
+            }
+        };
+        let sender = Arc::new(Mutex::new(sender));
+
+        // Poison the mutex of the sender
+        let poison_sender = Arc::clone(&sender);
+        let _ = std::thread::spawn(move || {
+            let _guard = poison_sender.lock().unwrap();
+            panic!("poison the sender mutex");
+        })
+        .join();
+        assert!(sender.is_poisoned());
+
+        let mut watchers = Watchers::new();
+        watchers.add_sender(
+            0,
+            &TableFilter::new_primary("table".to_string(), None),
+            EventSink::Channel(sender),
+            None,
+            false,
+        );
+        let watchers = Arc::new(RwLock::new(watchers));
+
+        let mut batch = Batch::new();
+        batch.add(
+            WatcherRequest::new(
+                "table".to_string(),
+                1u32.database_inner_key_value(),
+                HashMap::new(),
+            ),
+            Event::new_insert(DatabaseOutputValue(vec![])),
+        );
+        push_batch(Arc::clone(&watchers), batch).unwrap();
+
+        // The watcher still receives the events
+        assert!(matches!(receiver.try_recv(), Ok(Event::Insert(_))));
+    }
+}
@@ -14,6 +14,9 @@ impl WatchGet<'_, '_> {
     /// Returns a channel receiver and the watcher id.
     /// The watcher id can be used to unwatch the channel.
     ///
+    /// If the primary key of the model has a [`check_len`](crate::DatabaseBuilder::define#checked-length),
+    /// a key of another length returns the error [`KeyLengthMismatch`](crate::db_type::Error::KeyLengthMismatch).
+    ///
     /// # Example
     /// ```rust
     /// use native_db::*;
@@ -45,6 +48,195 @@ impl WatchGet<'_, '_> {
         self.internal.watch_primary::<T>(key)
     }
 
+    /// Watch the primary key, the events are sent to a channel that holds at most `capacity` events.
+    ///
+    /// Returns a [`BoundedReceiver`](crate::watch::BoundedReceiver) and the watcher id. When the channel is full,
+    /// the commit follows `policy`: it waits for the receiver ([`Block`](crate::watch::FullPolicy::Block)),
+    /// drops the event ([`DropEvent`](crate::watch::FullPolicy::DropEvent)), or fails before anything is written
+    /// ([`FailCommit`](crate::watch::FullPolicy::FailCommit)), so no event is lost. The `capacity` is at least `1`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_db::watch::FullPolicy;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     // At most one event waiting to be received, the next commits fail
+    ///     let (recv, _id) = db.watch().get().primary_bounded::<Data>(1u64, 1, FullPolicy::FailCommit)?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Data { id: 1 })?;
+    ///     rw.commit()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.remove(Data { id: 1 })?;
+    ///     assert!(matches!(rw.commit(), Err(db_type::Error::WatcherFull { .. })));
+    ///
+    ///     // The insert is received, the remove was not committed
+    ///     assert!(recv.try_recv().is_ok());
+    ///     assert!(recv.try_recv().is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn primary_bounded<T: Input>(
+        &self,
+        key: impl InnerKeyValue,
+        capacity: usize,
+        policy: watch::FullPolicy,
+    ) -> Result<(watch::BoundedReceiver, u64)> {
+        self.internal
+            .watch_primary_bounded::<T>(key, capacity, policy)
+    }
+
+    /// Watch the primary key, the events are passed to `callback` instead of a channel.
+    ///
+    /// Returns the watcher id, to [`unwatch`](crate::Database::unwatch) the callback.
+    ///
+    /// The callback is called synchronously by [`commit`](crate::transaction::RwTransaction::commit), in the thread
+    /// that commits, after the redb commit: keep it fast, e.g. invalidate a cache entry, the commit returns only
+    /// once all the callbacks are done. It is called after the watchers are unlocked, so it can
+    /// [`watch`](crate::Database::watch) or [`unwatch`](crate::Database::unwatch), e.g. to unwatch itself:
+    /// the watchers are selected before the callbacks are called, an unwatched callback still receives
+    /// the remaining events of the commit being dispatched. If the callback panics,
+    /// the panic is caught and the watcher is removed: the callback is never called again.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     let events = Arc::new(AtomicUsize::new(0));
+    ///     let events_callback = Arc::clone(&events);
+    ///     let _id = db.watch().get().primary_callback::<Data>(1u64, move |_event| {
+    ///         events_callback.fetch_add(1, Ordering::SeqCst);
+    ///     })?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Data { id: 1 })?;
+    ///     rw.commit()?;
+    ///     // The callback is called before the commit returns
+    ///     assert_eq!(events.load(Ordering::SeqCst), 1);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn primary_callback<T: Input>(
+        &self,
+        key: impl InnerKeyValue,
+        callback: impl Fn(watch::Event) + Send + Sync + 'static,
+    ) -> Result<u64> {
+        self.internal.watch_primary_callback::<T>(key, callback)
+    }
+
+    /// Watch the primary key, the watcher is removed when the returned [`WatchGuard`](crate::watch::WatchGuard) is dropped.
+    ///
+    /// Same as [`primary`](Self::primary) but there is no need to call [`unwatch`](crate::Database::unwatch),
+    /// e.g. when a request handler returns early on an error.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///     
+    ///     {
+    ///         // Watch the primary key until the end of the scope
+    ///         let (_recv, _guard) = db.watch().get().primary_guarded::<Data>(1u64)?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn primary_guarded<T: Input>(
+        &self,
+        key: impl InnerKeyValue,
+    ) -> Result<(MpscReceiver<watch::Event>, watch::WatchGuard)> {
+        self.internal.watch_primary_guarded::<T>(key)
+    }
+
+    /// Watch a set of primary keys with a single watcher.
+    ///
+    /// All the events of the values with one of the `keys` are sent on the same channel,
+    /// instead of one channel per key with [`primary`](Self::primary).
+    ///
+    /// Returns a channel receiver and the watcher id.
+    /// The watcher id can be used to unwatch the channel.
+    ///
+    /// If the primary key of the model has a [`check_len`](crate::DatabaseBuilder::define#checked-length),
+    /// a key of another length returns the error [`KeyLengthMismatch`](crate::db_type::Error::KeyLengthMismatch).
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///     
+    ///     // Watch the primary keys 1, 2 and 3
+    ///     let (_recv, _id) = db.watch().get().primary_keys::<Data, _>(vec![1u64, 2, 3])?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn primary_keys<T: Input, K: InnerKeyValue>(
+        &self,
+        keys: impl IntoIterator<Item = K>,
+    ) -> Result<(MpscReceiver<watch::Event>, u64)> {
+        self.internal.watch_primary_keys::<T, K>(keys)
+    }
+
     /// Watch the secondary key.
     ///
     /// Returns a channel receiver and the watcher id.
@@ -83,4 +275,75 @@ impl WatchGet<'_, '_> {
     ) -> Result<(MpscReceiver<watch::Event>, u64)> {
         self.internal.watch_secondary::<T>(&key_def, key)
     }
+
+    /// Watch the secondary key and keep only the events whose value matches the `predicate`.
+    ///
+    /// The events are first filtered by the secondary key, the `predicate` is called only on the
+    /// decoded values of the matching events. For an update, the event is sent if the predicate
+    /// matches the old or the new value.
+    ///
+    /// If the `predicate` panics, the watcher is dropped: its receiver is disconnected.
+    ///
+    /// Returns a channel receiver and the watcher id.
+    /// The watcher id can be used to unwatch the channel.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Order {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     #[secondary_key]
+    ///     region: String,
+    ///     amount: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Order>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///     
+    ///     // Watch the orders of the region "EU" with an amount greater than 1000
+    ///     let (_recv, _id) = db.watch().get().secondary_filter::<Order, _>(
+    ///         OrderKey::region,
+    ///         "EU",
+    ///         |order: &Order| order.amount > 1000,
+    ///     )?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn secondary_filter<T, P>(
+        &self,
+        key_def: impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        key: impl InnerKeyValue,
+        predicate: P,
+    ) -> Result<(MpscReceiver<watch::Event>, u64)>
+    where
+        T: Input + 'static,
+        P: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        self.internal
+            .watch_secondary_filter::<T, P>(&key_def, key, predicate)
+    }
+
+    /// Watch the key `key` of the raw table `name`, see [`DatabaseBuilder::define_raw`](crate::DatabaseBuilder::define_raw).
+    ///
+    /// The events contain the bytes of the values: use [`Insert::inner_raw`](crate::watch::Insert::inner_raw)
+    /// to read them.
+    ///
+    /// Returns a channel receiver and the watcher id.
+    /// The watcher id can be used to unwatch the channel.
+    pub fn raw(
+        &self,
+        name: &str,
+        key: impl InnerKeyValue,
+    ) -> Result<(MpscReceiver<watch::Event>, u64)> {
+        self.internal.watch_raw(name, key)
+    }
 }
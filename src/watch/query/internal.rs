@@ -1,31 +1,63 @@
 use crate::db_type::{
     DatabaseSecondaryKeyOptions, Error, InnerKeyValue, Input, KeyDefinition, Result,
 };
+use crate::table_definition::raw_table_name;
 use crate::watch;
 use crate::watch::{MpscReceiver, TableFilter};
+use std::collections::HashSet;
 use std::sync::atomic::AtomicU64;
 use std::sync::{Arc, Mutex, RwLock};
 
 pub(crate) struct InternalWatch<'db> {
     pub(crate) watchers: &'db Arc<RwLock<watch::Watchers>>,
     pub(crate) watchers_counter_id: &'db AtomicU64,
+    pub(crate) keys_only_deletes: bool,
 }
 
 impl InternalWatch<'_> {
     fn watch_generic(
         &self,
         table_filter: watch::TableFilter,
+        predicate: Option<watch::EventPredicate>,
     ) -> Result<(MpscReceiver<watch::Event>, u64)> {
         #[cfg(not(feature = "tokio"))]
         let (event_sender, event_receiver) = std::sync::mpsc::channel();
         #[cfg(feature = "tokio")]
         let (event_sender, event_receiver) = tokio::sync::mpsc::unbounded_channel();
-        let event_sender = Arc::new(Mutex::new(event_sender));
+        let event_sender = watch::EventSink::Channel(Arc::new(Mutex::new(event_sender)));
+        let id = self.add_watcher(table_filter, event_sender, predicate)?;
+        Ok((event_receiver, id))
+    }
+
+    fn watch_bounded(
+        &self,
+        table_filter: watch::TableFilter,
+        capacity: usize,
+        policy: watch::FullPolicy,
+    ) -> Result<(watch::BoundedReceiver, u64)> {
+        let (event_sender, event_receiver) = watch::bounded_channel(capacity, policy);
+        let event_sender = watch::EventSink::Bounded(Arc::new(event_sender));
+        let id = self.add_watcher(table_filter, event_sender, None)?;
+        Ok((event_receiver, id))
+    }
+
+    fn add_watcher(
+        &self,
+        table_filter: watch::TableFilter,
+        event_sender: watch::EventSink,
+        predicate: Option<watch::EventPredicate>,
+    ) -> Result<u64> {
         let id = self.generate_watcher_id()?;
         let mut watchers = self.watchers.write().unwrap();
-        watchers.add_sender(id, &table_filter, Arc::clone(&event_sender));
+        watchers.add_sender(
+            id,
+            &table_filter,
+            event_sender,
+            predicate,
+            self.keys_only_deletes,
+        );
         drop(watchers);
-        Ok((event_receiver, id))
+        Ok(id)
     }
 
     fn generate_watcher_id(&self) -> Result<u64> {
@@ -43,17 +75,101 @@ impl InternalWatch<'_> {
         &self,
         key: impl InnerKeyValue,
     ) -> Result<(MpscReceiver<watch::Event>, u64)> {
-        let table_name = T::native_db_model().primary_key;
+        let model = T::native_db_model();
         let key = key.database_inner_key_value();
+        // An empty key or a key of the wrong length never matches, fail instead of creating a watcher that never fires.
+        model.check_primary_key(&key)?;
+        let table_name = model.primary_key;
         let table_filter =
             TableFilter::new_primary(table_name.unique_table_name.clone(), Some(key));
-        self.watch_generic(table_filter)
+        self.watch_generic(table_filter, None)
+    }
+
+    pub(crate) fn watch_primary_callback<T: Input>(
+        &self,
+        key: impl InnerKeyValue,
+        callback: impl Fn(watch::Event) + Send + Sync + 'static,
+    ) -> Result<u64> {
+        let model = T::native_db_model();
+        let key = key.database_inner_key_value();
+        model.check_primary_key(&key)?;
+        let table_filter =
+            TableFilter::new_primary(model.primary_key.unique_table_name.clone(), Some(key));
+        self.add_watcher(
+            table_filter,
+            watch::EventSink::Callback(Arc::new(callback)),
+            None,
+        )
+    }
+
+    pub(crate) fn watch_primary_bounded<T: Input>(
+        &self,
+        key: impl InnerKeyValue,
+        capacity: usize,
+        policy: watch::FullPolicy,
+    ) -> Result<(watch::BoundedReceiver, u64)> {
+        let model = T::native_db_model();
+        let key = key.database_inner_key_value();
+        model.check_primary_key(&key)?;
+        let table_filter =
+            TableFilter::new_primary(model.primary_key.unique_table_name.clone(), Some(key));
+        self.watch_bounded(table_filter, capacity, policy)
+    }
+
+    pub(crate) fn watch_primary_guarded<T: Input>(
+        &self,
+        key: impl InnerKeyValue,
+    ) -> Result<(MpscReceiver<watch::Event>, watch::WatchGuard)> {
+        let (receiver, id) = self.watch_primary::<T>(key)?;
+        let guard = watch::WatchGuard {
+            watchers: Arc::clone(self.watchers),
+            id,
+        };
+        Ok((receiver, guard))
+    }
+
+    pub(crate) fn watch_primary_keys<T: Input, K: InnerKeyValue>(
+        &self,
+        keys: impl IntoIterator<Item = K>,
+    ) -> Result<(MpscReceiver<watch::Event>, u64)> {
+        let model = T::native_db_model();
+        let keys = keys
+            .into_iter()
+            .map(|key| {
+                let key = key.database_inner_key_value();
+                model.check_primary_key(&key)?;
+                Ok(key)
+            })
+            .collect::<Result<HashSet<_>>>()?;
+        let table_filter =
+            TableFilter::new_primary_set(model.primary_key.unique_table_name.clone(), keys);
+        self.watch_generic(table_filter, None)
+    }
+
+    pub(crate) fn watch_raw(
+        &self,
+        name: &str,
+        key: impl InnerKeyValue,
+    ) -> Result<(MpscReceiver<watch::Event>, u64)> {
+        let table_filter =
+            TableFilter::new_primary(raw_table_name(name), Some(key.database_inner_key_value()));
+        self.watch_generic(table_filter, None)
     }
 
     pub(crate) fn watch_primary_all<T: Input>(&self) -> Result<(MpscReceiver<watch::Event>, u64)> {
         let table_name = T::native_db_model().primary_key;
         let table_filter = TableFilter::new_primary(table_name.unique_table_name.clone(), None);
-        self.watch_generic(table_filter)
+        self.watch_generic(table_filter, None)
+    }
+
+    pub(crate) fn watch_primary_all_bounded<T: Input>(
+        &self,
+        capacity: usize,
+        policy: watch::FullPolicy,
+    ) -> Result<(watch::BoundedReceiver, u64)> {
+        let table_name = T::native_db_model().primary_key;
+        let table_filter = TableFilter::new_primary(table_name.unique_table_name.clone(), None);
+        self.watch_bounded(table_filter, capacity, policy)
     }
 
     pub(crate) fn watch_primary_start_with<T: Input>(
@@ -64,7 +180,7 @@ impl InternalWatch<'_> {
         let start_with = start_with.database_inner_key_value();
         let table_filter =
             TableFilter::new_primary_start_with(table_name.unique_table_name.clone(), start_with);
-        self.watch_generic(table_filter)
+        self.watch_generic(table_filter, None)
     }
 
     pub(crate) fn watch_secondary<T: Input>(
@@ -76,7 +192,32 @@ impl InternalWatch<'_> {
         let key = key.database_inner_key_value();
         let table_filter =
             TableFilter::new_secondary(table_name.unique_table_name.clone(), key_def, Some(key));
-        self.watch_generic(table_filter)
+        self.watch_generic(table_filter, None)
+    }
+
+    pub(crate) fn watch_secondary_filter<T, P>(
+        &self,
+        key_def: &impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+        key: impl InnerKeyValue,
+        predicate: P,
+    ) -> Result<(MpscReceiver<watch::Event>, u64)>
+    where
+        T: Input + 'static,
+        P: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        let table_name = T::native_db_model().primary_key;
+        let key = key.database_inner_key_value();
+        let table_filter =
+            TableFilter::new_secondary(table_name.unique_table_name.clone(), key_def, Some(key));
+        // Only called on the events that match the secondary key.
+        let predicate: watch::EventPredicate = Arc::new(move |event| match event {
+            watch::Event::Insert(insert) => predicate(&insert.inner()),
+            watch::Event::Update(update) => {
+                predicate(&update.inner_old()) || predicate(&update.inner_new())
+            }
+            watch::Event::Delete(delete) => predicate(&delete.inner()),
+        });
+        self.watch_generic(table_filter, Some(predicate))
     }
 
     pub(crate) fn watch_secondary_all<T: Input>(
@@ -86,7 +227,31 @@ impl InternalWatch<'_> {
         let table_name = T::native_db_model().primary_key;
         let table_filter =
             TableFilter::new_secondary(table_name.unique_table_name.clone(), key_def, None);
-        self.watch_generic(table_filter)
+        self.watch_generic(table_filter, None)
+    }
+
+    pub(crate) fn watch_secondary_changes<T: Input + 'static>(
+        &self,
+        key_def: &impl KeyDefinition<DatabaseSecondaryKeyOptions>,
+    ) -> Result<(MpscReceiver<watch::Event>, u64)> {
+        let table_name = T::native_db_model().primary_key;
+        let table_filter =
+            TableFilter::new_secondary(table_name.unique_table_name.clone(), key_def, None);
+        let key_def = key_def.database_key();
+        let key_value = move |item: T| {
+            item.native_db_secondary_keys()
+                .remove(&key_def)
+                .and_then(watch::key_value_bytes)
+        };
+        // Called with the deleted value even for the watchers registered with `deletes_keys_only`.
+        let predicate: watch::EventPredicate = Arc::new(move |event| match event {
+            watch::Event::Insert(insert) => key_value(insert.inner()).is_some(),
+            watch::Event::Update(update) => {
+                key_value(update.inner_old()) != key_value(update.inner_new())
+            }
+            watch::Event::Delete(delete) => key_value(delete.inner()).is_some(),
+        });
+        self.watch_generic(table_filter, Some(predicate))
     }
 
     pub(crate) fn watch_secondary_start_with<T: Input>(
@@ -101,6 +266,6 @@ impl InternalWatch<'_> {
             key_def,
             start_with,
         );
-        self.watch_generic(table_filter)
+        self.watch_generic(table_filter, None)
     }
 }
@@ -12,6 +12,42 @@ pub struct Watch<'db> {
 }
 
 impl<'db> Watch<'db> {
+    /// The watchers registered with this query receive the [`Delete`](crate::watch::Delete) events
+    /// without the deleted value, only its [`key`](crate::watch::Delete::key).
+    ///
+    /// Use it for consumers that only need the key of the deleted values, like a cache eviction:
+    /// the deleted value is not copied for the watcher. [`Delete::inner`](crate::watch::Delete::inner)
+    /// panics for these events. The `Insert` and `Update` events are unchanged.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     // Watch all values, the deletes carry only the key
+    ///     let (_recv, _id) = db.watch().deletes_keys_only().scan().primary().all::<Data>()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn deletes_keys_only(mut self) -> Self {
+        self.internal.keys_only_deletes = true;
+        self
+    }
+
     /// Watch only one value.
     pub fn get<'w>(&'w self) -> WatchGet<'db, 'w> {
         WatchGet {
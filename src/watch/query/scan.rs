@@ -71,6 +71,51 @@ impl WatchScanPrimary<'_, '_> {
         self.internal.watch_primary_all::<T>()
     }
 
+    /// Watch all values, the events are sent to a channel that holds at most `capacity` events.
+    ///
+    /// Same as [`WatchGet::primary_bounded`](crate::watch::query::WatchGet::primary_bounded) for all the values.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_db::watch::FullPolicy;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     // Watch all values, a slow receiver drops the events
+    ///     let (recv, _id) = db.watch().scan().primary().all_bounded::<Data>(1, FullPolicy::DropEvent)?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Data { id: 1 })?;
+    ///     rw.insert(Data { id: 2 })?;
+    ///     rw.commit()?;
+    ///     assert_eq!(recv.len(), 1);
+    ///     assert_eq!(recv.dropped(), 1);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn all_bounded<T: Input>(
+        &self,
+        capacity: usize,
+        policy: watch::FullPolicy,
+    ) -> Result<(watch::BoundedReceiver, u64)> {
+        self.internal
+            .watch_primary_all_bounded::<T>(capacity, policy)
+    }
+
     /// **TODO: needs to be implemented**
     pub fn range<'a>(
         &self,
@@ -158,6 +203,56 @@ impl WatchScanSecondary<'_, '_> {
         self.internal.watch_secondary_all::<T>(&self.key_def)
     }
 
+    /// Watch the changes of the secondary key: only the events that add, remove or change its value.
+    ///
+    /// An `Update` is sent only if the value of the secondary key differs between the old and the new value,
+    /// the updates of the other fields are not sent. An `Insert` or a `Delete` is sent if the value has this
+    /// secondary key: an optional key without value is ignored. Use it to keep an external index in sync,
+    /// with [`Update::secondary_key_changes`](crate::watch::Update::secondary_key_changes) to get the old and new values.
+    ///
+    /// # Example
+    /// ```rust
+    /// use native_db::*;
+    /// use native_model::{native_model, Model};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// #[native_model(id=1, version=1)]
+    /// #[native_db]
+    /// struct Data {
+    ///     #[primary_key]
+    ///     id: u64,
+    ///     #[secondary_key]
+    ///     name: String,
+    ///     count: u32,
+    /// }
+    ///
+    /// fn main() -> Result<(), db_type::Error> {
+    ///     let mut builder = DatabaseBuilder::new();
+    ///     builder.define::<Data>()?;
+    ///     let db = builder.create_in_memory()?;
+    ///
+    ///     #[allow(unused_mut)]
+    ///     let (mut recv, _id) = db.watch().scan().secondary(DataKey::name).changes::<Data>()?;
+    ///
+    ///     let rw = db.rw_transaction()?;
+    ///     rw.insert(Data { id: 1, name: "a".to_string(), count: 0 })?;
+    ///     // Not sent: the name is unchanged
+    ///     rw.update(
+    ///         Data { id: 1, name: "a".to_string(), count: 0 },
+    ///         Data { id: 1, name: "a".to_string(), count: 1 },
+    ///     )?;
+    ///     rw.commit()?;
+    ///
+    ///     assert!(matches!(recv.try_recv(), Ok(watch::Event::Insert(_))));
+    ///     assert!(recv.try_recv().is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn changes<T: Input + 'static>(&self) -> Result<(MpscReceiver<watch::Event>, u64)> {
+        self.internal.watch_secondary_changes::<T>(&self.key_def)
+    }
+
     pub fn range<'a, 'ws>(
         &'ws self,
         _range: impl RangeBounds<&'a [u8]> + 'a,
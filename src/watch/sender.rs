@@ -1,45 +1,104 @@
 use crate::db_type::DatabaseKeyValue;
 use crate::watch::filter::{KeyFilter, TableFilter};
 use crate::watch::request::WatcherRequest;
-use crate::watch::{Event, MpscSender};
+use crate::watch::{BoundedSender, Event, FullPolicy, MpscSender, WatcherBacklog};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 
-pub(crate) struct Watchers(HashMap<u64, (TableFilter, Arc<Mutex<MpscSender<Event>>>)>);
+/// Filter on the value of the events, applied after the key filter of the watcher.
+pub(crate) type EventPredicate = Arc<dyn Fn(&Event) -> bool + Send + Sync>;
+
+/// Callback of a watcher, called with each event during the dispatch of the commit.
+pub(crate) type EventCallback = Arc<dyn Fn(Event) + Send + Sync>;
+
+/// Where the events of a watcher are delivered.
+#[derive(Clone)]
+pub(crate) enum EventSink {
+    Channel(Arc<Mutex<MpscSender<Event>>>),
+    Callback(EventCallback),
+    Bounded(Arc<BoundedSender>),
+}
+
+#[derive(Clone)]
+pub(crate) struct WatcherSender {
+    pub(crate) id: u64,
+    pub(crate) sender: EventSink,
+    pub(crate) predicate: Option<EventPredicate>,
+    /// The `Delete` events are sent without the deleted value.
+    pub(crate) keys_only_deletes: bool,
+}
+
+pub(crate) struct Watchers {
+    senders: HashMap<u64, (TableFilter, WatcherSender)>,
+    seq_counter: AtomicU64,
+}
 
 impl Watchers {
     pub(crate) fn new() -> Self {
-        Self(HashMap::new())
+        Self {
+            senders: HashMap::new(),
+            seq_counter: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the next event sequence number, shared by all the watchers of the database.
+    pub(crate) fn next_seq(&self) -> u64 {
+        self.seq_counter.fetch_add(1, Ordering::SeqCst)
     }
 
     pub(crate) fn add_sender(
         &mut self,
         id: u64,
         table_filter: &TableFilter,
-        event_sender: Arc<Mutex<MpscSender<Event>>>,
+        event_sender: EventSink,
+        predicate: Option<EventPredicate>,
+        keys_only_deletes: bool,
     ) {
-        self.0.insert(id, (table_filter.clone(), event_sender));
+        let watcher_sender = WatcherSender {
+            id,
+            sender: event_sender,
+            predicate,
+            keys_only_deletes,
+        };
+        self.senders
+            .insert(id, (table_filter.clone(), watcher_sender));
     }
 
     pub(crate) fn remove_sender(&mut self, id: u64) {
-        self.0.remove(&id);
+        self.senders.remove(&id);
+    }
+
+    /// Number of events waiting to be received by the watcher `id`, see [`Database::watcher_backlog`](crate::Database::watcher_backlog).
+    pub(crate) fn backlog(&self, id: u64) -> Option<WatcherBacklog> {
+        let backlog = match &self.senders.get(&id)?.1.sender {
+            EventSink::Bounded(sender) => WatcherBacklog::Pending(sender.len()),
+            // The callbacks are called during the commit, nothing is waiting.
+            EventSink::Callback(_) => WatcherBacklog::Pending(0),
+            EventSink::Channel(_) => WatcherBacklog::NotMeasured,
+        };
+        Some(backlog)
     }
 
-    pub(crate) fn find_senders(
-        &self,
-        request: &WatcherRequest,
-    ) -> Vec<Arc<Mutex<MpscSender<Event>>>> {
+    /// Returns `true` if a bounded watcher has the policy [`FullPolicy::FailCommit`].
+    pub(crate) fn has_fail_commit(&self) -> bool {
+        self.senders.values().any(|(_, watcher)| {
+            matches!(&watcher.sender, EventSink::Bounded(sender) if sender.policy() == FullPolicy::FailCommit)
+        })
+    }
+
+    pub(crate) fn find_senders(&self, request: &WatcherRequest) -> Vec<WatcherSender> {
         let mut event_senders = Vec::new();
-        for (_, (filter, event_sender)) in &self.0 {
+        for (_, (filter, event_sender)) in &self.senders {
             if filter.table_name == request.table_name {
                 match &filter.key_filter {
                     KeyFilter::Primary(value) => {
                         if let Some(key) = &value {
                             if key == &request.primary_key {
-                                event_senders.push(Arc::clone(event_sender));
+                                event_senders.push(event_sender.clone());
                             }
                         } else {
-                            event_senders.push(Arc::clone(event_sender));
+                            event_senders.push(event_sender.clone());
                         }
                     }
                     KeyFilter::PrimaryStartWith(key_prefix) => {
@@ -48,7 +107,12 @@ impl Watchers {
                             .as_slice()
                             .starts_with(key_prefix.as_slice())
                         {
-                            event_senders.push(Arc::clone(event_sender));
+                            event_senders.push(event_sender.clone());
+                        }
+                    }
+                    KeyFilter::PrimarySet(keys) => {
+                        if keys.contains(&request.primary_key) {
+                            event_senders.push(event_sender.clone());
                         }
                     }
                     KeyFilter::Secondary(key_def, key) => {
@@ -60,19 +124,19 @@ impl Watchers {
                                     match request_secondary_key {
                                         DatabaseKeyValue::Default(value) => {
                                             if value == filter_value {
-                                                event_senders.push(Arc::clone(event_sender));
+                                                event_senders.push(event_sender.clone());
                                             }
                                         }
                                         DatabaseKeyValue::Optional(value) => {
                                             if let Some(value) = value {
                                                 if value == filter_value {
-                                                    event_senders.push(Arc::clone(event_sender));
+                                                    event_senders.push(event_sender.clone());
                                                 }
                                             }
                                         }
                                     }
                                 } else {
-                                    event_senders.push(Arc::clone(event_sender));
+                                    event_senders.push(event_sender.clone());
                                 }
                             }
                         }
@@ -85,7 +149,7 @@ impl Watchers {
                                 DatabaseKeyValue::Default(value) => {
                                     if key_def == request_secondary_key_def {
                                         if value.as_slice().starts_with(key_prefix.as_slice()) {
-                                            event_senders.push(Arc::clone(event_sender));
+                                            event_senders.push(event_sender.clone());
                                         }
                                     }
                                 }
@@ -93,7 +157,7 @@ impl Watchers {
                                     if let Some(value) = value {
                                         if key_def == request_secondary_key_def {
                                             if value.as_slice().starts_with(key_prefix.as_slice()) {
-                                                event_senders.push(Arc::clone(event_sender));
+                                                event_senders.push(event_sender.clone());
                                             }
                                         }
                                     }
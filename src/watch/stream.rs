@@ -0,0 +1,63 @@
+use crate::watch::{Event, MpscReceiver};
+use futures_core::Stream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// The events of a watcher as a [`Stream`](futures_core::Stream), available with the feature `futures`.
+///
+/// Created from the receiver returned by the [`watch`](crate::Database::watch) queries. The stream ends when
+/// the watcher is removed with [`unwatch`](crate::Database::unwatch) or when the database is dropped.
+///
+/// # Example
+/// ```rust
+/// use futures_util::StreamExt;
+/// use native_db::watch::{Event, WatchStream};
+/// use native_db::*;
+/// use native_model::{native_model, Model};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[native_model(id=1, version=1)]
+/// #[native_db]
+/// struct Data {
+///     #[primary_key]
+///     id: u64,
+/// }
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() -> Result<(), db_type::Error> {
+///     let mut builder = DatabaseBuilder::new();
+///     builder.define::<Data>()?;
+///     let db = builder.create_in_memory()?;
+///
+///     let (recv, _id) = db.watch().scan().primary().all::<Data>()?;
+///     let mut stream = WatchStream::from(recv).filter(|event| {
+///         std::future::ready(matches!(event, Event::Insert(_)))
+///     });
+///
+///     let rw = db.rw_transaction()?;
+///     rw.insert(Data { id: 1 })?;
+///     rw.commit()?;
+///
+///     let event = stream.next().await.unwrap();
+///     assert!(matches!(event, Event::Insert(_)));
+///     Ok(())
+/// }
+/// ```
+pub struct WatchStream {
+    receiver: MpscReceiver<Event>,
+}
+
+impl From<MpscReceiver<Event>> for WatchStream {
+    fn from(receiver: MpscReceiver<Event>) -> Self {
+        Self { receiver }
+    }
+}
+
+impl Stream for WatchStream {
+    type Item = Event;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Allows one write transaction at a time, like the write lock of redb, but with a timed acquisition.
+///
+/// Every write transaction of the database takes the gate before `begin_write`, so redb never blocks
+/// a transaction that holds the gate.
+pub(crate) struct WriteGate {
+    locked: Mutex<bool>,
+    released: Condvar,
+}
+
+/// Releases the [`WriteGate`] when dropped.
+pub(crate) struct WriteGateGuard<'a> {
+    gate: &'a WriteGate,
+}
+
+impl WriteGate {
+    pub(crate) fn new() -> Self {
+        Self {
+            locked: Mutex::new(false),
+            released: Condvar::new(),
+        }
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, bool> {
+        // The state is a simple flag, it is valid even if a thread panicked while holding the mutex.
+        self.locked
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub(crate) fn acquire(&self) -> WriteGateGuard<'_> {
+        let mut locked = self.lock_state();
+        while *locked {
+            locked = self
+                .released
+                .wait(locked)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+        *locked = true;
+        WriteGateGuard { gate: self }
+    }
+
+    /// Returns `None` if the gate is not released within `timeout`.
+    pub(crate) fn acquire_timeout(&self, timeout: Duration) -> Option<WriteGateGuard<'_>> {
+        let deadline = Instant::now() + timeout;
+        let mut locked = self.lock_state();
+        while *locked {
+            let remaining = deadline.checked_duration_since(Instant::now())?;
+            locked = self
+                .released
+                .wait_timeout(locked, remaining)
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .0;
+        }
+        *locked = true;
+        Some(WriteGateGuard { gate: self })
+    }
+}
+
+impl Drop for WriteGateGuard<'_> {
+    fn drop(&mut self) {
+        *self.gate.lock_state() = false;
+        self.gate.released.notify_one();
+    }
+}
@@ -0,0 +1,143 @@
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct Item {
+    #[primary_key]
+    id: u32,
+    name: String,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 2, version = 1)]
+#[native_db]
+struct AuditEntry {
+    #[primary_key]
+    id: u64,
+    table: String,
+    key: Vec<u8>,
+    kind: AuditKind,
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+fn audit_entry(record: AuditRecord) -> AuditEntry {
+    AuditEntry {
+        id: NEXT_ID.fetch_add(1, Ordering::SeqCst),
+        table: record.table,
+        key: record.key,
+        kind: record.kind,
+    }
+}
+
+fn audit_kinds(db: &Database) -> Vec<AuditKind> {
+    let r = db.r_transaction().unwrap();
+    let entries: Vec<AuditEntry> = r.scan().primary().unwrap().all().collect();
+    entries.into_iter().map(|entry| entry.kind).collect()
+}
+
+#[test]
+fn test_audit_log() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    builder.define::<AuditEntry>().unwrap();
+    builder.set_audit_log(audit_entry);
+    let db = builder.create_in_memory().unwrap();
+
+    let item = Item {
+        id: 1,
+        name: "a".to_string(),
+    };
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(item.clone()).unwrap();
+    rw.update(
+        item.clone(),
+        Item {
+            id: 1,
+            name: "b".to_string(),
+        },
+    )
+    .unwrap();
+    rw.commit().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.remove(Item {
+        id: 1,
+        name: "b".to_string(),
+    })
+    .unwrap();
+    rw.commit().unwrap();
+
+    assert_eq!(
+        audit_kinds(&db),
+        vec![AuditKind::Insert, AuditKind::Update, AuditKind::Delete]
+    );
+
+    let r = db.r_transaction().unwrap();
+    let entry: AuditEntry = r.scan().primary().unwrap().all().next().unwrap();
+    assert_eq!(entry.table, "1_1_id");
+    assert_eq!(entry.key, 1u32.database_inner_key_value().as_slice());
+}
+
+#[test]
+fn test_audit_log_aborted_and_disabled() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    builder.define::<AuditEntry>().unwrap();
+    builder.set_audit_log(audit_entry);
+    let db = builder.create_in_memory().unwrap();
+
+    // An aborted transaction has no entries
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Item {
+        id: 1,
+        name: "a".to_string(),
+    })
+    .unwrap();
+    drop(rw);
+    assert!(audit_kinds(&db).is_empty());
+
+    // A transaction with the audit log disabled has no entries
+    let rw = db.rw_transaction().unwrap();
+    rw.disable_audit_log();
+    rw.insert(Item {
+        id: 1,
+        name: "a".to_string(),
+    })
+    .unwrap();
+    rw.commit().unwrap();
+    assert!(audit_kinds(&db).is_empty());
+
+    // The writes of a model without notifications are audited
+    #[allow(unused_mut)]
+    let (mut recv, _) = db.watch().scan().primary().all::<Item>().unwrap();
+    let rw = db.rw_transaction().unwrap();
+    rw.suppress_notifications::<Item>();
+    rw.insert(Item {
+        id: 2,
+        name: "b".to_string(),
+    })
+    .unwrap();
+    rw.commit().unwrap();
+    assert_eq!(audit_kinds(&db), vec![AuditKind::Insert]);
+    assert!(recv.try_recv().is_err());
+}
+
+#[test]
+fn test_audit_log_model_not_defined() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    builder.set_audit_log(audit_entry);
+
+    assert!(matches!(
+        builder.create_in_memory(),
+        Err(db_type::Error::InvalidConfiguration { .. })
+    ));
+
+    builder.define::<AuditEntry>().unwrap();
+    assert!(builder.create_in_memory().is_ok());
+}
@@ -0,0 +1,23 @@
+#![cfg(feature = "axum")]
+
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use native_db::db_type::Error;
+
+#[test]
+fn test_error_into_response() {
+    let response = Error::KeyNotFound { key: vec![1] }.into_response();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = Error::DuplicateKey {
+        key_name: "name".to_string(),
+    }
+    .into_response();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response = Error::InvalidSequenceStep.into_response();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = Error::BatchWriteAborted.into_response();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
@@ -0,0 +1,160 @@
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct Item {
+    #[primary_key]
+    id: u32,
+    #[secondary_key(unique)]
+    name: String,
+}
+
+impl Item {
+    fn new(id: u32, name: &str) -> Self {
+        Self {
+            id,
+            name: name.to_string(),
+        }
+    }
+}
+
+#[test]
+fn test_batch_writer_max_ops() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    thread::scope(|scope| {
+        let writer = db.batch_writer(scope, 2, Duration::from_secs(3600));
+        let handle_1 = writer.write(|rw| rw.insert(Item::new(1, "a"))).unwrap();
+        assert_eq!(writer.len(), 1);
+        assert!(handle_1.try_wait().is_none());
+
+        let r = db.r_transaction().unwrap();
+        assert_eq!(r.len().primary::<Item>().unwrap(), 0);
+        drop(r);
+
+        let handle_2 = writer.write(|rw| rw.insert(Item::new(2, "b"))).unwrap();
+        assert!(writer.is_empty());
+        handle_1.wait().unwrap();
+        handle_2.wait().unwrap();
+    });
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<Item>().unwrap(), 2);
+}
+
+#[test]
+fn test_batch_writer_max_interval() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    thread::scope(|scope| {
+        let writer = db.batch_writer(scope, 100, Duration::from_millis(10));
+        let handle = writer.write(|rw| rw.insert(Item::new(1, "a"))).unwrap();
+        // Committed by the background thread, without another write or a flush
+        let start = Instant::now();
+        while handle.try_wait().is_none() {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(writer.is_empty());
+
+        let r = db.r_transaction().unwrap();
+        assert_eq!(r.len().primary::<Item>().unwrap(), 1);
+    });
+}
+
+#[test]
+fn test_batch_writer_flush_on_drop() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    thread::scope(|scope| {
+        let writer = db.batch_writer(scope, 100, Duration::from_secs(3600));
+        let handle = writer.write(|rw| rw.insert(Item::new(1, "a"))).unwrap();
+        drop(writer);
+        assert!(matches!(handle.try_wait(), Some(Ok(()))));
+    });
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<Item>().unwrap(), 1);
+}
+
+#[test]
+fn test_batch_writer_abort() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    thread::scope(|scope| {
+        let writer = db.batch_writer(scope, 100, Duration::from_secs(3600));
+        let handle_1 = writer.write(|rw| rw.insert(Item::new(1, "a"))).unwrap();
+        // Duplicate unique secondary key
+        let handle_2 = writer.write(|rw| rw.insert(Item::new(2, "a"))).unwrap();
+        let result = writer.flush();
+        assert!(matches!(result, Err(db_type::Error::DuplicateKey { .. })));
+
+        assert!(matches!(
+            handle_1.wait(),
+            Err(db_type::Error::BatchWriteAborted)
+        ));
+        assert!(matches!(
+            handle_2.wait(),
+            Err(db_type::Error::BatchWriteAborted)
+        ));
+    });
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<Item>().unwrap(), 0);
+}
+
+#[test]
+fn test_batch_writer_wait_flushes() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    thread::scope(|scope| {
+        let writer = db.batch_writer(scope, 100, Duration::from_secs(3600));
+        let handle = writer.write(|rw| rw.insert(Item::new(1, "a"))).unwrap();
+        // Doesn't wait for a threshold
+        handle.wait().unwrap();
+        assert!(writer.is_empty());
+
+        let r = db.r_transaction().unwrap();
+        assert_eq!(r.len().primary::<Item>().unwrap(), 1);
+    });
+}
+
+#[cfg(not(feature = "tokio"))]
+#[test]
+fn test_batch_writer_watch() {
+    use native_db::watch::Event;
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let (recv, _) = db.watch().scan().primary().all::<Item>().unwrap();
+
+    thread::scope(|scope| {
+        let writer = db.batch_writer(scope, 2, Duration::from_secs(3600));
+        writer.write(|rw| rw.insert(Item::new(1, "a"))).unwrap();
+        assert!(recv.try_recv().is_err());
+        writer.write(|rw| rw.insert(Item::new(2, "b"))).unwrap();
+    });
+
+    for _ in 0..2 {
+        let event = recv.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert!(matches!(event, Event::Insert(_)));
+    }
+    assert!(recv.try_recv().is_err());
+}
@@ -0,0 +1,172 @@
+use native_db::*;
+use native_model::{native_model, Model};
+use rand::distributions::{Alphanumeric, Standard};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+// Property: for any value `v` of a model, `decode(encode(v)) == v`, and the value read back
+// from the database is equal to the inserted value.
+
+const CASES: usize = 2_000;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+enum Variant {
+    Unit,
+    Tuple(i32, String),
+    Struct { a: u8, b: Option<Vec<u8>> },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+struct Nested {
+    flag: bool,
+    values: Vec<i64>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct Item {
+    #[primary_key]
+    id: u64,
+    #[secondary_key]
+    name: String,
+    u8: u8,
+    u16: u16,
+    u32: u32,
+    u128: u128,
+    i8: i8,
+    i16: i16,
+    i32: i32,
+    i64: i64,
+    i128: i128,
+    f32: f32,
+    f64: f64,
+    char: char,
+    bytes: Vec<u8>,
+    option: Option<String>,
+    tuple: (u16, bool, String),
+    array: [u8; 4],
+    variant: Variant,
+    nested: Vec<Nested>,
+    map: BTreeMap<String, u32>,
+    hash_map: HashMap<u32, Option<i16>>,
+    uuid: uuid::Uuid,
+}
+
+fn random_string(rng: &mut StdRng) -> String {
+    let len = rng.gen_range(0..32);
+    if rng.gen_bool(0.5) {
+        (0..len).map(|_| rng.sample(Alphanumeric) as char).collect()
+    } else {
+        // Any unicode scalar value, including the multi-byte ones.
+        rng.sample_iter::<char, _>(Standard).take(len).collect()
+    }
+}
+
+fn random_bytes(rng: &mut StdRng) -> Vec<u8> {
+    let len = rng.gen_range(0..64);
+    (0..len).map(|_| rng.gen()).collect()
+}
+
+// Any finite value or an infinity: NaN is never equal to itself.
+fn random_f64(rng: &mut StdRng) -> f64 {
+    match rng.gen_range(0..4) {
+        0 => f64::INFINITY,
+        1 => f64::NEG_INFINITY,
+        2 => f64::from_bits(rng.gen::<u64>() & !(0x7ff << 52)),
+        _ => rng.gen::<f64>() * f64::MAX * if rng.gen() { 1.0 } else { -1.0 },
+    }
+}
+
+fn random_variant(rng: &mut StdRng) -> Variant {
+    match rng.gen_range(0..3) {
+        0 => Variant::Unit,
+        1 => Variant::Tuple(rng.gen(), random_string(rng)),
+        _ => Variant::Struct {
+            a: rng.gen(),
+            b: if rng.gen() {
+                Some(random_bytes(rng))
+            } else {
+                None
+            },
+        },
+    }
+}
+
+fn random_item(rng: &mut StdRng, id: u64) -> Item {
+    Item {
+        id,
+        name: random_string(rng),
+        u8: rng.gen(),
+        u16: rng.gen(),
+        u32: rng.gen(),
+        u128: rng.gen(),
+        i8: rng.gen(),
+        i16: rng.gen(),
+        i32: rng.gen(),
+        i64: rng.gen(),
+        i128: rng.gen(),
+        f32: random_f64(rng) as f32,
+        f64: random_f64(rng),
+        char: rng.gen(),
+        bytes: random_bytes(rng),
+        option: if rng.gen() {
+            Some(random_string(rng))
+        } else {
+            None
+        },
+        tuple: (rng.gen(), rng.gen(), random_string(rng)),
+        array: rng.gen(),
+        variant: random_variant(rng),
+        nested: (0..rng.gen_range(0..4))
+            .map(|_| Nested {
+                flag: rng.gen(),
+                values: (0..rng.gen_range(0..8)).map(|_| rng.gen()).collect(),
+            })
+            .collect(),
+        map: (0..rng.gen_range(0..4))
+            .map(|_| (random_string(rng), rng.gen()))
+            .collect(),
+        hash_map: (0..rng.gen_range(0..4))
+            .map(|_| (rng.gen(), if rng.gen() { Some(rng.gen()) } else { None }))
+            .collect(),
+        uuid: uuid::Uuid::from_u128(rng.gen()),
+    }
+}
+
+#[test]
+fn test_codec_roundtrip() {
+    let mut rng = StdRng::seed_from_u64(0x6e61_7469_7665_6462);
+    for id in 0..CASES as u64 {
+        let item = random_item(&mut rng, id);
+        let encoded = bincode_encode_to_vec(&item).unwrap();
+        let (decoded, _) = bincode_decode_from_slice::<Item>(&encoded).unwrap();
+        assert_eq!(decoded, item, "encoded: {:?}", encoded);
+    }
+}
+
+#[test]
+fn test_codec_roundtrip_database() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let mut rng = StdRng::seed_from_u64(0x726f_756e_6474_7269);
+    let items: Vec<Item> = (0..CASES as u64)
+        .map(|id| random_item(&mut rng, id))
+        .collect();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert_many(items.clone()).unwrap();
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let stored: Vec<Item> = r.scan().primary().unwrap().all().collect();
+    assert_eq!(stored, items);
+    for item in &items {
+        let value: Item = r.get().primary(item.id).unwrap().unwrap();
+        assert_eq!(&value, item);
+    }
+}
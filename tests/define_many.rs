@@ -0,0 +1,71 @@
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct User {
+    #[primary_key]
+    id: u32,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+#[native_model(id = 2, version = 1)]
+#[native_db]
+struct Order {
+    #[primary_key]
+    id: u32,
+    #[secondary_key]
+    user_id: u32,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+#[native_model(id = 3, version = 1)]
+#[native_db]
+struct Product {
+    #[primary_key]
+    id: u32,
+}
+
+fn primary_table_names(db: &Database) -> Vec<String> {
+    let mut names: Vec<String> = db
+        .redb_stats()
+        .unwrap()
+        .primary_tables
+        .into_iter()
+        .map(|table| table.name)
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_define_many() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define_many::<(User, Order, Product)>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    assert_eq!(primary_table_names(&db), vec!["1_1_id", "2_1_id", "3_1_id"]);
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(User { id: 1 }).unwrap();
+    rw.insert(Order { id: 1, user_id: 1 }).unwrap();
+    rw.insert(Product { id: 1 }).unwrap();
+    rw.commit().unwrap();
+}
+
+#[test]
+fn test_define_all() {
+    let mut builder = DatabaseBuilder::new();
+    define_all!(builder, User, Order, Product,).unwrap();
+    let db = builder.create_in_memory().unwrap();
+    assert_eq!(primary_table_names(&db), vec!["1_1_id", "2_1_id", "3_1_id"]);
+
+    // From a mutable reference
+    let mut builder = DatabaseBuilder::new();
+    let builder_ref = &mut builder;
+    define_all!(*builder_ref, User).unwrap();
+    let db = builder.create_in_memory().unwrap();
+    assert_eq!(primary_table_names(&db), vec!["1_1_id"]);
+}
@@ -0,0 +1,90 @@
+use native_db::db_type::{
+    DatabaseKeyDefinition, DatabasePrimaryKeyOptions, DatabaseSecondaryKeyOptions,
+};
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 1, version = 1)]
+#[native_db(primary_key(id_key), secondary_key(id_key))]
+struct Colliding {
+    id: u32,
+}
+
+impl Colliding {
+    fn id_key(&self) -> u32 {
+        self.id
+    }
+}
+
+fn model(primary_key: &'static str, secondary_keys: &[(u32, &'static str)]) -> DatabaseModel {
+    DatabaseModel {
+        primary_key: DatabaseKeyDefinition::new(
+            1,
+            1,
+            primary_key,
+            DatabasePrimaryKeyOptions::default(),
+        ),
+        secondary_keys: secondary_keys
+            .iter()
+            .map(|(model_id, name)| {
+                DatabaseKeyDefinition::new(
+                    *model_id,
+                    1,
+                    name,
+                    DatabaseSecondaryKeyOptions::default(),
+                )
+            })
+            .collect::<HashSet<_>>(),
+    }
+}
+
+fn is_invalid_schema(result: Result<(), db_type::Error>) -> bool {
+    matches!(result, Err(db_type::Error::InvalidSchema { .. }))
+}
+
+#[test]
+fn test_define_colliding_secondary_key() {
+    let mut builder = DatabaseBuilder::new();
+    let result = builder.define::<Colliding>();
+    assert!(is_invalid_schema(result));
+    // The model is not defined
+    let db = builder.create_in_memory().unwrap();
+    let rw = db.rw_transaction().unwrap();
+    assert!(rw.insert(Colliding { id: 1 }).is_err());
+}
+
+#[test]
+fn test_validate_valid_model() {
+    assert!(model("id", &[]).validate().is_ok());
+    assert!(model("id", &[(1, "name"), (1, "email")]).validate().is_ok());
+}
+
+#[test]
+fn test_validate_empty_primary_key_name() {
+    assert!(is_invalid_schema(model("", &[]).validate()));
+}
+
+#[test]
+fn test_validate_empty_secondary_key_name() {
+    assert!(is_invalid_schema(model("id", &[(1, "")]).validate()));
+}
+
+#[test]
+fn test_validate_secondary_key_same_as_primary_key() {
+    assert!(is_invalid_schema(model("id", &[(1, "id")]).validate()));
+}
+
+#[test]
+fn test_validate_duplicated_secondary_key() {
+    // The same name in another model: the tables are different but the key is defined twice.
+    let result = model("id", &[(1, "name"), (2, "name")]).validate();
+    assert!(is_invalid_schema(result));
+}
+
+#[test]
+fn test_validate_secondary_key_of_another_model() {
+    assert!(is_invalid_schema(model("id", &[(2, "name")]).validate()));
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "serde_json")]
+
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct User {
+    #[primary_key]
+    id: u32,
+    name: String,
+    tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[native_model(id = 2, version = 1)]
+#[native_db]
+struct Order {
+    #[primary_key]
+    id: u64,
+    amount: Option<u32>,
+}
+
+#[test]
+fn test_primary_json() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define_json::<User>("users").unwrap();
+    builder.define_json::<Order>("orders").unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(User {
+        id: 2,
+        name: "b".to_string(),
+        tags: vec![],
+    })
+    .unwrap();
+    rw.insert(User {
+        id: 1,
+        name: "a".to_string(),
+        tags: vec!["admin".to_string()],
+    })
+    .unwrap();
+    rw.insert(Order {
+        id: 1,
+        amount: None,
+    })
+    .unwrap();
+
+    // The read-write transaction sees its own writes
+    let values: Vec<serde_json::Value> = rw
+        .scan()
+        .primary_json("orders")
+        .unwrap()
+        .all()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(values, vec![json!({ "id": 1, "amount": null })]);
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let values: Vec<serde_json::Value> = r
+        .scan()
+        .primary_json("users")
+        .unwrap()
+        .all()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        values,
+        vec![
+            json!({ "id": 1, "name": "a", "tags": ["admin"] }),
+            json!({ "id": 2, "name": "b", "tags": [] }),
+        ]
+    );
+
+    let last = r.scan().primary_json("users").unwrap().all().next_back();
+    assert_eq!(last.unwrap().unwrap()["id"], json!(2));
+}
+
+#[test]
+fn test_primary_json_not_registered() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<User>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let result = r.scan().primary_json("users");
+    assert!(matches!(
+        result,
+        Err(db_type::Error::TableDefinitionNotFound { .. })
+    ));
+}
+
+#[test]
+fn test_define_json_duplicate_name() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define_json::<User>("data").unwrap();
+    builder.define_json::<Order>("data").unwrap();
+
+    assert!(matches!(
+        builder.create_in_memory(),
+        Err(db_type::Error::InvalidConfiguration { .. })
+    ));
+}
@@ -26,3 +26,102 @@ fn test_insert_my_item() {
     let key = item.native_db_primary_key();
     assert_eq!(key, "1-test".database_inner_key_value());
 }
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[native_model(id = 2, version = 1)]
+#[native_db(primary_key(compute_primary_key, check_len = 4))]
+struct ItemFixedLen {
+    id: u32,
+    name: String,
+}
+
+impl ItemFixedLen {
+    pub fn compute_primary_key(&self) -> String {
+        self.name.clone()
+    }
+}
+
+#[test]
+fn test_insert_check_len() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemFixedLen>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemFixedLen {
+        id: 1,
+        name: "abcd".to_string(),
+    })
+    .unwrap();
+    let result = rw.insert(ItemFixedLen {
+        id: 2,
+        name: "abc".to_string(),
+    });
+    assert!(matches!(
+        result,
+        Err(db_type::Error::KeyLengthMismatch {
+            expected: 4,
+            got: 3
+        })
+    ));
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<ItemFixedLen>().unwrap(), 1);
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[native_model(id = 3, version = 1)]
+#[native_db(primary_key(compute_primary_key))]
+struct ItemName {
+    id: u32,
+    name: String,
+}
+
+impl ItemName {
+    pub fn compute_primary_key(&self) -> String {
+        self.name.clone()
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[native_model(id = 4, version = 1)]
+#[native_db(primary_key(compute_primary_key, allow_empty))]
+struct ItemNameAllowEmpty {
+    id: u32,
+    name: String,
+}
+
+impl ItemNameAllowEmpty {
+    pub fn compute_primary_key(&self) -> String {
+        self.name.clone()
+    }
+}
+
+#[test]
+fn test_insert_empty_key() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemName>().unwrap();
+    builder.define::<ItemNameAllowEmpty>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    let result = rw.insert(ItemName {
+        id: 1,
+        name: "".to_string(),
+    });
+    assert!(matches!(
+        result,
+        Err(db_type::Error::EmptyPrimaryKey { .. })
+    ));
+    rw.insert(ItemNameAllowEmpty {
+        id: 1,
+        name: "".to_string(),
+    })
+    .unwrap();
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<ItemName>().unwrap(), 0);
+    assert_eq!(r.len().primary::<ItemNameAllowEmpty>().unwrap(), 1);
+}
@@ -1,3 +1,4 @@
+use native_db::db_type::PrimaryKeySource;
 use native_db::*;
 use native_model::{native_model, Model};
 use serde::{Deserialize, Serialize};
@@ -22,3 +23,89 @@ fn test_insert_my_item() {
     let key = item.native_db_primary_key();
     assert_eq!(key, 1_u32.database_inner_key_value());
 }
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[native_model(id = 2, version = 1)]
+#[native_db]
+struct ItemFixedLen {
+    #[primary_key(check_len = 8)]
+    id: u64,
+}
+
+#[test]
+fn test_check_len_option() {
+    let model = ItemFixedLen::native_db_model();
+    assert_eq!(model.primary_key.options().check_len, Some(8));
+}
+
+#[test]
+fn test_key_source() {
+    let item = Item {
+        id: 1,
+        name: "test".to_string(),
+    };
+    let key = Item::native_db_key_source_primary_key(&ItemKeySource { id: 1 });
+    assert_eq!(key, item.native_db_primary_key());
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(item).unwrap();
+    let result: Option<Item> = rw
+        .get()
+        .primary_by::<Item>(&ItemKeySource { id: 1 })
+        .unwrap();
+    assert!(result.is_some());
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let result: Item = r
+        .get()
+        .primary_by::<Item>(&ItemKeySource { id: 1 })
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.name, "test");
+    let result: Option<Item> = r
+        .get()
+        .primary_by::<Item>(&ItemKeySource { id: 2 })
+        .unwrap();
+    assert!(result.is_none());
+}
+
+#[test]
+fn test_check_len_watch() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemFixedLen>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let result = db.watch().get().primary::<ItemFixedLen>(1u32);
+    assert!(matches!(
+        result,
+        Err(db_type::Error::KeyLengthMismatch {
+            expected: 8,
+            got: 4
+        })
+    ));
+    assert!(db.watch().get().primary::<ItemFixedLen>(1u64).is_ok());
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[native_model(id = 3, version = 1)]
+#[native_db]
+struct ItemAllowEmpty {
+    #[primary_key(allow_empty)]
+    name: String,
+}
+
+#[test]
+fn test_allow_empty_option() {
+    assert!(
+        ItemAllowEmpty::native_db_model()
+            .primary_key
+            .options()
+            .allow_empty
+    );
+    assert!(!Item::native_db_model().primary_key.options().allow_empty);
+}
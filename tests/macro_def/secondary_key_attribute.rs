@@ -172,3 +172,34 @@ fn test_secondary_others() {
         &DatabaseKeyValue::Default("test2".database_inner_key_value())
     );
 }
+
+#[test]
+fn test_secondary_indexes() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemSecondaryOthers>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let indexes = db.secondary_indexes::<ItemSecondaryOthers>().unwrap();
+    let names: Vec<&str> = indexes.iter().map(|key| key.name()).collect();
+    assert_eq!(names, vec!["name", "name2"]);
+    assert!(indexes[0].options().unique);
+    assert!(!indexes[1].options().unique);
+
+    // The definitions drive the secondary queries.
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemSecondaryOthers {
+        id: 1,
+        name: "test".to_string(),
+        name2: "test2".to_string(),
+    })
+    .unwrap();
+    let item: Option<ItemSecondaryOthers> = rw.get().secondary(indexes[0].clone(), "test").unwrap();
+    assert_eq!(item.unwrap().id, 1);
+    rw.commit().unwrap();
+
+    let result = db.secondary_indexes::<ItemSecondary>();
+    assert!(matches!(
+        result,
+        Err(db_type::Error::TableDefinitionNotFound { .. })
+    ));
+}
@@ -0,0 +1,75 @@
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+use shortcut_assert_fs::TmpFs;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct ItemWithoutIndex {
+    #[primary_key]
+    id: u32,
+    name: String,
+}
+
+// Same model with a new secondary key
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct Item {
+    #[primary_key]
+    id: u32,
+    #[secondary_key]
+    name: String,
+}
+
+#[test]
+fn test_backfill_secondary_index() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemWithoutIndex>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+    let rw = db.rw_transaction().unwrap();
+    for id in 0..5 {
+        rw.insert(ItemWithoutIndex {
+            id,
+            name: format!("name_{}", id % 2),
+        })
+        .unwrap();
+    }
+    rw.commit().unwrap();
+    drop(db);
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.open(tf.path("test").as_std_path()).unwrap();
+
+    let count_name_0 = |db: &Database| {
+        let r = db.r_transaction().unwrap();
+        let items: Vec<Item> = r
+            .scan()
+            .secondary(ItemKey::name)
+            .unwrap()
+            .start_with("name_0")
+            .collect();
+        items.len()
+    };
+    assert_eq!(count_name_0(&db), 0);
+
+    let scanned = db
+        .backfill_secondary_index::<Item>(ItemKey::name, 2)
+        .unwrap();
+    assert_eq!(scanned, 5);
+    assert_eq!(count_name_0(&db), 3);
+
+    // Resume: the values already indexed are unchanged
+    let scanned = db
+        .backfill_secondary_index::<Item>(ItemKey::name, 2)
+        .unwrap();
+    assert_eq!(scanned, 5);
+    assert_eq!(count_name_0(&db), 3);
+
+    let stats = db.redb_stats().unwrap();
+    assert_eq!(stats.secondary_tables[0].n_entries, Some(5));
+}
@@ -0,0 +1,123 @@
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct ItemV1 {
+    #[primary_key]
+    id: u32,
+    name: String,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 2, version = 1)]
+#[native_db]
+struct ItemV2 {
+    #[primary_key]
+    id: u32,
+    #[secondary_key(unique)]
+    name: String,
+}
+
+#[test]
+fn test_migrate_dry_run() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemV1>().unwrap();
+    builder.define::<ItemV2>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for id in 0..5 {
+        rw.insert(ItemV1 {
+            id,
+            name: format!("name_{}", id),
+        })
+        .unwrap();
+    }
+    rw.commit().unwrap();
+
+    // Valid migration
+    let report = db
+        .migrate_dry_run::<ItemV1, ItemV2, _, String>(|item| {
+            Ok(ItemV2 {
+                id: item.id,
+                name: item.name,
+            })
+        })
+        .unwrap();
+    assert!(report.is_ok());
+    assert_eq!(report.total, 5);
+    assert_eq!(report.changed, 5);
+
+    // The mapping fails for the id 1 and the unique secondary key collides for the ids 3 and 4
+    let report = db
+        .migrate_dry_run::<ItemV1, ItemV2, _, String>(|item| {
+            if item.id == 1 {
+                return Err("invalid item".to_string());
+            }
+            let name = if item.id >= 2 {
+                "same".to_string()
+            } else {
+                item.name
+            };
+            Ok(ItemV2 { id: item.id, name })
+        })
+        .unwrap();
+    assert!(!report.is_ok());
+    assert_eq!(report.total, 5);
+    assert_eq!(report.changed, 2);
+    assert_eq!(report.failures.len(), 3);
+    assert!(matches!(
+        &report.failures[0].error,
+        db_type::Error::MigrationFailed(message) if message == "invalid item"
+    ));
+    assert_eq!(
+        report.failures[0].primary_key,
+        1u32.database_inner_key_value()
+    );
+    assert!(matches!(
+        report.failures[1].error,
+        db_type::Error::DuplicateKey { .. }
+    ));
+    assert_eq!(
+        report.failures[1].primary_key,
+        3u32.database_inner_key_value()
+    );
+
+    // Nothing is written
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<ItemV1>().unwrap(), 5);
+    assert_eq!(r.len().primary::<ItemV2>().unwrap(), 0);
+}
+
+#[test]
+fn test_migrate_dry_run_same_model() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemV1>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemV1 {
+        id: 1,
+        name: "a".to_string(),
+    })
+    .unwrap();
+    rw.insert(ItemV1 {
+        id: 2,
+        name: "B".to_string(),
+    })
+    .unwrap();
+    rw.commit().unwrap();
+
+    let report = db
+        .migrate_dry_run::<ItemV1, ItemV1, _, String>(|mut item| {
+            item.name = item.name.to_uppercase();
+            Ok(item)
+        })
+        .unwrap();
+    assert!(report.is_ok());
+    assert_eq!(report.total, 2);
+    assert_eq!(report.changed, 1);
+}
@@ -0,0 +1,169 @@
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct ItemV1 {
+    #[primary_key]
+    id: u32,
+    #[secondary_key(unique)]
+    name: String,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[native_model(id = 1, version = 2, from = ItemV1)]
+#[native_db]
+struct ItemV2 {
+    #[primary_key]
+    id: u32,
+    #[secondary_key(unique)]
+    name: String,
+    count: u32,
+}
+
+impl From<ItemV1> for ItemV2 {
+    fn from(item: ItemV1) -> Self {
+        ItemV2 {
+            id: item.id,
+            name: item.name,
+            count: 0,
+        }
+    }
+}
+
+impl From<ItemV2> for ItemV1 {
+    fn from(item: ItemV2) -> Self {
+        ItemV1 {
+            id: item.id,
+            name: item.name,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[native_model(id = 1, version = 3, from = ItemV2)]
+#[native_db]
+struct ItemV3 {
+    #[primary_key]
+    id: u32,
+    #[secondary_key(unique)]
+    name: String,
+    count: u64,
+}
+
+impl From<ItemV2> for ItemV3 {
+    fn from(item: ItemV2) -> Self {
+        ItemV3 {
+            id: item.id,
+            name: item.name,
+            count: item.count as u64,
+        }
+    }
+}
+
+impl From<ItemV3> for ItemV2 {
+    fn from(item: ItemV3) -> Self {
+        ItemV2 {
+            id: item.id,
+            name: item.name,
+            count: item.count as u32,
+        }
+    }
+}
+
+#[test]
+fn test_primary_upgrade() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemV1>().unwrap();
+    builder.define::<ItemV2>().unwrap();
+    builder.define::<ItemV3>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemV1 {
+        id: 1,
+        name: "a".to_string(),
+    })
+    .unwrap();
+    rw.insert(ItemV2 {
+        id: 2,
+        name: "b".to_string(),
+        count: 2,
+    })
+    .unwrap();
+    rw.insert(ItemV3 {
+        id: 3,
+        name: "c".to_string(),
+        count: 3,
+    })
+    .unwrap();
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    // Upgraded from the version 1 through the version 2
+    let item: ItemV3 = r.get().primary_upgrade(1u32).unwrap().unwrap();
+    assert_eq!(
+        item,
+        ItemV3 {
+            id: 1,
+            name: "a".to_string(),
+            count: 0,
+        }
+    );
+    let item: ItemV3 = r.get().primary_upgrade(2u32).unwrap().unwrap();
+    assert_eq!(item.count, 2);
+    let item: ItemV3 = r.get().primary_upgrade(3u32).unwrap().unwrap();
+    assert_eq!(item.count, 3);
+    let item: Option<ItemV3> = r.get().primary_upgrade(4u32).unwrap();
+    assert_eq!(item, None);
+
+    // An older version is not searched in the newer tables
+    let item: Option<ItemV2> = r.get().primary_upgrade(3u32).unwrap();
+    assert_eq!(item, None);
+
+    // Nothing is written back
+    assert_eq!(r.len().primary::<ItemV1>().unwrap(), 1);
+    assert_eq!(r.len().primary::<ItemV3>().unwrap(), 1);
+}
+
+#[test]
+fn test_upgrade() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemV1>().unwrap();
+    builder.define::<ItemV2>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemV1 {
+        id: 1,
+        name: "a".to_string(),
+    })
+    .unwrap();
+    rw.commit().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    let item = rw.upgrade::<ItemV1, ItemV2>(1u32).unwrap();
+    assert_eq!(
+        item,
+        Some(ItemV2 {
+            id: 1,
+            name: "a".to_string(),
+            count: 0,
+        })
+    );
+    // Already upgraded
+    let item = rw.upgrade::<ItemV1, ItemV2>(1u32).unwrap();
+    assert_eq!(item.unwrap().id, 1);
+    let item = rw.upgrade::<ItemV1, ItemV2>(2u32).unwrap();
+    assert_eq!(item, None);
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<ItemV1>().unwrap(), 0);
+    let item: Option<ItemV1> = r.get().secondary(ItemV1Key::name, "a").unwrap();
+    assert_eq!(item, None);
+    let item: ItemV2 = r.get().secondary(ItemV2Key::name, "a").unwrap().unwrap();
+    assert_eq!(item.id, 1);
+}
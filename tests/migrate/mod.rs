@@ -1,2 +1,7 @@
+mod backfill_secondary;
+mod dry_run;
+mod lazy_upgrade;
 mod only_primary_key;
+mod read_as;
+mod rebuild_secondary;
 mod with_secondary_keys;
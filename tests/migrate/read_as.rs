@@ -0,0 +1,118 @@
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct ItemV1 {
+    #[primary_key]
+    id: u32,
+    name: String,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[native_model(id = 1, version = 2, from = ItemV1)]
+#[native_db]
+struct ItemV2 {
+    #[primary_key]
+    id: u32,
+    name: String,
+    #[serde(default)]
+    count: u64,
+}
+
+impl From<ItemV1> for ItemV2 {
+    fn from(item: ItemV1) -> Self {
+        ItemV2 {
+            id: item.id,
+            name: item.name,
+            count: 0,
+        }
+    }
+}
+
+impl From<ItemV2> for ItemV1 {
+    fn from(item: ItemV2) -> Self {
+        ItemV1 {
+            id: item.id,
+            name: item.name,
+        }
+    }
+}
+
+#[test]
+fn test_read_as_new_version() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemV1>().unwrap();
+    builder.define::<ItemV2>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    // Written by the old code
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemV1 {
+        id: 1,
+        name: "old".to_string(),
+    })
+    .unwrap();
+    rw.commit().unwrap();
+
+    // Read by the new code
+    let r = db.r_transaction().unwrap();
+    let item: ItemV2 = r.get().primary_as::<ItemV1, ItemV2>(1u32).unwrap().unwrap();
+    assert_eq!(
+        item,
+        ItemV2 {
+            id: 1,
+            name: "old".to_string(),
+            count: 0,
+        }
+    );
+    // The table of the new version is empty
+    let item: Option<ItemV2> = r.get().primary(1u32).unwrap();
+    assert!(item.is_none());
+}
+
+#[test]
+fn test_read_as_old_version() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemV1>().unwrap();
+    builder.define::<ItemV2>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemV2 {
+        id: 1,
+        name: "new".to_string(),
+        count: 5,
+    })
+    .unwrap();
+    let item: ItemV1 = rw
+        .get()
+        .primary_as::<ItemV2, ItemV1>(1u32)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        item,
+        ItemV1 {
+            id: 1,
+            name: "new".to_string(),
+        }
+    );
+    rw.commit().unwrap();
+}
+
+#[test]
+fn test_bincode_not_self_describing() {
+    // The default codec is not self-describing, `#[serde(default)]` does not allow to decode
+    // an item encoded without the field: a new version of the model is required.
+    let old = ItemV1 {
+        id: 1,
+        name: "old".to_string(),
+    };
+    use native_model::{Decode, Encode};
+
+    let encoded = native_model::bincode_1_3::Bincode::encode(&old).unwrap();
+    let decoded: Result<ItemV2, _> = native_model::bincode_1_3::Bincode::decode(encoded);
+    assert!(decoded.is_err());
+}
@@ -0,0 +1,87 @@
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+use shortcut_assert_fs::TmpFs;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct ItemWithoutIndex {
+    #[primary_key]
+    id: u32,
+    name: String,
+}
+
+// Same model with a secondary key
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct Item {
+    #[primary_key]
+    id: u32,
+    #[secondary_key]
+    name: String,
+}
+
+#[test]
+fn test_rebuild_secondary_index() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+    let rw = db.rw_transaction().unwrap();
+    for id in 0..4 {
+        rw.insert(Item {
+            id,
+            name: format!("old_{}", id),
+        })
+        .unwrap();
+    }
+    rw.commit().unwrap();
+    drop(db);
+
+    // Rename the items without updating the index
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemWithoutIndex>().unwrap();
+    let db = builder.open(tf.path("test").as_std_path()).unwrap();
+    let rw = db.rw_transaction().unwrap();
+    for id in 0..3 {
+        rw.insert(ItemWithoutIndex {
+            id,
+            name: format!("new_{}", id),
+        })
+        .unwrap();
+    }
+    rw.remove(ItemWithoutIndex {
+        id: 3,
+        name: "old_3".to_string(),
+    })
+    .unwrap();
+    rw.commit().unwrap();
+    drop(db);
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.open(tf.path("test").as_std_path()).unwrap();
+
+    let count = |db: &Database, prefix: &str| {
+        let r = db.r_transaction().unwrap();
+        let items: Vec<Item> = r
+            .scan()
+            .secondary(ItemKey::name)
+            .unwrap()
+            .start_with(prefix)
+            .collect();
+        items.len()
+    };
+    assert_eq!(count(&db, "new_"), 0);
+
+    let written = db.rebuild_secondary_index::<Item>(ItemKey::name).unwrap();
+    assert_eq!(written, 3);
+    assert_eq!(count(&db, "new_"), 3);
+    assert_eq!(count(&db, "old_"), 0);
+
+    let stats = db.redb_stats().unwrap();
+    assert_eq!(stats.secondary_tables[0].n_entries, Some(3));
+}
@@ -3,7 +3,7 @@ use native_model::{native_model, Model};
 use serde::{Deserialize, Serialize};
 use shortcut_assert_fs::TmpFs;
 
-#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug, Default)]
 #[native_model(id = 1, version = 1)]
 #[native_db]
 struct Item {
@@ -33,6 +33,28 @@ fn insert_get() {
     assert_eq!(item, result_item);
 }
 
+#[test]
+fn insert_returns_primary_key() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    let key = rw
+        .insert(Item {
+            id: 1,
+            name: "test".to_string(),
+        })
+        .unwrap();
+    rw.commit().unwrap();
+    assert_eq!(key, 1u32.database_inner_key_value());
+    assert_eq!(key.as_slice(), &1u32.to_be_bytes());
+
+    let r = db.r_transaction().unwrap();
+    let result_item: Item = r.get().primary(key).unwrap().unwrap();
+    assert_eq!(result_item.id, 1);
+}
+
 // TODO: insert should fail if the primary key already exists
 //       add a patch method to tables to allow for this
 #[ignore]
@@ -58,3 +80,70 @@ fn test_insert_duplicate_key() {
         db_type::Error::DuplicateKey { .. }
     ));
 }
+
+#[test]
+fn get_primary_or_default() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let item = Item {
+        id: 1,
+        name: "test".to_string(),
+    };
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(item.clone()).unwrap();
+    let result: Item = rw.get().primary_or_default(2u32).unwrap();
+    assert_eq!(result, Item::default());
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let result: Item = r.get().primary_or_default(1u32).unwrap();
+    assert_eq!(result, item);
+    let result: Item = r.get().primary_or_default(2u32).unwrap();
+    assert_eq!(result, Item::default());
+    // Nothing is inserted
+    assert_eq!(r.len().primary::<Item>().unwrap(), 1);
+}
+
+#[test]
+fn get_primary_many() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let item_1 = Item {
+        id: 1,
+        name: "a".to_string(),
+    };
+    let item_3 = Item {
+        id: 3,
+        name: "c".to_string(),
+    };
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(item_1.clone()).unwrap();
+    rw.insert(item_3.clone()).unwrap();
+
+    // Read your writes
+    let values: Vec<Option<Item>> = rw.get().primary_many(vec![3u32, 2]).unwrap();
+    assert_eq!(values, vec![Some(item_3.clone()), None]);
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let values: Vec<Option<Item>> = r.get().primary_many(vec![1u32, 2, 3]).unwrap();
+    assert_eq!(
+        values,
+        vec![Some(item_1.clone()), None, Some(item_3.clone())]
+    );
+
+    let values = r
+        .get()
+        .primary_many_map::<Item, _>(vec![1u32, 2, 3, 4])
+        .unwrap();
+    assert_eq!(values.len(), 2);
+    assert_eq!(values[1u32.database_inner_key_value().as_slice()], item_1);
+    assert_eq!(values[3u32.database_inner_key_value().as_slice()], item_3);
+
+    let values = r.get().primary_many_map::<Item, u32>(Vec::new()).unwrap();
+    assert!(values.is_empty());
+}
@@ -153,3 +153,343 @@ fn test_insert_optional() {
         .unwrap();
     assert_eq!(item_1, result_item);
 }
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct ItemSparse {
+    #[primary_key]
+    id: u32,
+    #[secondary_key(unique, sparse)]
+    name: String,
+}
+
+#[test]
+fn test_insert_sparse() {
+    let item_1 = ItemSparse {
+        id: 1,
+        name: "test".to_string(),
+    };
+    let item_2 = ItemSparse {
+        id: 2,
+        name: "".to_string(),
+    };
+    let item_3 = ItemSparse {
+        id: 3,
+        name: "".to_string(),
+    };
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemSparse>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(item_1.clone()).unwrap();
+    // Empty keys are not indexed, so they are not duplicates.
+    rw.insert(item_2.clone()).unwrap();
+    rw.insert(item_3.clone()).unwrap();
+    rw.commit().unwrap();
+
+    let stats = db.redb_stats().unwrap();
+    assert_eq!(stats.primary_tables[0].n_entries, Some(3));
+    assert_eq!(stats.secondary_tables[0].n_entries, Some(1));
+
+    let r = db.r_transaction().unwrap();
+    let result: Vec<ItemSparse> = r
+        .scan()
+        .secondary(ItemSparseKey::name)
+        .unwrap()
+        .all()
+        .collect();
+    assert_eq!(result, vec![item_1.clone()]);
+
+    // Update from an empty key to a non-empty key and remove.
+    let rw = db.rw_transaction().unwrap();
+    rw.update(
+        item_2.clone(),
+        ItemSparse {
+            id: 2,
+            name: "test2".to_string(),
+        },
+    )
+    .unwrap();
+    rw.remove(item_3).unwrap();
+    rw.commit().unwrap();
+
+    let stats = db.redb_stats().unwrap();
+    assert_eq!(stats.primary_tables[0].n_entries, Some(2));
+    assert_eq!(stats.secondary_tables[0].n_entries, Some(2));
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct ItemRole {
+    #[primary_key]
+    id: u32,
+    #[secondary_key]
+    role: String,
+    #[secondary_key(unique)]
+    name: String,
+}
+
+#[test]
+fn test_secondary_exists() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemRole>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let admin = ItemRole {
+        id: 1,
+        role: "admin".to_string(),
+        name: "alice".to_string(),
+    };
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(admin.clone()).unwrap();
+    rw.insert(ItemRole {
+        id: 2,
+        role: "user".to_string(),
+        name: "bob".to_string(),
+    })
+    .unwrap();
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let get = r.get();
+    assert!(get
+        .secondary_exists::<ItemRole>(ItemRoleKey::role, "admin")
+        .unwrap());
+    // A prefix of an existing key is not a match.
+    assert!(!get
+        .secondary_exists::<ItemRole>(ItemRoleKey::role, "ad")
+        .unwrap());
+    assert!(!get
+        .secondary_exists::<ItemRole>(ItemRoleKey::role, "guest")
+        .unwrap());
+    assert!(get
+        .secondary_exists::<ItemRole>(ItemRoleKey::name, "alice")
+        .unwrap());
+    assert!(!get
+        .secondary_exists::<ItemRole>(ItemRoleKey::name, "carol")
+        .unwrap());
+    drop(r);
+
+    // "Can't remove the last admin"
+    let rw = db.rw_transaction().unwrap();
+    rw.remove(admin).unwrap();
+    assert!(!rw
+        .get()
+        .secondary_exists::<ItemRole>(ItemRoleKey::role, "admin")
+        .unwrap());
+}
+
+#[test]
+fn test_secondary_primary_keys() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemRole>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for (id, role, name) in [
+        (3, "admin", "carol"),
+        (1, "admin", "alice"),
+        (2, "user", "bob"),
+        (4, "administrator", "dave"),
+    ] {
+        rw.insert(ItemRole {
+            id,
+            role: role.to_string(),
+            name: name.to_string(),
+        })
+        .unwrap();
+    }
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let keys = r
+        .get()
+        .secondary_primary_keys::<ItemRole>(ItemRoleKey::role, "admin")
+        .unwrap();
+    assert_eq!(
+        keys,
+        vec![
+            1u32.database_inner_key_value().as_slice().to_vec(),
+            3u32.database_inner_key_value().as_slice().to_vec(),
+        ]
+    );
+    let keys = r
+        .get()
+        .secondary_primary_keys::<ItemRole>(ItemRoleKey::name, "bob")
+        .unwrap();
+    assert_eq!(
+        keys,
+        vec![2u32.database_inner_key_value().as_slice().to_vec()]
+    );
+    assert!(r
+        .get()
+        .secondary_primary_keys::<ItemRole>(ItemRoleKey::role, "guest")
+        .unwrap()
+        .is_empty());
+    drop(r);
+
+    let rw = db.rw_transaction().unwrap();
+    let keys = rw
+        .get()
+        .secondary_primary_keys::<ItemRole>(ItemRoleKey::role, "user")
+        .unwrap();
+    assert_eq!(keys.len(), 1);
+}
+
+#[test]
+fn test_secondary_unique() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemRole>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let bob = ItemRole {
+        id: 2,
+        role: "user".to_string(),
+        name: "bob".to_string(),
+    };
+    let rw = db.rw_transaction().unwrap();
+    for (id, role, name) in [(1, "admin", "alice"), (3, "admin", "carol")] {
+        rw.insert(ItemRole {
+            id,
+            role: role.to_string(),
+            name: name.to_string(),
+        })
+        .unwrap();
+    }
+    rw.insert(bob.clone()).unwrap();
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    // Non-unique secondary key with a single value
+    let item: Option<ItemRole> = r.get().secondary_unique(ItemRoleKey::role, "user").unwrap();
+    assert_eq!(item, Some(bob.clone()));
+    // Non-unique secondary key with many values
+    let result = r
+        .get()
+        .secondary_unique::<ItemRole>(ItemRoleKey::role, "admin");
+    match result {
+        Err(db_type::Error::NonUniqueSecondaryKey { key_name, count }) => {
+            assert_eq!(key_name, "1_1_role");
+            assert_eq!(count, 2);
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+    let item: Option<ItemRole> = r
+        .get()
+        .secondary_unique(ItemRoleKey::role, "guest")
+        .unwrap();
+    assert_eq!(item, None);
+    // Unique secondary key
+    let item: Option<ItemRole> = r.get().secondary_unique(ItemRoleKey::name, "bob").unwrap();
+    assert_eq!(item, Some(bob.clone()));
+    drop(r);
+
+    let rw = db.rw_transaction().unwrap();
+    let item: Option<ItemRole> = rw
+        .get()
+        .secondary_unique(ItemRoleKey::role, "user")
+        .unwrap();
+    assert_eq!(item, Some(bob));
+}
+
+#[test]
+fn test_secondary_first() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemRole>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for (id, role, name) in [
+        (3, "admin", "carol"),
+        (2, "admin", "bob"),
+        (1, "administrator", "alice"),
+    ] {
+        rw.insert(ItemRole {
+            id,
+            role: role.to_string(),
+            name: name.to_string(),
+        })
+        .unwrap();
+    }
+    // Read your writes
+    let first: ItemRole = rw
+        .get()
+        .secondary_first(ItemRoleKey::role, "admin")
+        .unwrap()
+        .unwrap();
+    assert_eq!(first.id, 2);
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let get = r.get();
+    // The smallest primary key, not the first inserted
+    let first: ItemRole = get
+        .secondary_first(ItemRoleKey::role, "admin")
+        .unwrap()
+        .unwrap();
+    assert_eq!(first.id, 2);
+    // A prefix of an existing key is not a match.
+    assert!(get
+        .secondary_first::<ItemRole>(ItemRoleKey::role, "ad")
+        .unwrap()
+        .is_none());
+    let first: ItemRole = get
+        .secondary_first(ItemRoleKey::name, "carol")
+        .unwrap()
+        .unwrap();
+    assert_eq!(first.id, 3);
+}
+
+#[test]
+fn test_secondary_group_counts() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemRole>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    // The key `a` + 0x63000000 (`ac...`) is after the key `ab` + 1.
+    for (id, role, name) in [
+        (2, "a", "bob"),
+        (1, "ab", "alice"),
+        (0x6300_0000, "a", "carol"),
+    ] {
+        rw.insert(ItemRole {
+            id,
+            role: role.to_string(),
+            name: name.to_string(),
+        })
+        .unwrap();
+    }
+    // Read your writes
+    let counts = rw
+        .len()
+        .secondary_group_counts::<ItemRole>(ItemRoleKey::role)
+        .unwrap();
+    assert_eq!(counts, vec![(b"a".to_vec(), 2), (b"ab".to_vec(), 1)]);
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let counts = r
+        .len()
+        .secondary_group_counts::<ItemRole>(ItemRoleKey::role)
+        .unwrap();
+    assert_eq!(counts, vec![(b"a".to_vec(), 2), (b"ab".to_vec(), 1)]);
+
+    // Unique secondary key
+    let counts = r
+        .len()
+        .secondary_group_counts::<ItemRole>(ItemRoleKey::name)
+        .unwrap();
+    assert_eq!(
+        counts,
+        vec![
+            (b"alice".to_vec(), 1),
+            (b"bob".to_vec(), 1),
+            (b"carol".to_vec(), 1)
+        ]
+    );
+}
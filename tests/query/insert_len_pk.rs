@@ -46,3 +46,92 @@ fn insert_len_read_transaction() {
     let result_item = r.len().primary::<Item>().unwrap();
     assert_eq!(2, result_item);
 }
+
+#[test]
+fn insert_len_range() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for id in 1..=10 {
+        rw.insert(Item {
+            id,
+            name: "test".to_string(),
+        })
+        .unwrap();
+    }
+    assert_eq!(rw.len().primary_range::<Item, _, _>(3u32..5u32).unwrap(), 2);
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let len = r.len();
+    assert_eq!(len.primary_range::<Item, _, _>(3u32..5u32).unwrap(), 2);
+    assert_eq!(len.primary_range::<Item, _, _>(3u32..=5u32).unwrap(), 3);
+    assert_eq!(len.primary_range::<Item, _, _>(..5u32).unwrap(), 4);
+    assert_eq!(len.primary_range::<Item, _, _>(8u32..).unwrap(), 3);
+    assert_eq!(len.primary_range::<Item, u32, _>(..).unwrap(), 10);
+    assert_eq!(len.primary_range::<Item, _, _>(20u32..30u32).unwrap(), 0);
+
+    // Same bounds as the scan
+    let scanned = r
+        .scan()
+        .primary::<Item>()
+        .unwrap()
+        .range(3u32..=5u32)
+        .count();
+    assert_eq!(scanned, 3);
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+#[native_model(id = 2, version = 1)]
+#[native_db]
+struct Item2 {
+    #[primary_key]
+    id: u32,
+}
+
+#[test]
+fn insert_len_all() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    builder.define::<Item2>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for id in 0..3 {
+        rw.insert(Item {
+            id,
+            name: "test".to_string(),
+        })
+        .unwrap();
+    }
+    rw.insert(Item2 { id: 1 }).unwrap();
+    rw.commit().unwrap();
+
+    let item_table = Item::native_db_model()
+        .primary_key
+        .unique_table_name()
+        .to_string();
+    let item_2_table = Item2::native_db_model()
+        .primary_key
+        .unique_table_name()
+        .to_string();
+
+    let r = db.r_transaction().unwrap();
+
+    // Written after the read transaction is opened: not counted
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Item2 { id: 2 }).unwrap();
+    rw.commit().unwrap();
+
+    let counts = r.len().all().unwrap();
+    assert_eq!(counts.len(), 2);
+    assert_eq!(counts[&item_table], 3);
+    assert_eq!(counts[&item_2_table], 1);
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Item2 { id: 3 }).unwrap();
+    let counts = rw.len().all().unwrap();
+    assert_eq!(counts[&item_2_table], 3);
+}
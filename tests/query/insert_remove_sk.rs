@@ -52,6 +52,131 @@ fn insert_remove() {
     assert_eq!(stats.secondary_tables[0].n_entries, Some(0));
 }
 
+#[test]
+fn insert_remove_range() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for id in 1..=5u32 {
+        rw.insert(Item {
+            id,
+            name: format!("test_{}", id),
+        })
+        .unwrap();
+    }
+    rw.commit().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    let removed = rw.remove_range::<Item, _, _>(..3u32).unwrap();
+    assert_eq!(removed, 2);
+    let removed = rw.remove_range::<Item, _, _>(10u32..).unwrap();
+    assert_eq!(removed, 0);
+    rw.commit().unwrap();
+
+    let stats = db.redb_stats().unwrap();
+    assert_eq!(stats.primary_tables[0].n_entries, Some(3));
+    assert_eq!(stats.secondary_tables[0].n_entries, Some(3));
+
+    let r = db.r_transaction().unwrap();
+    let ids: Vec<u32> = r
+        .scan()
+        .primary()
+        .unwrap()
+        .all()
+        .map(|item: Item| item.id)
+        .collect();
+    assert_eq!(ids, vec![3, 4, 5]);
+}
+
+#[test]
+fn insert_remove_by_key() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let item = Item {
+        id: 1,
+        name: "test".to_string(),
+    };
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(item.clone()).unwrap();
+    rw.commit().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    let old_value: Option<Item> = rw.remove_by_key(1u32).unwrap();
+    assert_eq!(old_value, Some(item));
+    let old_value: Option<Item> = rw.remove_by_key(1u32).unwrap();
+    assert_eq!(old_value, None);
+    rw.commit().unwrap();
+
+    let stats = db.redb_stats().unwrap();
+    assert_eq!(stats.primary_tables[0].n_entries, Some(0));
+    assert_eq!(stats.secondary_tables[0].n_entries, Some(0));
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct Order {
+    #[primary_key]
+    id: u32,
+    #[secondary_key]
+    created_at: u64,
+    #[secondary_key(unique)]
+    reference: String,
+}
+
+#[test]
+fn insert_remove_secondary_range() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Order>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for (id, created_at) in [(1u32, 300u64), (2, 100), (3, 100), (4, 200), (5, 400)] {
+        rw.insert(Order {
+            id,
+            created_at,
+            reference: format!("ref_{}", id),
+        })
+        .unwrap();
+    }
+    rw.commit().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    let removed = rw
+        .remove_secondary_range::<Order, _, _>(OrderKey::created_at, ..250u64)
+        .unwrap();
+    assert_eq!(removed, 3);
+    let removed = rw
+        .remove_secondary_range::<Order, _, _>(OrderKey::created_at, 1000u64..)
+        .unwrap();
+    assert_eq!(removed, 0);
+    rw.commit().unwrap();
+
+    // All the secondary keys of the removed values are removed
+    let stats = db.redb_stats().unwrap();
+    assert_eq!(stats.primary_tables[0].n_entries, Some(2));
+    for secondary_table in stats.secondary_tables {
+        assert_eq!(secondary_table.n_entries, Some(2));
+    }
+
+    let r = db.r_transaction().unwrap();
+    let ids: Vec<u32> = r
+        .scan()
+        .primary()
+        .unwrap()
+        .all()
+        .map(|order: Order| order.id)
+        .collect();
+    assert_eq!(ids, vec![1, 5]);
+    let order: Option<Order> = r.get().secondary(OrderKey::reference, "ref_2").unwrap();
+    assert_eq!(order, None);
+}
+
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
 #[native_model(id = 1, version = 1)]
 #[native_db]
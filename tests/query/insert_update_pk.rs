@@ -60,3 +60,37 @@ fn insert_update_pk() {
     let length = r.len().primary::<Item>().unwrap();
     assert_eq!(length, 1);
 }
+
+#[test]
+fn modify_pk() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Item {
+        id: 1,
+        name: "a".to_string(),
+    })
+    .unwrap();
+    let item = rw
+        .modify(1u32, |item: &mut Item| item.name.push('b'))
+        .unwrap();
+    assert_eq!(item.unwrap().name, "ab");
+    let item = rw
+        .modify(2u32, |item: &mut Item| item.name.push('b'))
+        .unwrap();
+    assert_eq!(item, None);
+    rw.commit().unwrap();
+
+    // The primary key can be modified too
+    let rw = db.rw_transaction().unwrap();
+    rw.modify(1u32, |item: &mut Item| item.id = 3).unwrap();
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let item: Option<Item> = r.get().primary(1u32).unwrap();
+    assert_eq!(item, None);
+    let item: Item = r.get().primary(3u32).unwrap().unwrap();
+    assert_eq!(item.name, "ab");
+}
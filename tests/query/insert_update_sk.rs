@@ -76,3 +76,436 @@ fn insert_update_sk() {
     let length = r.len().primary::<Item>().unwrap();
     assert_eq!(length, 1);
 }
+
+#[test]
+fn upsert_sk() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    let old = rw
+        .upsert(Item {
+            id: 1,
+            name: "a".to_string(),
+        })
+        .unwrap();
+    assert!(old.is_none());
+    let old = rw
+        .upsert(Item {
+            id: 1,
+            name: "b".to_string(),
+        })
+        .unwrap();
+    assert_eq!(
+        old,
+        Some(Item {
+            id: 1,
+            name: "a".to_string()
+        })
+    );
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<Item>().unwrap(), 1);
+    let result: Option<Item> = r.get().secondary(ItemKey::name, "a").unwrap();
+    assert!(result.is_none());
+    let result: Item = r.get().secondary(ItemKey::name, "b").unwrap().unwrap();
+    assert_eq!(result.id, 1);
+}
+
+#[test]
+fn upsert_with_sk() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let concat = |existing: &Item, incoming: &Item| Item {
+        id: existing.id,
+        name: format!("{}{}", existing.name, incoming.name),
+    };
+
+    let rw = db.rw_transaction().unwrap();
+    let old = rw
+        .upsert_with(
+            Item {
+                id: 1,
+                name: "a".to_string(),
+            },
+            concat,
+        )
+        .unwrap();
+    assert!(old.is_none());
+    let old = rw
+        .upsert_with(
+            Item {
+                id: 1,
+                name: "b".to_string(),
+            },
+            concat,
+        )
+        .unwrap();
+    assert_eq!(
+        old,
+        Some(Item {
+            id: 1,
+            name: "a".to_string()
+        })
+    );
+
+    // The merged value must keep the primary key.
+    let result = rw.upsert_with(
+        Item {
+            id: 1,
+            name: "c".to_string(),
+        },
+        |_, _| Item {
+            id: 2,
+            name: "c".to_string(),
+        },
+    );
+    assert!(matches!(
+        result,
+        Err(db_type::Error::PrimaryKeyMismatch { .. })
+    ));
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<Item>().unwrap(), 1);
+    let result: Option<Item> = r.get().secondary(ItemKey::name, "a").unwrap();
+    assert!(result.is_none());
+    let result: Item = r.get().secondary(ItemKey::name, "ab").unwrap().unwrap();
+    assert_eq!(result.id, 1);
+}
+
+#[test]
+fn update_many_sk() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    let items: Vec<Item> = (1..=3)
+        .map(|id| Item {
+            id,
+            name: format!("test{}", id),
+        })
+        .collect();
+    let rw = db.rw_transaction().unwrap();
+    rw.insert_many(items.clone()).unwrap();
+    rw.commit().unwrap();
+
+    // Rename the items 1 and 2, the item 3 is unchanged
+    let rw = db.rw_transaction().unwrap();
+    rw.update_many(items.iter().map(|item| {
+        let mut updated = item.clone();
+        if item.id != 3 {
+            updated.name = format!("renamed{}", item.id);
+        }
+        (item.clone(), updated)
+    }))
+    .unwrap();
+    // Exchange the names of the items 1 and 2 in the same call
+    rw.update_many(vec![
+        (
+            Item {
+                id: 1,
+                name: "renamed1".to_string(),
+            },
+            Item {
+                id: 1,
+                name: "tmp".to_string(),
+            },
+        ),
+        (
+            Item {
+                id: 2,
+                name: "renamed2".to_string(),
+            },
+            Item {
+                id: 2,
+                name: "renamed1".to_string(),
+            },
+        ),
+        (
+            Item {
+                id: 1,
+                name: "tmp".to_string(),
+            },
+            Item {
+                id: 1,
+                name: "renamed2".to_string(),
+            },
+        ),
+    ])
+    .unwrap();
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let item: Option<Item> = r.get().secondary(ItemKey::name, "test1").unwrap();
+    assert_eq!(item, None);
+    let item: Item = r
+        .get()
+        .secondary(ItemKey::name, "renamed2")
+        .unwrap()
+        .unwrap();
+    assert_eq!(item.id, 1);
+    let item: Item = r.get().secondary(ItemKey::name, "test3").unwrap().unwrap();
+    assert_eq!(item.id, 3);
+    assert_eq!(r.len().primary::<Item>().unwrap(), 3);
+}
+
+#[test]
+fn scan_modify_sk() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for id in 1..=4u32 {
+        rw.insert(Item {
+            id,
+            name: format!("a{}", id),
+        })
+        .unwrap();
+    }
+    rw.commit().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    let mut visited = vec![];
+    let count = rw
+        .scan_modify(|item: Item| {
+            visited.push(item.id);
+            match item.id {
+                1 => transaction::ScanAction::Delete,
+                2 => transaction::ScanAction::Update(Item {
+                    id: 2,
+                    name: "b2".to_string(),
+                }),
+                3 => transaction::ScanAction::Update(item),
+                _ => transaction::ScanAction::Keep,
+            }
+        })
+        .unwrap();
+    // The unchanged update is not counted
+    assert_eq!(count, 2);
+    assert_eq!(visited, vec![1, 2, 3, 4]);
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let items: Vec<Item> = r.scan().primary().unwrap().all().collect();
+    assert_eq!(
+        items
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["b2", "a3", "a4"]
+    );
+    let item: Option<Item> = r.get().secondary(ItemKey::name, "a1").unwrap();
+    assert_eq!(item, None);
+    let item: Option<Item> = r.get().secondary(ItemKey::name, "a2").unwrap();
+    assert_eq!(item, None);
+    let item: Item = r.get().secondary(ItemKey::name, "b2").unwrap().unwrap();
+    assert_eq!(item.id, 2);
+}
+
+#[test]
+fn get_mut_sk() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for id in 1..=2u32 {
+        rw.insert(Item {
+            id,
+            name: format!("a{}", id),
+        })
+        .unwrap();
+    }
+    rw.commit().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    // Saved explicitly
+    let mut item = rw.get_mut::<Item>(1u32).unwrap().unwrap();
+    item.name = "b1".to_string();
+    assert!(item.save().unwrap());
+    // Saved on drop
+    {
+        let mut item = rw.get_mut::<Item>(2u32).unwrap().unwrap();
+        item.name = "b2".to_string();
+    }
+    // Unchanged
+    let item = rw.get_mut::<Item>(2u32).unwrap().unwrap();
+    assert!(!item.save().unwrap());
+    assert!(rw.get_mut::<Item>(3u32).unwrap().is_none());
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let item: Option<Item> = r.get().secondary(ItemKey::name, "a1").unwrap();
+    assert_eq!(item, None);
+    let item: Item = r.get().secondary(ItemKey::name, "b1").unwrap().unwrap();
+    assert_eq!(item.id, 1);
+    let item: Item = r.get().secondary(ItemKey::name, "b2").unwrap().unwrap();
+    assert_eq!(item.id, 2);
+}
+
+#[test]
+fn get_mut_sk_error_on_drop() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for id in 1..=2u32 {
+        rw.insert(Item {
+            id,
+            name: format!("a{}", id),
+        })
+        .unwrap();
+    }
+    rw.commit().unwrap();
+
+    // The unique secondary key is already used: the error is returned by the commit
+    let rw = db.rw_transaction().unwrap();
+    {
+        let mut item = rw.get_mut::<Item>(2u32).unwrap().unwrap();
+        item.name = "a1".to_string();
+    }
+    let result = rw.commit();
+    assert!(matches!(result, Err(db_type::Error::DuplicateKey { .. })));
+
+    let r = db.r_transaction().unwrap();
+    let item: Item = r.get().primary(2u32).unwrap().unwrap();
+    assert_eq!(item.name, "a2");
+}
+
+#[test]
+fn compare_and_swap_sk() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let a = Item {
+        id: 1,
+        name: "a".to_string(),
+    };
+    let b = Item {
+        id: 1,
+        name: "b".to_string(),
+    };
+
+    let rw = db.rw_transaction().unwrap();
+    // Insert: None -> Some
+    assert!(rw.compare_and_swap(1u32, None, Some(a.clone())).unwrap());
+    assert!(!rw.compare_and_swap(1u32, None, Some(b.clone())).unwrap());
+    // Update: Some -> Some
+    assert!(!rw
+        .compare_and_swap(1u32, Some(b.clone()), Some(a.clone()))
+        .unwrap());
+    assert!(rw
+        .compare_and_swap(1u32, Some(a.clone()), Some(b.clone()))
+        .unwrap());
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let item: Option<Item> = r.get().secondary(ItemKey::name, "a").unwrap();
+    assert_eq!(item, None);
+    let item: Option<Item> = r.get().secondary(ItemKey::name, "b").unwrap();
+    assert_eq!(item, Some(b.clone()));
+
+    let rw = db.rw_transaction().unwrap();
+    // Delete: Some -> None
+    assert!(!rw.compare_and_swap(1u32, Some(a.clone()), None).unwrap());
+    assert!(rw.compare_and_swap(1u32, Some(b.clone()), None).unwrap());
+    // Absent and expected absent
+    assert!(rw.compare_and_swap::<Item>(1u32, None, None).unwrap());
+    // The primary key of the values must be the key
+    let result = rw.compare_and_swap(2u32, None, Some(a));
+    assert!(matches!(
+        result,
+        Err(db_type::Error::PrimaryKeyMismatch { .. })
+    ));
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<Item>().unwrap(), 0);
+    let item: Option<Item> = r.get().secondary(ItemKey::name, "b").unwrap();
+    assert_eq!(item, None);
+}
+
+#[test]
+fn entry_sk() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    #[allow(unused_mut)]
+    let (mut recv, _) = db.watch().scan().primary().all::<Item>().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    // Vacant: inserted
+    let item = rw
+        .entry::<Item>(1u32)
+        .unwrap()
+        .and_modify(|item| item.name = "b".to_string())
+        .unwrap()
+        .or_insert(Item {
+            id: 1,
+            name: "a".to_string(),
+        })
+        .unwrap();
+    assert_eq!(item.name, "a");
+    // Occupied: modified
+    let item = rw
+        .entry::<Item>(1u32)
+        .unwrap()
+        .and_modify(|item| item.name = "b".to_string())
+        .unwrap()
+        .or_insert_with(|| panic!("the value exists"))
+        .unwrap();
+    assert_eq!(item.name, "b");
+    // Occupied: unchanged, no write
+    rw.entry::<Item>(1u32).unwrap().and_modify(|_| {}).unwrap();
+    // The primary key of the inserted value must be the key of the entry
+    let result = match rw.entry::<Item>(2u32).unwrap() {
+        transaction::query::Entry::Vacant(entry) => entry.insert(Item {
+            id: 3,
+            name: "c".to_string(),
+        }),
+        transaction::query::Entry::Occupied(_) => panic!("the value does not exist"),
+    };
+    assert!(matches!(
+        result,
+        Err(db_type::Error::PrimaryKeyMismatch { .. })
+    ));
+    rw.commit().unwrap();
+
+    // One insert and one update
+    assert!(matches!(recv.try_recv().unwrap(), watch::Event::Insert(_)));
+    assert!(matches!(recv.try_recv().unwrap(), watch::Event::Update(_)));
+    assert!(recv.try_recv().is_err());
+
+    let r = db.r_transaction().unwrap();
+    let item: Option<Item> = r.get().secondary(ItemKey::name, "a").unwrap();
+    assert_eq!(item, None);
+    let item: Item = r.get().secondary(ItemKey::name, "b").unwrap().unwrap();
+    assert_eq!(item.id, 1);
+
+    let rw = db.rw_transaction().unwrap();
+    match rw.entry::<Item>(1u32).unwrap() {
+        transaction::query::Entry::Occupied(entry) => {
+            assert_eq!(entry.get().name, "b");
+            assert_eq!(entry.remove().unwrap().name, "b");
+        }
+        transaction::query::Entry::Vacant(_) => panic!("the value exists"),
+    }
+    rw.commit().unwrap();
+    assert!(matches!(recv.try_recv().unwrap(), watch::Event::Delete(_)));
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<Item>().unwrap(), 0);
+    let stats = db.redb_stats().unwrap();
+    assert_eq!(stats.secondary_tables[0].n_entries, Some(0));
+}
@@ -0,0 +1,111 @@
+use native_db::*;
+use shortcut_assert_fs::TmpFs;
+
+#[test]
+fn test_raw_table() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define_raw("blobs").unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    assert_eq!(rw.insert_raw("blobs", 1u64, &[1, 2, 3]).unwrap(), None);
+    assert_eq!(
+        rw.insert_raw("blobs", 1u64, &[4, 5]).unwrap(),
+        Some(vec![1, 2, 3])
+    );
+    rw.insert_raw("blobs", 2u64, &[6]).unwrap();
+    assert_eq!(rw.get().raw("blobs", 1u64).unwrap(), Some(vec![4, 5]));
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.get().raw("blobs", 1u64).unwrap(), Some(vec![4, 5]));
+    assert_eq!(r.get().raw("blobs", 2u64).unwrap(), Some(vec![6]));
+    assert_eq!(r.get().raw("blobs", 3u64).unwrap(), None);
+    drop(r);
+
+    let rw = db.rw_transaction().unwrap();
+    assert_eq!(rw.remove_raw("blobs", 2u64).unwrap(), Some(vec![6]));
+    assert_eq!(rw.remove_raw("blobs", 2u64).unwrap(), None);
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.get().raw("blobs", 2u64).unwrap(), None);
+}
+
+#[test]
+fn test_raw_table_not_defined() {
+    let builder = DatabaseBuilder::new();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    let result = rw.insert_raw("blobs", 1u64, &[1]);
+    assert!(matches!(
+        result,
+        Err(db_type::Error::TableDefinitionNotFound { .. })
+    ));
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let result = r.get().raw("blobs", 1u64);
+    assert!(matches!(
+        result,
+        Err(db_type::Error::TableDefinitionNotFound { .. })
+    ));
+}
+
+#[test]
+fn test_raw_table_snapshot() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define_raw("blobs").unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert_raw("blobs", "a", b"value").unwrap();
+    rw.commit().unwrap();
+
+    let db_snapshot = db
+        .snapshot(&builder, tf.path("snapshot.db").as_std_path())
+        .unwrap();
+    let r = db_snapshot.r_transaction().unwrap();
+    assert_eq!(r.get().raw("blobs", "a").unwrap(), Some(b"value".to_vec()));
+}
+
+#[cfg(not(feature = "tokio"))]
+#[test]
+fn test_raw_table_watch() {
+    use native_db::watch::Event;
+    use std::time::Duration;
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define_raw("blobs").unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let (recv, _) = db.watch().get().raw("blobs", 1u64).unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert_raw("blobs", 1u64, &[1]).unwrap();
+    rw.insert_raw("blobs", 1u64, &[2]).unwrap();
+    rw.insert_raw("blobs", 2u64, &[3]).unwrap();
+    rw.remove_raw("blobs", 1u64).unwrap();
+    rw.commit().unwrap();
+
+    let timeout = Duration::from_secs(1);
+    match recv.recv_timeout(timeout).unwrap() {
+        Event::Insert(insert) => assert_eq!(insert.inner_raw(), &[1]),
+        _ => panic!("wrong event"),
+    }
+    match recv.recv_timeout(timeout).unwrap() {
+        Event::Update(update) => {
+            assert_eq!(update.inner_old_raw(), &[1]);
+            assert_eq!(update.inner_new_raw(), &[2]);
+        }
+        _ => panic!("wrong event"),
+    }
+    match recv.recv_timeout(timeout).unwrap() {
+        Event::Delete(delete) => assert_eq!(delete.inner_raw(), &[2]),
+        _ => panic!("wrong event"),
+    }
+    assert!(recv.try_recv().is_err());
+}
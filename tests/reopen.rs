@@ -0,0 +1,155 @@
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+use shortcut_assert_fs::TmpFs;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct Item {
+    #[primary_key]
+    id: u32,
+    #[secondary_key(unique)]
+    name: String,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 2, version = 1)]
+#[native_db]
+struct Other {
+    #[primary_key]
+    id: u32,
+}
+
+#[test]
+fn test_reopen_keeps_data() {
+    let tf = TmpFs::new().unwrap();
+    let path = tf.path("test");
+
+    {
+        let mut builder = DatabaseBuilder::new();
+        builder.define::<Item>().unwrap();
+        builder.define_raw("blobs").unwrap();
+        let db = builder.create(path.as_std_path()).unwrap();
+        let rw = db.rw_transaction().unwrap();
+        rw.insert(Item {
+            id: 1,
+            name: "a".to_string(),
+        })
+        .unwrap();
+        rw.insert(Item {
+            id: 2,
+            name: "b".to_string(),
+        })
+        .unwrap();
+        rw.insert_raw("blobs", 1u32, &[1, 2, 3]).unwrap();
+        rw.commit().unwrap();
+    }
+
+    // Create again on the same path, with a new model
+    {
+        let mut builder = DatabaseBuilder::new();
+        builder.define::<Item>().unwrap();
+        builder.define::<Other>().unwrap();
+        builder.define_raw("blobs").unwrap();
+        let db = builder.create(path.as_std_path()).unwrap();
+        let r = db.r_transaction().unwrap();
+        assert_eq!(r.len().primary::<Item>().unwrap(), 2);
+        assert_eq!(r.len().primary::<Other>().unwrap(), 0);
+        let item: Item = r.get().secondary(ItemKey::name, "b").unwrap().unwrap();
+        assert_eq!(item.id, 2);
+        assert_eq!(r.get().raw("blobs", 1u32).unwrap(), Some(vec![1, 2, 3]));
+        drop(r);
+
+        let rw = db.rw_transaction().unwrap();
+        rw.insert(Other { id: 1 }).unwrap();
+        rw.commit().unwrap();
+    }
+
+    // Open
+    {
+        let mut builder = DatabaseBuilder::new();
+        builder.define::<Item>().unwrap();
+        builder.define::<Other>().unwrap();
+        let db = builder.open(path.as_std_path()).unwrap();
+        let r = db.r_transaction().unwrap();
+        assert_eq!(r.len().primary::<Item>().unwrap(), 2);
+        assert_eq!(r.len().primary::<Other>().unwrap(), 1);
+        let item: Item = r.get().primary(1u32).unwrap().unwrap();
+        assert_eq!(item.name, "a");
+    }
+}
+
+#[test]
+fn test_open_not_found() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    assert!(builder.open(tf.path("missing").as_std_path()).is_err());
+}
+
+#[test]
+fn test_create_temp_removed_on_drop() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+
+    let db = builder.create_temp().unwrap();
+    let path = db.temp_path().unwrap().to_path_buf();
+    assert!(path.exists());
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Item {
+        id: 1,
+        name: "test".to_string(),
+    })
+    .unwrap();
+    rw.commit().unwrap();
+
+    // Each database gets its own file
+    let other_db = builder.create_temp().unwrap();
+    assert_ne!(other_db.temp_path().unwrap(), path);
+
+    drop(db);
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_create_keeps_file_on_drop() {
+    let tf = TmpFs::new().unwrap();
+    let path = tf.path("test");
+
+    let builder = DatabaseBuilder::new();
+    let db = builder.create(&path).unwrap();
+    assert!(db.temp_path().is_none());
+    drop(db);
+    assert!(path.exists());
+}
+
+#[test]
+fn test_checkpoint() {
+    let tf = TmpFs::new().unwrap();
+    let path = tf.path("test");
+
+    {
+        let mut builder = DatabaseBuilder::new();
+        builder.define::<Item>().unwrap();
+        let db = builder.create(path.as_std_path()).unwrap();
+        let rw = db.rw_transaction().unwrap();
+        rw.insert(Item {
+            id: 1,
+            name: "a".to_string(),
+        })
+        .unwrap();
+        rw.commit().unwrap();
+        // Doesn't need an exclusive access, unlike `compact`
+        let r = db.r_transaction().unwrap();
+        db.checkpoint().unwrap();
+        assert_eq!(r.len().primary::<Item>().unwrap(), 1);
+    }
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.open(path.as_std_path()).unwrap();
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<Item>().unwrap(), 1);
+}
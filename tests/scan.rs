@@ -584,3 +584,396 @@ fn test_txn_write_start_with_scenario() {
         assert_eq!(obj3.name, format!("{}3", p));
     }
 }
+
+#[test]
+fn test_iter_keys() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Item::new(2, "test2")).unwrap();
+    rw.insert(Item::new(1, "test")).unwrap();
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let keys: Vec<Vec<u8>> = r.scan().primary::<Item>().unwrap().keys().collect();
+    assert_eq!(
+        keys,
+        vec![1u32.to_be_bytes().to_vec(), 2u32.to_be_bytes().to_vec()]
+    );
+
+    let keys: Vec<Vec<u8>> = r.scan().primary::<Item>().unwrap().keys().rev().collect();
+    assert_eq!(
+        keys,
+        vec![2u32.to_be_bytes().to_vec(), 1u32.to_be_bytes().to_vec()]
+    );
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 3, version = 1)]
+#[native_db]
+struct Player {
+    #[primary_key]
+    id: u32,
+    #[secondary_key]
+    score: u32,
+    #[secondary_key(unique)]
+    name: String,
+}
+
+#[test]
+fn test_secondary_top_n() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Player>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for (id, score) in [(1, 10), (2, 300), (3, 20), (4, 300), (5, 300), (6, 5)] {
+        rw.insert(Player {
+            id,
+            score,
+            name: format!("p{}", id),
+        })
+        .unwrap();
+    }
+    rw.commit().unwrap();
+
+    let ids = |players: Vec<Player>| players.into_iter().map(|p| p.id).collect::<Vec<_>>();
+
+    let r = db.r_transaction().unwrap();
+    // Ties are ordered by ascending primary key
+    let top = r.scan().secondary_top_n(PlayerKey::score, 4, true).unwrap();
+    assert_eq!(ids(top), vec![2, 4, 5, 3]);
+    let top = r.scan().secondary_top_n(PlayerKey::score, 2, true).unwrap();
+    assert_eq!(ids(top), vec![2, 4]);
+    let bottom = r
+        .scan()
+        .secondary_top_n(PlayerKey::score, 3, false)
+        .unwrap();
+    assert_eq!(ids(bottom), vec![6, 1, 3]);
+    let all = r
+        .scan()
+        .secondary_top_n(PlayerKey::score, 100, true)
+        .unwrap();
+    assert_eq!(ids(all), vec![2, 4, 5, 3, 1, 6]);
+
+    // Unique key
+    let top = r.scan().secondary_top_n(PlayerKey::name, 2, true).unwrap();
+    assert_eq!(ids(top), vec![6, 5]);
+
+    let rw = db.rw_transaction().unwrap();
+    let top: Vec<Player> = rw
+        .scan()
+        .secondary_top_n(PlayerKey::score, 0, true)
+        .unwrap();
+    assert!(top.is_empty());
+}
+
+#[test]
+fn test_key_range() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Player>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for id in [1, 2, 42, 99, 100, 256, 300] {
+        rw.insert(Player {
+            id,
+            score: 0,
+            name: format!("p{}", id),
+        })
+        .unwrap();
+    }
+    rw.commit().unwrap();
+
+    let ids = |players: Vec<Player>| players.into_iter().map(|p| p.id).collect::<Vec<_>>();
+
+    let r = db.r_transaction().unwrap();
+    let scan = r.scan().primary().unwrap();
+    let result = scan.range(KeyRange::from(42u32).to(99u32)).collect();
+    assert_eq!(ids(result), vec![42]);
+    let result = scan
+        .range(KeyRange::from(42u32).to_inclusive(256u32))
+        .collect();
+    assert_eq!(ids(result), vec![42, 99, 100, 256]);
+    let result = scan.range(KeyRange::all().to_inclusive(2u32)).collect();
+    assert_eq!(ids(result), vec![1, 2]);
+    let result = scan.range(KeyRange::from(100u32)).collect();
+    assert_eq!(ids(result), vec![100, 256, 300]);
+    assert_eq!(scan.range(KeyRange::<u32>::all()).count(), 7);
+
+    let len = r
+        .len()
+        .primary_range::<Player, _, _>(KeyRange::from(2u32).to(300u32))
+        .unwrap();
+    assert_eq!(len, 5);
+}
+
+#[test]
+fn test_secondary_range_sorted_by() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Player>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for (id, name) in [(1, "file10"), (2, "file2"), (3, "file1"), (4, "other")] {
+        rw.insert(Player {
+            id,
+            score: 0,
+            name: name.to_string(),
+        })
+        .unwrap();
+    }
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let scan = r.scan().secondary::<Player>(PlayerKey::name).unwrap();
+    let names = |players: Vec<Player>| players.into_iter().map(|p| p.name).collect::<Vec<_>>();
+
+    // Byte order
+    let result = scan.range("file".."filf").collect();
+    assert_eq!(names(result), vec!["file1", "file10", "file2"]);
+
+    // Numeric-aware order
+    let result = scan.range_sorted_by("file".."filf", |a, b| {
+        let a: u32 = a.name["file".len()..].parse().unwrap();
+        let b: u32 = b.name["file".len()..].parse().unwrap();
+        a.cmp(&b)
+    });
+    assert_eq!(names(result), vec!["file1", "file2", "file10"]);
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 4, version = 1)]
+#[native_db(secondary_key(delta_key, unique))]
+struct Measure {
+    #[primary_key]
+    id: u32,
+    delta: i64,
+}
+
+impl Measure {
+    fn delta_key(&self) -> SignedKey<i64> {
+        SignedKey(self.delta)
+    }
+}
+
+#[test]
+fn test_signed_key_range() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Measure>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for (id, delta) in [
+        (1, -300),
+        (2, -1),
+        (3, 0),
+        (4, 1),
+        (5, 42),
+        (6, i64::MIN),
+        (7, i64::MAX),
+    ] {
+        rw.insert(Measure { id, delta }).unwrap();
+    }
+    rw.commit().unwrap();
+
+    let deltas = |measures: Vec<Measure>| measures.into_iter().map(|m| m.delta).collect::<Vec<_>>();
+
+    let r = db.r_transaction().unwrap();
+    let scan = r.scan().secondary(MeasureKey::delta_key).unwrap();
+    let result = scan.range(SignedKey(-10i64)..SignedKey(10i64)).collect();
+    assert_eq!(deltas(result), vec![-1, 0, 1]);
+    let result = scan
+        .range(KeyRange::from(SignedKey(-300i64)).to_inclusive(SignedKey(42i64)))
+        .collect();
+    assert_eq!(deltas(result), vec![-300, -1, 0, 1, 42]);
+    let result = scan.all().collect();
+    assert_eq!(deltas(result), vec![i64::MIN, -300, -1, 0, 1, 42, i64::MAX]);
+}
+
+#[test]
+fn test_page() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for id in 1..=5 {
+        rw.insert(Item::new(id, &format!("test_{}", id))).unwrap();
+    }
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let scan = r.scan().primary::<Item>().unwrap();
+    let page = scan.page(None, 2).unwrap();
+    assert_eq!(
+        page.values,
+        vec![Item::new(1, "test_1"), Item::new(2, "test_2")]
+    );
+    assert_eq!(
+        page.next,
+        Some(2u32.database_inner_key_value().as_slice().to_vec())
+    );
+    let page = scan.page(page.next.as_deref(), 2).unwrap();
+    assert_eq!(
+        page.values,
+        vec![Item::new(3, "test_3"), Item::new(4, "test_4")]
+    );
+    let page = scan.page(page.next.as_deref(), 2).unwrap();
+    assert_eq!(page.values, vec![Item::new(5, "test_5")]);
+    assert_eq!(page.next, None);
+
+    // A full last page has no next page
+    let after = 3u32.database_inner_key_value();
+    let page = scan.page(Some(after.as_slice()), 2).unwrap();
+    assert_eq!(page.values.len(), 2);
+    assert_eq!(page.next, None);
+
+    // The key doesn't need to exist
+    let after = 10u32.database_inner_key_value();
+    let page = scan.page(Some(after.as_slice()), 2).unwrap();
+    assert!(page.values.is_empty());
+    assert_eq!(page.next, None);
+
+    // All the values
+    let page = scan.page(None, usize::MAX).unwrap();
+    assert_eq!(page.values.len(), 5);
+    assert_eq!(page.next, None);
+
+    // An empty page can't tell if there is a next page
+    assert!(matches!(
+        scan.page(None, 0),
+        Err(db_type::Error::InvalidPageLimit)
+    ));
+
+    // Skip without decoding the skipped values
+    let values: Vec<Item> = scan.all().skip(3).take(1).collect();
+    assert_eq!(values, vec![Item::new(4, "test_4")]);
+    let values: Vec<Item> = scan.all().skip(10).collect();
+    assert!(values.is_empty());
+}
+
+#[test]
+fn test_all_buffered() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let scan = r.scan().primary::<Item>().unwrap();
+    assert_eq!(scan.all_buffered(3).count(), 0);
+
+    let rw = db.rw_transaction().unwrap();
+    for id in 1..=10 {
+        rw.insert(Item::new(id, &format!("test_{}", id))).unwrap();
+    }
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let scan = r.scan().primary::<Item>().unwrap();
+    let expected: Vec<Item> = scan.all().collect();
+    for buffer in [0, 1, 3, 5, 10, 100, usize::MAX] {
+        let values: Vec<Item> = scan.all_buffered(buffer).collect();
+        assert_eq!(values, expected);
+    }
+}
+
+#[test]
+fn test_live_scan() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    assert_eq!(db.live_scan::<Item>(3, |_| unreachable!()).unwrap(), 0);
+
+    let rw = db.rw_transaction().unwrap();
+    for id in (2..=10).step_by(2) {
+        rw.insert(Item::new(id, &format!("test_{}", id))).unwrap();
+    }
+    rw.commit().unwrap();
+
+    // Insert a value before and a value after the cursor during the scan.
+    let mut ids = vec![];
+    let count = db
+        .live_scan::<Item>(2, |item: Item| {
+            if item.id == 4 {
+                let rw = db.rw_transaction().unwrap();
+                rw.insert(Item::new(1, "test_1")).unwrap();
+                rw.insert(Item::new(7, "test_7")).unwrap();
+                rw.commit().unwrap();
+            }
+            ids.push(item.id);
+        })
+        .unwrap();
+    assert_eq!(ids, vec![2, 4, 6, 7, 8, 10]);
+    assert_eq!(count, 6);
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 6, version = 1)]
+#[native_db(primary_key(tenant_entity))]
+struct Entity {
+    tenant_id: String,
+    entity_id: String,
+}
+
+impl Entity {
+    fn new(tenant_id: &str, entity_id: &str) -> Self {
+        Self {
+            tenant_id: tenant_id.to_string(),
+            entity_id: entity_id.to_string(),
+        }
+    }
+
+    fn tenant_entity(&self) -> CompositeKey {
+        CompositeKey::new()
+            .with(self.tenant_id.as_str())
+            .with(self.entity_id.as_str())
+    }
+}
+
+#[test]
+fn test_composite_key_start_with() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Entity>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    // Same concatenation of the components
+    rw.insert(Entity::new("a", "bc")).unwrap();
+    rw.insert(Entity::new("ab", "c")).unwrap();
+    rw.insert(Entity::new("a", "a\0b")).unwrap();
+    rw.insert(Entity::new("a", "a")).unwrap();
+    rw.insert(Entity::new("b", "a")).unwrap();
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<Entity>().unwrap(), 5);
+
+    let entity: Entity = r
+        .get()
+        .primary(CompositeKey::new().with("ab").with("c"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(entity, Entity::new("ab", "c"));
+
+    // Sorted by tenant then by entity
+    let scan = r.scan().primary().unwrap();
+    let entities: Vec<Entity> = scan.start_with(CompositeKey::new().with("a")).collect();
+    assert_eq!(
+        entities,
+        vec![
+            Entity::new("a", "a"),
+            Entity::new("a", "a\0b"),
+            Entity::new("a", "bc"),
+        ]
+    );
+    let entities: Vec<Entity> = scan.all().collect();
+    assert_eq!(entities.len(), 5);
+    assert_eq!(entities[3], Entity::new("ab", "c"));
+    assert_eq!(entities[4], Entity::new("b", "a"));
+}
@@ -0,0 +1,184 @@
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+use shortcut_assert_fs::TmpFs;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct Item {
+    #[primary_key]
+    id: u64,
+}
+
+#[test]
+fn test_sequence_default() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    assert_eq!(db.current_sequence::<Item>().unwrap(), 1);
+    let rw = db.rw_transaction().unwrap();
+    assert_eq!(rw.next_sequence::<Item>().unwrap(), 1);
+    assert_eq!(rw.next_sequence::<Item>().unwrap(), 2);
+    rw.commit().unwrap();
+    assert_eq!(db.current_sequence::<Item>().unwrap(), 3);
+}
+
+#[test]
+fn test_sequence_start_step() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    db.configure_sequence::<Item>(1_000_000, 10).unwrap();
+    assert_eq!(db.current_sequence::<Item>().unwrap(), 1_000_000);
+
+    let rw = db.rw_transaction().unwrap();
+    for _ in 0..3 {
+        let id = rw.next_sequence::<Item>().unwrap();
+        rw.insert(Item { id }).unwrap();
+    }
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let ids: Vec<u64> = r
+        .scan()
+        .primary::<Item>()
+        .unwrap()
+        .all()
+        .map(|item| item.id)
+        .collect();
+    assert_eq!(ids, vec![1_000_000, 1_000_010, 1_000_020]);
+    assert_eq!(db.current_sequence::<Item>().unwrap(), 1_000_030);
+}
+
+#[test]
+fn test_sequence_abort() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    assert_eq!(rw.next_sequence::<Item>().unwrap(), 1);
+    drop(rw);
+    assert_eq!(db.current_sequence::<Item>().unwrap(), 1);
+}
+
+#[test]
+fn test_sequence_invalid_step() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let result = db.configure_sequence::<Item>(1, 0);
+    assert!(matches!(result, Err(db_type::Error::InvalidSequenceStep)));
+}
+
+#[test]
+fn test_sequence_overflow() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    db.configure_sequence::<Item>(u64::MAX, 1).unwrap();
+    let rw = db.rw_transaction().unwrap();
+    let result = rw.next_sequence::<Item>();
+    assert!(matches!(
+        result,
+        Err(db_type::Error::SequenceOverflow { model_id: 1 })
+    ));
+}
+
+#[test]
+fn test_sequence_reopen() {
+    let tf = TmpFs::new().unwrap();
+    let db_path = tf.path("test");
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    {
+        let db = builder.create(db_path.as_std_path()).unwrap();
+        db.configure_sequence::<Item>(100, 2).unwrap();
+        let rw = db.rw_transaction().unwrap();
+        rw.next_sequence::<Item>().unwrap();
+        rw.commit().unwrap();
+    }
+
+    let db = builder.open(db_path.as_std_path()).unwrap();
+    assert_eq!(db.current_sequence::<Item>().unwrap(), 102);
+    let rw = db.rw_transaction().unwrap();
+    assert_eq!(rw.next_sequence::<Item>().unwrap(), 102);
+    rw.commit().unwrap();
+
+    let db_snapshot = db
+        .snapshot(&builder, tf.path("snapshot.db").as_std_path())
+        .unwrap();
+    assert_eq!(db_snapshot.current_sequence::<Item>().unwrap(), 104);
+}
+
+#[test]
+fn test_reset_sequence() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    db.configure_sequence::<Item>(1, 5).unwrap();
+    let rw = db.rw_transaction().unwrap();
+    for _ in 0..2 {
+        let id = rw.next_sequence::<Item>().unwrap();
+        rw.insert(Item { id }).unwrap();
+    }
+    rw.commit().unwrap();
+    assert_eq!(db.current_sequence::<Item>().unwrap(), 11);
+
+    // The id 6 is already used.
+    let result = db.reset_sequence::<Item>(6);
+    assert!(matches!(
+        result,
+        Err(db_type::Error::SequenceBelowExistingKey { key: 6, .. })
+    ));
+    assert_eq!(db.current_sequence::<Item>().unwrap(), 11);
+
+    // The step is kept.
+    db.reset_sequence::<Item>(7).unwrap();
+    let rw = db.rw_transaction().unwrap();
+    assert_eq!(rw.next_sequence::<Item>().unwrap(), 7);
+    assert_eq!(rw.next_sequence::<Item>().unwrap(), 12);
+    rw.commit().unwrap();
+
+    // Reset on an empty table.
+    let rw = db.rw_transaction().unwrap();
+    rw.remove(Item { id: 1 }).unwrap();
+    rw.remove(Item { id: 6 }).unwrap();
+    rw.commit().unwrap();
+    db.reset_sequence::<Item>(1).unwrap();
+    assert_eq!(db.current_sequence::<Item>().unwrap(), 1);
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 3, version = 1)]
+#[native_db]
+struct Code {
+    #[primary_key]
+    code: String,
+}
+
+#[test]
+fn test_reset_sequence_string_key() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Code>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    // 8 bytes, like a u64 key.
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Code {
+        code: "zzzzzzzz".to_string(),
+    })
+    .unwrap();
+    rw.commit().unwrap();
+
+    // The string key is not read as a u64.
+    db.reset_sequence::<Code>(1).unwrap();
+    assert_eq!(db.current_sequence::<Code>().unwrap(), 1);
+}
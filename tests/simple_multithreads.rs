@@ -67,3 +67,70 @@ fn multi_threads() {
         assert_eq!(item_b.name, "b".to_string());
     }
 }
+
+#[test]
+fn database_is_send_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Database>();
+    assert_send_sync::<Arc<Database>>();
+}
+
+#[cfg(not(feature = "tokio"))]
+#[test]
+fn multi_threads_read_write_watch() {
+    use native_db::watch::Event;
+    use std::sync::Barrier;
+    use std::time::Duration;
+
+    const THREADS: u32 = 8;
+
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    let db = Arc::new(db);
+    let watched = Barrier::new(THREADS as usize);
+    let committed = Barrier::new(THREADS as usize);
+
+    thread::scope(|s| {
+        let mut handles = vec![];
+        for id in 0..THREADS {
+            let db = db.clone();
+            let watched = &watched;
+            let committed = &committed;
+            handles.push(s.spawn(move || {
+                let (recv, watcher_id) = db.watch().scan().primary().all::<Item>().unwrap();
+                watched.wait();
+
+                let rw = db.rw_transaction().unwrap();
+                rw.insert(Item {
+                    id,
+                    name: id.to_string(),
+                })
+                .unwrap();
+                rw.commit().unwrap();
+
+                let r = db.r_transaction().unwrap();
+                let item: Item = r.get().primary(id).unwrap().unwrap();
+                assert_eq!(item.name, id.to_string());
+
+                // Keep the receiver until all the threads have committed.
+                committed.wait();
+                for _ in 0..THREADS {
+                    let event = recv.recv_timeout(Duration::from_secs(1)).unwrap();
+                    assert!(matches!(event, Event::Insert(_)));
+                }
+                assert!(recv.try_recv().is_err());
+                db.unwatch(watcher_id).unwrap();
+            }));
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    });
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<Item>().unwrap(), THREADS as u64);
+}
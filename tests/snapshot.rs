@@ -43,3 +43,113 @@ fn test_snapshot() {
 
     tf.display_dir_entries();
 }
+
+#[test]
+fn test_snapshot_to() {
+    let tf = TmpFs::new().unwrap();
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Item {
+        id: 1,
+        name: "test".to_string(),
+    })
+    .unwrap();
+    rw.commit().unwrap();
+
+    // Writes in progress do not block the snapshot and are not part of it.
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Item {
+        id: 2,
+        name: "test".to_string(),
+    })
+    .unwrap();
+    db.snapshot_to(tf.path("snapshot.db").as_std_path())
+        .unwrap();
+    rw.commit().unwrap();
+
+    let db_snapshot = builder.open(tf.path("snapshot.db").as_std_path()).unwrap();
+    let r = db_snapshot.r_transaction().unwrap();
+    let result_item: Item = r.get().primary(1u32).unwrap().unwrap();
+    assert_eq!(
+        Item {
+            id: 1,
+            name: "test".to_string()
+        },
+        result_item
+    );
+    let result_item: Option<Item> = r.get().primary(2u32).unwrap();
+    assert!(result_item.is_none());
+    drop(r);
+    drop(db_snapshot);
+
+    // An existing file is not overwritten
+    let result = db.snapshot_to(tf.path("snapshot.db").as_std_path());
+    assert!(
+        matches!(result, Err(db_type::Error::Io(err)) if err.kind() == std::io::ErrorKind::AlreadyExists)
+    );
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+#[native_model(id = 2, version = 1)]
+#[native_db]
+struct ItemSecondaryKey {
+    #[primary_key]
+    id: u32,
+    #[secondary_key(unique)]
+    name: String,
+}
+
+#[test]
+fn test_copy_table() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemSecondaryKey>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+    let fork = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for id in 1..=3u32 {
+        rw.insert(ItemSecondaryKey {
+            id,
+            name: format!("test_{}", id),
+        })
+        .unwrap();
+    }
+    rw.commit().unwrap();
+
+    // The values already in the destination are replaced
+    let rw = fork.rw_transaction().unwrap();
+    rw.insert(ItemSecondaryKey {
+        id: 4,
+        name: "stale".to_string(),
+    })
+    .unwrap();
+    rw.commit().unwrap();
+
+    assert_eq!(db.copy_table::<ItemSecondaryKey>(&fork).unwrap(), 3);
+
+    let r = fork.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<ItemSecondaryKey>().unwrap(), 3);
+    let item: ItemSecondaryKey = r
+        .get()
+        .secondary(ItemSecondaryKeyKey::name, "test_2")
+        .unwrap()
+        .unwrap();
+    assert_eq!(item.id, 2);
+    let item: Option<ItemSecondaryKey> = r
+        .get()
+        .secondary(ItemSecondaryKeyKey::name, "stale")
+        .unwrap();
+    assert_eq!(item, None);
+
+    // The model must be defined in the destination
+    let other_builder = DatabaseBuilder::new();
+    let other = other_builder.create_in_memory().unwrap();
+    let result = db.copy_table::<ItemSecondaryKey>(&other);
+    assert!(matches!(
+        result,
+        Err(db_type::Error::TableDefinitionNotFound { .. })
+    ));
+}
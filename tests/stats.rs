@@ -0,0 +1,116 @@
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+use shortcut_assert_fs::TmpFs;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct Item {
+    #[primary_key]
+    id: u32,
+    name: String,
+}
+
+#[test]
+fn test_redb_database_stats() {
+    let tf = TmpFs::new().unwrap();
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for id in 0..100 {
+        rw.insert(Item {
+            id,
+            name: "test".to_string(),
+        })
+        .unwrap();
+    }
+    rw.commit().unwrap();
+
+    let stats = db.redb_database_stats().unwrap();
+    assert!(stats.page_size > 0);
+    assert!(stats.allocated_pages > 0);
+    assert!(stats.leaf_pages > 0);
+    assert!(stats.stored_bytes > 0);
+    assert!(stats.tree_height > 0);
+
+    // The transaction opened by the stats is not committed
+    let rw = db.rw_transaction().unwrap();
+    rw.commit().unwrap();
+}
+
+#[test]
+fn test_compact() {
+    let tf = TmpFs::new().unwrap();
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let mut db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for id in 0..1000 {
+        rw.insert(Item {
+            id,
+            name: "test".repeat(100),
+        })
+        .unwrap();
+    }
+    rw.commit().unwrap();
+    let rw = db.rw_transaction().unwrap();
+    rw.remove_range::<Item, _, _>(0u32..990).unwrap();
+    rw.commit().unwrap();
+
+    let allocated_pages = db.redb_database_stats().unwrap().allocated_pages;
+    db.compact().unwrap();
+    assert!(db.redb_database_stats().unwrap().allocated_pages < allocated_pages);
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<Item>().unwrap(), 10);
+}
+
+#[test]
+fn test_auto_compaction() {
+    let tf = TmpFs::new().unwrap();
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    thread::scope(|scope| {
+        let db = db.auto_compaction(scope, Duration::from_millis(10), 0.5);
+        let compactions = {
+            let database = db.database();
+            let compactions = db.compactions();
+            let rw = database.rw_transaction().unwrap();
+            for id in 0..1000 {
+                rw.insert(Item {
+                    id,
+                    name: "test".repeat(100),
+                })
+                .unwrap();
+            }
+            rw.commit().unwrap();
+
+            // Not compacted while the guard is alive
+            thread::sleep(Duration::from_millis(50));
+            assert_eq!(db.compactions(), compactions);
+
+            let rw = database.rw_transaction().unwrap();
+            rw.remove_range::<Item, _, _>(0u32..990).unwrap();
+            rw.commit().unwrap();
+            compactions
+        };
+
+        let start = Instant::now();
+        while db.compactions() <= compactions {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let database = db.database();
+        let r = database.r_transaction().unwrap();
+        assert_eq!(r.len().primary::<Item>().unwrap(), 10);
+    });
+}
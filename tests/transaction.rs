@@ -112,3 +112,577 @@ fn test_transaction_fail() {
     let result: Option<Item> = r.get().primary(2u32).unwrap();
     assert!(result.is_none());
 }
+
+#[derive(Debug)]
+enum AppError {
+    Db(db_type::Error),
+    Invalid,
+}
+
+impl From<db_type::Error> for AppError {
+    fn from(err: db_type::Error) -> Self {
+        AppError::Db(err)
+    }
+}
+
+#[test]
+fn test_write_closure_custom_error() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    let id = db
+        .write(|rw| {
+            rw.insert(Item {
+                id: 1,
+                name: "test".to_string(),
+            })?;
+            Ok::<_, AppError>(1u32)
+        })
+        .unwrap();
+    assert_eq!(id, 1);
+
+    let result = db.write(|rw| {
+        rw.insert(Item {
+            id: 2,
+            name: "test".to_string(),
+        })?;
+        Err::<(), _>(AppError::Invalid)
+    });
+    assert!(matches!(result, Err(AppError::Invalid)));
+
+    let result = db.read(|r| {
+        let item_1: Option<Item> = r.get().primary(1u32)?;
+        let item_2: Option<Item> = r.get().primary(2u32)?;
+        Ok::<_, AppError>((item_1, item_2))
+    });
+    let (item_1, item_2) = result.unwrap();
+    assert!(item_1.is_some());
+    assert!(item_2.is_none());
+
+    // Database errors are converted into the custom error.
+    let result = db.write(|rw| {
+        rw.insert(Item2 {
+            id: 1,
+            name: "test".to_string(),
+        })?;
+        Ok::<(), AppError>(())
+    });
+    assert!(matches!(
+        result,
+        Err(AppError::Db(db_type::Error::TableDefinitionNotFound { .. }))
+    ));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_write_blocking() {
+    use once_cell::sync::Lazy;
+    use std::sync::Arc;
+
+    static BUILDER: Lazy<DatabaseBuilder> = Lazy::new(|| {
+        let mut builder = DatabaseBuilder::new();
+        builder.define::<Item>().unwrap();
+        builder
+    });
+    let db = Arc::new(BUILDER.create_in_memory().unwrap());
+
+    let key = db
+        .clone()
+        .write_blocking(|rw| {
+            rw.insert(Item {
+                id: 1,
+                name: "test".to_string(),
+            })
+        })
+        .await
+        .unwrap();
+    assert_eq!(key, 1u32.database_inner_key_value());
+
+    // The transaction is aborted if the closure fails
+    let result = db
+        .clone()
+        .write_blocking(|rw| {
+            rw.insert(Item {
+                id: 2,
+                name: "test".to_string(),
+            })?;
+            Err::<(), db_type::Error>(db_type::Error::PrimaryKeyNotFound)
+        })
+        .await;
+    assert!(matches!(result, Err(db_type::Error::PrimaryKeyNotFound)));
+
+    let item: Option<Item> = db
+        .clone()
+        .read_blocking(|r| r.get().primary(1u32))
+        .await
+        .unwrap();
+    assert_eq!(item.unwrap().name, "test");
+    let len = db
+        .read_blocking(|r| r.len().primary::<Item>())
+        .await
+        .unwrap();
+    assert_eq!(len, 1);
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 3, version = 1)]
+#[native_db]
+struct ItemSk {
+    #[primary_key]
+    id: u32,
+    #[secondary_key(unique)]
+    name: String,
+}
+
+#[test]
+fn test_read_your_writes() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemSk>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let item = ItemSk {
+        id: 1,
+        name: "a".to_string(),
+    };
+    let item_updated = ItemSk {
+        id: 1,
+        name: "b".to_string(),
+    };
+
+    let rw = db.rw_transaction().unwrap();
+
+    // Insert
+    rw.insert(item.clone()).unwrap();
+    let result: Option<ItemSk> = rw.get().primary(1u32).unwrap();
+    assert_eq!(result, Some(item.clone()));
+    let result: Option<ItemSk> = rw.get().secondary(ItemSkKey::name, "a").unwrap();
+    assert_eq!(result, Some(item.clone()));
+    assert_eq!(rw.len().primary::<ItemSk>().unwrap(), 1);
+
+    // Not visible from the other transactions before the commit
+    let r = db.r_transaction().unwrap();
+    let result: Option<ItemSk> = r.get().primary(1u32).unwrap();
+    assert!(result.is_none());
+    drop(r);
+
+    // Update
+    rw.update(item.clone(), item_updated.clone()).unwrap();
+    let result: Option<ItemSk> = rw.get().primary(1u32).unwrap();
+    assert_eq!(result, Some(item_updated.clone()));
+    let result: Option<ItemSk> = rw.get().secondary(ItemSkKey::name, "a").unwrap();
+    assert!(result.is_none());
+    let result: Vec<ItemSk> = rw
+        .scan()
+        .secondary(ItemSkKey::name)
+        .unwrap()
+        .all()
+        .collect();
+    assert_eq!(result, vec![item_updated.clone()]);
+
+    // Remove
+    rw.remove(item_updated).unwrap();
+    let result: Option<ItemSk> = rw.get().primary(1u32).unwrap();
+    assert!(result.is_none());
+    let result: Vec<ItemSk> = rw.scan().primary().unwrap().all().collect();
+    assert!(result.is_empty());
+    assert_eq!(rw.len().primary::<ItemSk>().unwrap(), 0);
+    rw.commit().unwrap();
+}
+
+fn find_by_name(txn: &impl transaction::ReadableTransaction, name: &str) -> Option<ItemSk> {
+    txn.get_secondary(ItemSkKey::name, name).unwrap()
+}
+
+#[test]
+fn test_readable_transaction() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemSk>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let item = ItemSk {
+        id: 1,
+        name: "a".to_string(),
+    };
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(item.clone()).unwrap();
+    let read_only = rw.as_read_only();
+    assert_eq!(find_by_name(&read_only, "a"), Some(item.clone()));
+    assert_eq!(find_by_name(&rw, "b"), None);
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(find_by_name(&r, "a"), Some(item.clone()));
+    use transaction::ReadableTransaction;
+    assert_eq!(r.get_primary::<ItemSk>(1u32).unwrap(), Some(item.clone()));
+    assert_eq!(r.scan_primary_all::<ItemSk>().unwrap(), vec![item]);
+    assert_eq!(r.len_primary::<ItemSk>().unwrap(), 1);
+}
+
+#[test]
+fn test_error_kind() {
+    use db_type::ErrorKind;
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemSk>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemSk {
+        id: 1,
+        name: "a".to_string(),
+    })
+    .unwrap();
+    let err = rw
+        .insert(ItemSk {
+            id: 2,
+            name: "a".to_string(),
+        })
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Conflict);
+
+    let err = rw.get().primary::<Item>(1u32).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+
+    let err: Box<dyn std::error::Error> = Box::new(err);
+    assert!(err.to_string().starts_with("Table definition not found"));
+
+    let _: std::process::ExitCode = db_type::Error::BatchWriteAborted.into();
+}
+
+#[test]
+fn test_table_not_defined() {
+    fn assert_not_defined<T: std::fmt::Debug>(result: Result<T, db_type::Error>) {
+        match result {
+            Err(db_type::Error::TableDefinitionNotFound { table }) => assert_eq!(table, "3_1_id"),
+            other => panic!("expected TableDefinitionNotFound, got {:?}", other),
+        }
+    }
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    // Every query on a model that was not defined returns an error instead of panicking
+    let r = db.r_transaction().unwrap();
+    assert_not_defined(r.get().primary::<ItemSk>(1u32));
+    assert_not_defined(r.get().secondary::<ItemSk>(ItemSkKey::name, "a"));
+    assert_not_defined(r.scan().primary::<ItemSk>().map(|_| ()));
+    assert_not_defined(r.scan().secondary::<ItemSk>(ItemSkKey::name).map(|_| ()));
+    assert_not_defined(r.len().primary::<ItemSk>());
+
+    let rw = db.rw_transaction().unwrap();
+    let item = ItemSk {
+        id: 1,
+        name: "a".to_string(),
+    };
+    assert_not_defined(rw.insert(item.clone()));
+    assert_not_defined(rw.upsert(item.clone()));
+    assert_not_defined(rw.remove(item));
+    assert_not_defined(rw.drain().primary::<ItemSk>());
+    assert_not_defined(rw.get().primary::<ItemSk>(1u32));
+
+    let err = rw.get().primary::<ItemSk>(1u32).unwrap_err();
+    assert!(err.to_string().contains("DatabaseBuilder::define"));
+}
+
+#[test]
+fn test_slow_commit_threshold() {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    let slow_commits = Arc::new(Mutex::new(vec![]));
+    let slow_commits_callback = Arc::clone(&slow_commits);
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    builder.set_slow_commit_threshold(Duration::ZERO, move |elapsed, events| {
+        slow_commits_callback
+            .lock()
+            .unwrap()
+            .push((elapsed, events));
+    });
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for id in 0..3 {
+        rw.insert(Item {
+            id,
+            name: "test".to_string(),
+        })
+        .unwrap();
+    }
+    rw.commit().unwrap();
+
+    let slow_commits = slow_commits.lock().unwrap();
+    assert_eq!(slow_commits.len(), 1);
+    assert!(slow_commits[0].0 > Duration::ZERO);
+    assert_eq!(slow_commits[0].1, 3);
+}
+
+#[test]
+fn test_slow_commit_threshold_not_reached() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let calls_callback = Arc::clone(&calls);
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    builder.set_slow_commit_threshold(Duration::from_secs(3600), move |_, _| {
+        calls_callback.fetch_add(1, Ordering::SeqCst);
+    });
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Item {
+        id: 1,
+        name: "test".to_string(),
+    })
+    .unwrap();
+    rw.commit().unwrap();
+
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_max_size() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    builder.set_max_size(1024 * 1024);
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Item {
+        id: 1,
+        name: "test".to_string(),
+    })
+    .unwrap();
+    rw.commit().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for id in 2..200 {
+        rw.insert(Item {
+            id,
+            name: "a".repeat(10 * 1024),
+        })
+        .unwrap();
+    }
+    let err = rw.commit().unwrap_err();
+    assert!(matches!(
+        err,
+        db_type::Error::DatabaseFull { max_size, .. } if max_size == 1024 * 1024
+    ));
+    assert_eq!(err.kind(), db_type::ErrorKind::LimitReached);
+
+    // The transaction is aborted
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<Item>().unwrap(), 1);
+}
+
+#[test]
+fn test_commit_with_metrics() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Item {
+        id: 1,
+        name: "test".to_string(),
+    })
+    .unwrap();
+    let first = rw.commit_with_metrics().unwrap();
+    assert_eq!(first.writes, 1);
+    assert!(first.allocated_pages > 0);
+    assert!(first.page_size > 0);
+
+    let rw = db.rw_transaction().unwrap();
+    for id in 2..100 {
+        rw.insert(Item {
+            id,
+            name: "a".repeat(1024),
+        })
+        .unwrap();
+    }
+    let second = rw.commit_with_metrics().unwrap();
+    assert_eq!(second.writes, 98);
+    assert!(second.allocated_pages > first.allocated_pages);
+}
+
+#[test]
+fn test_atomic_multi_models() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    builder.define::<Item2>().unwrap();
+    builder.define::<ItemSk>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let item_2 = Item2 {
+        id: 1,
+        name: "stock".to_string(),
+    };
+    db.write(|rw| {
+        rw.insert(item_2.clone())?;
+        rw.insert(ItemSk {
+            id: 1,
+            name: "log".to_string(),
+        })
+    })
+    .unwrap();
+
+    #[allow(unused_mut)]
+    let (mut recv_item, _) = db.watch().scan().primary().all::<Item>().unwrap();
+    #[allow(unused_mut)]
+    let (mut recv_item_2, _) = db.watch().scan().primary().all::<Item2>().unwrap();
+    #[allow(unused_mut)]
+    let (mut recv_item_sk, _) = db.watch().scan().primary().all::<ItemSk>().unwrap();
+
+    // The third write fails: the first two are not persisted
+    let result = db.write(|rw| {
+        rw.insert(Item {
+            id: 1,
+            name: "order".to_string(),
+        })?;
+        rw.update(
+            item_2.clone(),
+            Item2 {
+                id: 1,
+                name: "stock - 1".to_string(),
+            },
+        )?;
+        rw.insert(ItemSk {
+            id: 2,
+            name: "log".to_string(),
+        })?;
+        Ok::<(), db_type::Error>(())
+    });
+    assert!(matches!(result, Err(db_type::Error::DuplicateKey { .. })));
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<Item>().unwrap(), 0);
+    let result: Item2 = r.get().primary(1u32).unwrap().unwrap();
+    assert_eq!(result, item_2);
+    assert_eq!(r.len().primary::<ItemSk>().unwrap(), 1);
+    drop(r);
+    assert!(recv_item.try_recv().is_err());
+    assert!(recv_item_2.try_recv().is_err());
+    assert!(recv_item_sk.try_recv().is_err());
+
+    // All the writes succeed: they are committed together
+    db.write(|rw| {
+        rw.insert(Item {
+            id: 1,
+            name: "order".to_string(),
+        })?;
+        rw.insert(ItemSk {
+            id: 2,
+            name: "log 2".to_string(),
+        })?;
+        Ok::<(), db_type::Error>(())
+    })
+    .unwrap();
+    assert!(recv_item.try_recv().is_ok());
+    assert!(recv_item_2.try_recv().is_err());
+    assert!(recv_item_sk.try_recv().is_ok());
+}
+
+#[test]
+fn test_rw_transaction_timeout() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    let result = db.rw_transaction_timeout(std::time::Duration::from_millis(50));
+    assert!(matches!(result, Err(db_type::Error::WriteTimeout { .. })));
+    assert_eq!(result.err().unwrap().kind(), db_type::ErrorKind::Aborted);
+
+    // The transaction is acquired as soon as the transaction in progress is committed.
+    std::thread::scope(|scope| {
+        let handle = scope.spawn(|| {
+            let rw = db
+                .rw_transaction_timeout(std::time::Duration::from_secs(10))
+                .unwrap();
+            let item: Option<Item> = rw.get().primary(1u32).unwrap();
+            assert!(item.is_some());
+            rw.commit().unwrap();
+        });
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        rw.insert(Item {
+            id: 1,
+            name: "test".to_string(),
+        })
+        .unwrap();
+        rw.commit().unwrap();
+        handle.join().unwrap();
+    });
+}
+
+#[test]
+fn test_rw_transaction_timeout_stats() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    std::thread::scope(|scope| {
+        // The stats wait for the transaction in progress, like a read-write transaction.
+        let stats = scope.spawn(|| db.redb_database_stats().unwrap());
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        assert!(!stats.is_finished());
+
+        // The timeout is still respected while the stats are waiting.
+        let result = db.rw_transaction_timeout(std::time::Duration::from_millis(50));
+        assert!(matches!(result, Err(db_type::Error::WriteTimeout { .. })));
+
+        rw.insert(Item {
+            id: 1,
+            name: "test".to_string(),
+        })
+        .unwrap();
+        rw.commit().unwrap();
+        stats.join().unwrap();
+    });
+
+    // The stats release the write transaction.
+    for _ in 0..10 {
+        db.redb_database_stats().unwrap();
+        let rw = db
+            .rw_transaction_timeout(std::time::Duration::from_secs(1))
+            .unwrap();
+        rw.commit().unwrap();
+    }
+}
+
+#[test]
+fn test_r_transaction_close() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let r = db.r_transaction().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Item {
+        id: 1,
+        name: "test".to_string(),
+    })
+    .unwrap();
+    rw.commit().unwrap();
+
+    // The snapshot of the read transaction started before the commit
+    let result: Option<Item> = r.get().primary(1u32).unwrap();
+    assert!(result.is_none());
+    r.close();
+
+    let r = db.r_transaction().unwrap();
+    let result: Option<Item> = r.get().primary(1u32).unwrap();
+    assert!(result.is_some());
+    r.close();
+}
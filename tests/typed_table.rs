@@ -0,0 +1,107 @@
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct Item {
+    #[primary_key]
+    id: u32,
+    name: String,
+}
+
+#[test]
+fn test_typed_table() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    let items = rw.of::<Item>();
+    let item_1 = Item {
+        id: 1,
+        name: "a".to_string(),
+    };
+    let item_2 = Item {
+        id: 2,
+        name: "b".to_string(),
+    };
+    items.insert(item_1.clone()).unwrap();
+    items.insert(item_2.clone()).unwrap();
+    let item_2_updated = Item {
+        id: 2,
+        name: "c".to_string(),
+    };
+    items.update(item_2, item_2_updated.clone()).unwrap();
+    assert_eq!(items.get(2u32).unwrap(), Some(item_2_updated.clone()));
+    assert_eq!(items.len().unwrap(), 2);
+    assert_eq!(items.remove(item_1.clone()).unwrap(), item_1);
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    let items = r.of::<Item>();
+    assert_eq!(items.get(1u32).unwrap(), None);
+    assert_eq!(items.len().unwrap(), 1);
+    assert!(!items.is_empty().unwrap());
+    let all: Vec<Item> = items.scan().unwrap().all().collect();
+    assert_eq!(all, vec![item_2_updated]);
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 2, version = 1)]
+#[native_db]
+struct ItemSk {
+    #[primary_key]
+    id: u32,
+    #[secondary_key(unique)]
+    name: String,
+}
+
+#[test]
+fn test_tables_for() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    builder.define::<ItemSk>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    {
+        let mut items = rw.tables_for::<Item>().unwrap();
+        let mut items_sk = rw.tables_for::<ItemSk>().unwrap();
+        for id in 0..10 {
+            items
+                .insert(Item {
+                    id,
+                    name: id.to_string(),
+                })
+                .unwrap();
+            items_sk
+                .insert(ItemSk {
+                    id,
+                    name: id.to_string(),
+                })
+                .unwrap();
+        }
+        let old = items_sk.get(1u32).unwrap().unwrap();
+        let new = ItemSk {
+            id: 1,
+            name: "one".to_string(),
+        };
+        items_sk.update(old, new).unwrap();
+        let value = items.get(2u32).unwrap().unwrap();
+        items.remove(value).unwrap();
+
+        // The tables of the model are already open.
+        assert!(rw.get().primary::<Item>(3u32).is_err());
+    }
+    rw.commit().unwrap();
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<Item>().unwrap(), 9);
+    assert_eq!(r.len().primary::<ItemSk>().unwrap(), 10);
+    let result: ItemSk = r.get().secondary(ItemSkKey::name, "one").unwrap().unwrap();
+    assert_eq!(result.id, 1);
+    let result: Option<ItemSk> = r.get().secondary(ItemSkKey::name, "1").unwrap();
+    assert!(result.is_none());
+}
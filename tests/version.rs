@@ -0,0 +1,116 @@
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+use shortcut_assert_fs::TmpFs;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct Item {
+    #[primary_key]
+    id: u32,
+    #[secondary_key(unique)]
+    name: String,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 2, version = 1)]
+#[native_db]
+struct Other {
+    #[primary_key]
+    id: u32,
+}
+
+fn item(id: u32, name: &str) -> Item {
+    Item {
+        id,
+        name: name.to_string(),
+    }
+}
+
+#[test]
+fn test_modified_since() {
+    let mut builder = DatabaseBuilder::new();
+    builder.set_version_tracking(true);
+    builder.define::<Item>().unwrap();
+    builder.define::<Other>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let changes = db.modified_since::<Item>(0).unwrap();
+    assert!(changes.values.is_empty());
+    assert_eq!(changes.version, 0);
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(item(1, "a")).unwrap();
+    rw.insert(item(2, "b")).unwrap();
+    rw.insert(item(3, "c")).unwrap();
+    rw.commit().unwrap();
+
+    let synced = db.modified_since::<Item>(0).unwrap();
+    assert_eq!(synced.values.len(), 3);
+    assert_eq!(synced.version, 1);
+
+    // Update, remove and write another model
+    let rw = db.rw_transaction().unwrap();
+    rw.update(item(2, "b"), item(2, "bb")).unwrap();
+    rw.remove(item(3, "c")).unwrap();
+    rw.insert(Other { id: 1 }).unwrap();
+    rw.commit().unwrap();
+
+    let changes = db.modified_since::<Item>(synced.version).unwrap();
+    assert_eq!(changes.values, vec![item(2, "bb")]);
+    assert_eq!(changes.version, 2);
+
+    let changes = db.modified_since::<Other>(synced.version).unwrap();
+    assert_eq!(changes.values, vec![Other { id: 1 }]);
+
+    // A transaction without writes doesn't create a version
+    let rw = db.rw_transaction().unwrap();
+    rw.commit().unwrap();
+    let changes = db.modified_since::<Item>(2).unwrap();
+    assert!(changes.values.is_empty());
+    assert_eq!(changes.version, 2);
+
+    // Each row is returned once, with its last version
+    let changes = db.modified_since::<Item>(0).unwrap();
+    assert_eq!(changes.values, vec![item(1, "a"), item(2, "bb")]);
+}
+
+#[test]
+fn test_modified_since_disabled() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let result = db.modified_since::<Item>(0);
+    assert!(matches!(
+        result,
+        Err(db_type::Error::VersionTrackingDisabled)
+    ));
+}
+
+#[test]
+fn test_modified_since_after_reopen() {
+    let tf = TmpFs::new().unwrap();
+    let path = tf.path("test");
+
+    let mut builder = DatabaseBuilder::new();
+    builder.set_version_tracking(true);
+    builder.define::<Item>().unwrap();
+
+    {
+        let db = builder.create(&path).unwrap();
+        let rw = db.rw_transaction().unwrap();
+        rw.insert(item(1, "a")).unwrap();
+        rw.commit().unwrap();
+    }
+
+    let db = builder.open(&path).unwrap();
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(item(2, "b")).unwrap();
+    rw.commit().unwrap();
+
+    let changes = db.modified_since::<Item>(1).unwrap();
+    assert_eq!(changes.values, vec![item(2, "b")]);
+    assert_eq!(changes.version, 2);
+}
@@ -7,6 +7,7 @@ use native_db::*;
 use native_model::{native_model, Model};
 use serde::{Deserialize, Serialize};
 use shortcut_assert_fs::TmpFs;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
@@ -56,6 +57,68 @@ fn watch_one_primary_key() {
     assert!(recv.try_recv().is_err());
 }
 
+#[test]
+fn watch_primary_keys() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemA>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    let (recv, _) = db
+        .watch()
+        .get()
+        .primary_keys::<ItemA, _>(vec![1u32, 3])
+        .unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    for id in 1..=4 {
+        rw.insert(ItemA { id }).unwrap();
+    }
+    rw.remove(ItemA { id: 2 }).unwrap();
+    rw.remove(ItemA { id: 3 }).unwrap();
+    rw.commit().unwrap();
+
+    let mut received = vec![];
+    for _ in 0..3 {
+        match recv.recv_timeout(TIMEOUT).unwrap() {
+            Event::Insert(event) => received.push(("insert", event.inner::<ItemA>().id)),
+            Event::Delete(event) => received.push(("delete", event.inner::<ItemA>().id)),
+            _ => panic!("wrong event"),
+        }
+    }
+    assert_eq!(received, vec![("insert", 1), ("insert", 3), ("delete", 3)]);
+    assert!(recv.try_recv().is_err());
+}
+
+#[test]
+fn watch_primary_guarded() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemA>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    let (recv, guard) = db.watch().get().primary_guarded::<ItemA>(1u32).unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemA { id: 1 }).unwrap();
+    rw.commit().unwrap();
+    assert!(matches!(
+        recv.recv_timeout(TIMEOUT).unwrap(),
+        Event::Insert(_)
+    ));
+
+    drop(guard);
+    let rw = db.rw_transaction().unwrap();
+    rw.remove(ItemA { id: 1 }).unwrap();
+    rw.commit().unwrap();
+    assert!(matches!(
+        recv.try_recv(),
+        Err(std::sync::mpsc::TryRecvError::Disconnected)
+    ));
+}
+
 #[test]
 fn watch_all_primary_key() {
     let tf = TmpFs::new().unwrap();
@@ -173,6 +236,40 @@ fn watch_outside() {
     assert!(recv.try_recv().is_err());
 }
 
+#[test]
+fn watch_dropped_receiver_does_not_block_others() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemA>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let (recv_dropped, _) = db.watch().scan().primary().all::<ItemA>().unwrap();
+    let (recv, _) = db.watch().scan().primary().all::<ItemA>().unwrap();
+    drop(recv_dropped);
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemA { id: 1 }).unwrap();
+    rw.insert(ItemA { id: 2 }).unwrap();
+    let result = rw.commit();
+    assert!(matches!(
+        result,
+        Err(db_type::Error::WatchEventError(
+            watch::WatchEventError::SendError(_)
+        ))
+    ));
+
+    // The other watcher receives all the events and the data is committed
+    for id in [1, 2] {
+        if let Event::Insert(event) = recv.recv_timeout(TIMEOUT).unwrap() {
+            assert_eq!(event.inner::<ItemA>(), ItemA { id });
+        } else {
+            panic!("wrong event")
+        }
+    }
+    assert!(recv.try_recv().is_err());
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<ItemA>().unwrap(), 2);
+}
+
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
 #[native_model(id = 3, version = 1)]
 #[native_db]
@@ -422,6 +519,52 @@ fn watch_all_delete() {
     assert!(recv.try_recv().is_err());
 }
 
+#[test]
+fn watch_deletes_keys_only() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemA>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let (recv, _) = db.watch().scan().primary().all::<ItemA>().unwrap();
+    let (recv_keys, _) = db
+        .watch()
+        .deletes_keys_only()
+        .scan()
+        .primary()
+        .all::<ItemA>()
+        .unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemA { id: 1 }).unwrap();
+    rw.commit().unwrap();
+    let rw = db.rw_transaction().unwrap();
+    rw.remove(ItemA { id: 1 }).unwrap();
+    rw.commit().unwrap();
+
+    // The inserts are unchanged
+    assert!(matches!(
+        recv_keys.recv_timeout(TIMEOUT).unwrap(),
+        Event::Insert(_)
+    ));
+    if let Event::Delete(event) = recv_keys.recv_timeout(TIMEOUT).unwrap() {
+        assert_eq!(event.key(), &1u32.database_inner_key_value());
+        let result = std::panic::catch_unwind(|| event.inner::<ItemA>());
+        assert!(result.is_err());
+    } else {
+        panic!("wrong event")
+    }
+    assert!(recv_keys.try_recv().is_err());
+
+    // The other watchers receive the value
+    recv.recv_timeout(TIMEOUT).unwrap();
+    if let Event::Delete(event) = recv.recv_timeout(TIMEOUT).unwrap() {
+        assert_eq!(event.key(), &1u32.database_inner_key_value());
+        assert_eq!(event.inner::<ItemA>(), ItemA { id: 1 });
+    } else {
+        panic!("wrong event")
+    }
+}
+
 #[test]
 fn watch_all_update() {
     let tf = TmpFs::new().unwrap();
@@ -457,3 +600,891 @@ fn watch_all_update() {
     }
     assert!(recv.try_recv().is_err());
 }
+
+#[test]
+fn watch_update_unchanged() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemA>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    let item_a_1 = ItemA { id: 1 };
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(item_a_1.clone()).unwrap();
+    rw.commit().unwrap();
+
+    let (recv, _) = db.watch().scan().primary().all::<ItemA>().unwrap();
+
+    // Same bytes: no write and no event
+    let rw = db.rw_transaction().unwrap();
+    assert!(!rw.update(item_a_1.clone(), item_a_1.clone()).unwrap());
+    rw.commit().unwrap();
+    assert!(recv.try_recv().is_err());
+
+    // Forced: the event is sent
+    let rw = db.rw_transaction().unwrap();
+    rw.force_update(item_a_1.clone(), item_a_1.clone()).unwrap();
+    rw.commit().unwrap();
+    if let Event::Update(event) = recv.recv_timeout(TIMEOUT).unwrap() {
+        assert_eq!(event.inner_old::<ItemA>(), item_a_1);
+        assert_eq!(event.inner_new::<ItemA>(), item_a_1);
+    } else {
+        panic!("wrong event")
+    }
+    assert!(recv.try_recv().is_err());
+
+    // Missing value: written even if the old value equals the updated value
+    let item_a_2 = ItemA { id: 2 };
+    let rw = db.rw_transaction().unwrap();
+    assert!(rw.update(item_a_2.clone(), item_a_2.clone()).unwrap());
+    rw.commit().unwrap();
+    if let Event::Update(event) = recv.recv_timeout(TIMEOUT).unwrap() {
+        assert_eq!(event.inner_new::<ItemA>(), item_a_2);
+    } else {
+        panic!("wrong event")
+    }
+    assert!(recv.try_recv().is_err());
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<ItemA>().unwrap(), 2);
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 10, version = 1)]
+#[native_db]
+struct ItemSecondaryKeys {
+    #[primary_key]
+    id: u32,
+    #[secondary_key(unique)]
+    name: String,
+    #[secondary_key]
+    city: String,
+    #[secondary_key(optional)]
+    tag: Option<String>,
+}
+
+#[test]
+fn watch_update_secondary_key_changes() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemSecondaryKeys>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let item_1 = ItemSecondaryKeys {
+        id: 1,
+        name: "a".to_string(),
+        city: "Paris".to_string(),
+        tag: Some("x".to_string()),
+    };
+    let item_2 = ItemSecondaryKeys {
+        id: 1,
+        name: "b".to_string(),
+        city: "Paris".to_string(),
+        tag: None,
+    };
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(item_1.clone()).unwrap();
+    rw.commit().unwrap();
+
+    let (recv, _) = db
+        .watch()
+        .scan()
+        .primary()
+        .all::<ItemSecondaryKeys>()
+        .unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.update(item_1.clone(), item_2.clone()).unwrap();
+    rw.commit().unwrap();
+
+    if let Event::Update(event) = recv.recv_timeout(TIMEOUT).unwrap() {
+        assert_eq!(
+            event.secondary_key_changes::<ItemSecondaryKeys>(),
+            vec![
+                ("name", Some(b"a".to_vec()), Some(b"b".to_vec())),
+                ("tag", Some(b"x".to_vec()), None),
+            ]
+        );
+    } else {
+        panic!("wrong event")
+    }
+
+    // No secondary key changed
+    let rw = db.rw_transaction().unwrap();
+    rw.force_update(item_2.clone(), item_2.clone()).unwrap();
+    rw.commit().unwrap();
+
+    if let Event::Update(event) = recv.recv_timeout(TIMEOUT).unwrap() {
+        assert!(event
+            .secondary_key_changes::<ItemSecondaryKeys>()
+            .is_empty());
+    } else {
+        panic!("wrong event")
+    }
+}
+
+#[test]
+fn watch_secondary_key_changes_only() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemSecondaryKeys>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let (recv, _) = db
+        .watch()
+        .scan()
+        .secondary(ItemSecondaryKeysKey::tag)
+        .changes::<ItemSecondaryKeys>()
+        .unwrap();
+
+    let item_1 = ItemSecondaryKeys {
+        id: 1,
+        name: "a".to_string(),
+        city: "Paris".to_string(),
+        tag: Some("x".to_string()),
+    };
+    let item_1_moved = ItemSecondaryKeys {
+        city: "Lyon".to_string(),
+        ..item_1.clone()
+    };
+    let item_1_untagged = ItemSecondaryKeys {
+        tag: None,
+        ..item_1_moved.clone()
+    };
+    let item_2 = ItemSecondaryKeys {
+        id: 2,
+        name: "b".to_string(),
+        city: "Paris".to_string(),
+        tag: None,
+    };
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(item_1.clone()).unwrap();
+    // Not sent: the tag is unchanged
+    rw.update(item_1.clone(), item_1_moved.clone()).unwrap();
+    rw.update(item_1_moved.clone(), item_1_untagged.clone())
+        .unwrap();
+    // Not sent: no tag
+    rw.insert(item_2.clone()).unwrap();
+    rw.remove(item_2).unwrap();
+    rw.commit().unwrap();
+
+    assert!(matches!(
+        recv.recv_timeout(TIMEOUT).unwrap(),
+        Event::Insert(_)
+    ));
+    if let Event::Update(event) = recv.recv_timeout(TIMEOUT).unwrap() {
+        assert_eq!(
+            event.secondary_key_changes::<ItemSecondaryKeys>(),
+            vec![("tag", Some(b"x".to_vec()), None)]
+        );
+    } else {
+        panic!("wrong event")
+    }
+    assert!(recv.try_recv().is_err());
+
+    let rw = db.rw_transaction().unwrap();
+    rw.update(item_1_untagged, item_1.clone()).unwrap();
+    rw.remove(item_1).unwrap();
+    rw.commit().unwrap();
+
+    assert!(matches!(
+        recv.recv_timeout(TIMEOUT).unwrap(),
+        Event::Update(_)
+    ));
+    assert!(matches!(
+        recv.recv_timeout(TIMEOUT).unwrap(),
+        Event::Delete(_)
+    ));
+    assert!(recv.try_recv().is_err());
+}
+
+#[test]
+fn watch_replace_table() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemSecondaryKeys>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let item = |id: u32, name: &str| ItemSecondaryKeys {
+        id,
+        name: name.to_string(),
+        city: "Paris".to_string(),
+        tag: None,
+    };
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(item(1, "a")).unwrap();
+    rw.insert(item(2, "b")).unwrap();
+    rw.insert(item(3, "c")).unwrap();
+    rw.commit().unwrap();
+
+    let (recv, _) = db
+        .watch()
+        .scan()
+        .primary()
+        .all::<ItemSecondaryKeys>()
+        .unwrap();
+
+    // The unique name "a" of the removed item 1 is reused by the item 4
+    let count = db
+        .replace_table(vec![item(3, "c"), item(2, "d"), item(4, "a")])
+        .unwrap();
+    assert_eq!(count, 3);
+
+    let r = db.r_transaction().unwrap();
+    let items: Vec<ItemSecondaryKeys> = r.scan().primary().unwrap().all().collect();
+    assert_eq!(items, vec![item(2, "d"), item(3, "c"), item(4, "a")]);
+    let found: ItemSecondaryKeys = r
+        .get()
+        .secondary(ItemSecondaryKeysKey::name, "a")
+        .unwrap()
+        .unwrap();
+    assert_eq!(found, item(4, "a"));
+    assert!(r
+        .get()
+        .secondary::<ItemSecondaryKeys>(ItemSecondaryKeysKey::name, "b")
+        .unwrap()
+        .is_none());
+
+    // The item 3 is unchanged: no event
+    if let Event::Update(event) = recv.recv_timeout(TIMEOUT).unwrap() {
+        assert_eq!(event.inner_new::<ItemSecondaryKeys>(), item(2, "d"));
+    } else {
+        panic!("wrong event")
+    }
+    if let Event::Insert(event) = recv.recv_timeout(TIMEOUT).unwrap() {
+        assert_eq!(event.inner::<ItemSecondaryKeys>(), item(4, "a"));
+    } else {
+        panic!("wrong event")
+    }
+    if let Event::Delete(event) = recv.recv_timeout(TIMEOUT).unwrap() {
+        assert_eq!(event.inner::<ItemSecondaryKeys>(), item(1, "a"));
+    } else {
+        panic!("wrong event")
+    }
+    assert!(recv.try_recv().is_err());
+}
+
+#[test]
+fn watch_update_many() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemA1K>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    let item = |id: u32, prefix: &str| ItemA1K {
+        id,
+        name: format!("{}{}", prefix, id),
+    };
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert_many((1..=3).map(|id| item(id, "a"))).unwrap();
+    rw.commit().unwrap();
+
+    let (recv, _) = db.watch().scan().primary().all::<ItemA1K>().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.update_many((1..=3).map(|id| (item(id, "a"), item(id, "b"))))
+        .unwrap();
+    rw.commit().unwrap();
+
+    for id in 1..=3 {
+        if let Event::Update(event) = recv.recv_timeout(TIMEOUT).unwrap() {
+            assert_eq!(event.inner_old::<ItemA1K>(), item(id, "a"));
+            assert_eq!(event.inner_new::<ItemA1K>(), item(id, "b"));
+        } else {
+            panic!("wrong event")
+        }
+    }
+    assert!(recv.try_recv().is_err());
+
+    // The second pair takes the name of the item 3: nothing is written and no event of the call is sent.
+    let rw = db.rw_transaction().unwrap();
+    let result = rw.update_many(vec![
+        (item(1, "b"), item(1, "c")),
+        (
+            item(2, "b"),
+            ItemA1K {
+                id: 2,
+                name: "b3".to_string(),
+            },
+        ),
+    ]);
+    assert!(matches!(result, Err(db_type::Error::DuplicateKey { .. })));
+    // The second pair is stale: the item 2 is "b2"
+    let result = rw.update_many(vec![
+        (item(1, "b"), item(1, "c")),
+        (item(2, "a"), item(2, "c")),
+    ]);
+    assert!(matches!(result, Err(db_type::Error::StaleUpdate { .. })));
+    // The second pair doesn't exist
+    let result = rw.update_many(vec![
+        (item(1, "b"), item(1, "c")),
+        (item(4, "b"), item(4, "c")),
+    ]);
+    assert!(matches!(result, Err(db_type::Error::KeyNotFound { .. })));
+    rw.commit().unwrap();
+    assert!(recv.try_recv().is_err());
+
+    let r = db.r_transaction().unwrap();
+    for id in 1..=3 {
+        let value: ItemA1K = r.get().primary(id).unwrap().unwrap();
+        assert_eq!(value, item(id, "b"));
+    }
+}
+
+#[test]
+fn watch_event_seq() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemA>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    let (recv, _) = db.watch().scan().primary().all::<ItemA>().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemA { id: 1 }).unwrap();
+    rw.insert(ItemA { id: 2 }).unwrap();
+    rw.commit().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.update(ItemA { id: 1 }, ItemA { id: 3 }).unwrap();
+    rw.remove(ItemA { id: 2 }).unwrap();
+    rw.commit().unwrap();
+
+    let mut seqs = vec![];
+    for _ in 0..4 {
+        let event = recv.recv_timeout(TIMEOUT).unwrap();
+        let seq = match &event {
+            Event::Insert(insert) => insert.seq(),
+            Event::Update(update) => update.seq(),
+            Event::Delete(delete) => delete.seq(),
+        };
+        assert_eq!(seq, event.seq());
+        seqs.push(seq);
+    }
+    assert_eq!(seqs, vec![0, 1, 2, 3]);
+    assert!(recv.try_recv().is_err());
+}
+
+#[test]
+fn watch_remove_range() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemA>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemA { id: 1 }).unwrap();
+    rw.insert(ItemA { id: 2 }).unwrap();
+    rw.insert(ItemA { id: 3 }).unwrap();
+    rw.commit().unwrap();
+
+    let (recv, _) = db.watch().scan().primary().all::<ItemA>().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.remove_range::<ItemA, _, _>(1u32..=2u32).unwrap();
+    rw.commit().unwrap();
+
+    let mut ids = vec![];
+    for _ in 0..2 {
+        let inner_event: ItemA = if let Event::Delete(event) = recv.recv_timeout(TIMEOUT).unwrap() {
+            event.inner()
+        } else {
+            panic!("wrong event")
+        };
+        ids.push(inner_event.id);
+    }
+    ids.sort();
+    assert_eq!(ids, vec![1, 2]);
+    assert!(recv.try_recv().is_err());
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 4, version = 1)]
+#[native_db]
+struct Order {
+    #[primary_key]
+    id: u32,
+    #[secondary_key]
+    region: String,
+    amount: u64,
+}
+
+impl Order {
+    fn new(id: u32, region: &str, amount: u64) -> Self {
+        Self {
+            id,
+            region: region.to_string(),
+            amount,
+        }
+    }
+}
+
+#[test]
+fn watch_secondary_key_with_predicate() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Order>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    let (recv, _) = db
+        .watch()
+        .get()
+        .secondary_filter::<Order, _>(OrderKey::region, "EU", |order: &Order| order.amount > 1000)
+        .unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Order::new(1, "EU", 500)).unwrap();
+    rw.insert(Order::new(2, "US", 5000)).unwrap();
+    rw.insert(Order::new(3, "EU", 5000)).unwrap();
+    rw.commit().unwrap();
+
+    let inner_event: Order = if let Event::Insert(event) = recv.recv_timeout(TIMEOUT).unwrap() {
+        event.inner()
+    } else {
+        panic!("wrong event")
+    };
+    assert_eq!(inner_event, Order::new(3, "EU", 5000));
+    assert!(recv.try_recv().is_err());
+
+    // The old value matches the predicate
+    let rw = db.rw_transaction().unwrap();
+    rw.update(Order::new(3, "EU", 5000), Order::new(3, "EU", 10))
+        .unwrap();
+    rw.commit().unwrap();
+    assert!(matches!(
+        recv.recv_timeout(TIMEOUT).unwrap(),
+        Event::Update(_)
+    ));
+    assert!(recv.try_recv().is_err());
+}
+
+#[test]
+fn watch_predicate_panic_drops_watcher() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Order>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    let (recv_panic, _) = db
+        .watch()
+        .get()
+        .secondary_filter::<Order, _>(OrderKey::region, "EU", |order: &Order| {
+            if order.amount == 0 {
+                panic!("invalid amount");
+            }
+            true
+        })
+        .unwrap();
+    let (recv, _) = db
+        .watch()
+        .get()
+        .secondary::<Order>(OrderKey::region, "EU")
+        .unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Order::new(1, "EU", 0)).unwrap();
+    rw.commit().unwrap();
+
+    // The other watchers are not affected
+    assert!(matches!(
+        recv.recv_timeout(TIMEOUT).unwrap(),
+        Event::Insert(_)
+    ));
+    // The watcher is dropped
+    assert!(matches!(
+        recv_panic.recv_timeout(TIMEOUT),
+        Err(std::sync::mpsc::RecvTimeoutError::Disconnected)
+    ));
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Order::new(2, "EU", 10)).unwrap();
+    rw.commit().unwrap();
+    assert!(matches!(
+        recv.recv_timeout(TIMEOUT).unwrap(),
+        Event::Insert(_)
+    ));
+}
+
+#[test]
+fn watch_primary_callback_unwatch() {
+    use std::sync::atomic::AtomicU64;
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemA>().unwrap();
+    // The callback borrows the database
+    let builder: &'static DatabaseBuilder = Box::leak(Box::new(builder));
+    let db: &'static Database = Box::leak(Box::new(builder.create_in_memory().unwrap()));
+
+    let id = Arc::new(AtomicU64::new(u64::MAX));
+    let calls = Arc::new(AtomicU64::new(0));
+    let id_callback = Arc::clone(&id);
+    let calls_callback = Arc::clone(&calls);
+    let callback_id = db
+        .watch()
+        .get()
+        .primary_callback::<ItemA>(1u32, move |_| {
+            calls_callback.fetch_add(1, Ordering::SeqCst);
+            // Watch and unwatch from the callback, during the commit
+            db.watch().get().primary::<ItemA>(2u32).unwrap();
+            db.unwatch(id_callback.load(Ordering::SeqCst)).unwrap();
+        })
+        .unwrap();
+    id.store(callback_id, Ordering::SeqCst);
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemA { id: 1 }).unwrap();
+    rw.commit().unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    // The callback has unwatched itself
+    let rw = db.rw_transaction().unwrap();
+    rw.remove(ItemA { id: 1 }).unwrap();
+    rw.commit().unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn watch_primary_callback() {
+    use std::sync::Mutex;
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemA>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let events = Arc::new(Mutex::new(vec![]));
+    let events_callback = Arc::clone(&events);
+    db.watch()
+        .get()
+        .primary_callback::<ItemA>(1u32, move |event| {
+            if let Event::Delete(_) = event {
+                panic!("delete");
+            }
+            events_callback.lock().unwrap().push(event.seq());
+        })
+        .unwrap();
+    let (recv, _) = db.watch().get().primary::<ItemA>(1u32).unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemA { id: 1 }).unwrap();
+    rw.insert(ItemA { id: 2 }).unwrap();
+    rw.commit().unwrap();
+    // Called during the commit
+    assert_eq!(events.lock().unwrap().len(), 1);
+
+    // The callback panics: the watcher is removed, the commit and the other watchers are not affected
+    let rw = db.rw_transaction().unwrap();
+    rw.remove(ItemA { id: 1 }).unwrap();
+    rw.commit().unwrap();
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemA { id: 1 }).unwrap();
+    rw.commit().unwrap();
+    assert_eq!(events.lock().unwrap().len(), 1);
+
+    assert!(matches!(
+        recv.recv_timeout(TIMEOUT).unwrap(),
+        Event::Insert(_)
+    ));
+    assert!(matches!(
+        recv.recv_timeout(TIMEOUT).unwrap(),
+        Event::Delete(_)
+    ));
+    assert!(matches!(
+        recv.recv_timeout(TIMEOUT).unwrap(),
+        Event::Insert(_)
+    ));
+}
+
+#[test]
+fn watch_upsert_origin() {
+    use native_db::watch::Origin;
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemA>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let (recv, _) = db.watch().scan().primary().all::<ItemA>().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemA { id: 1 }).unwrap();
+    rw.force_update(ItemA { id: 1 }, ItemA { id: 1 }).unwrap();
+    rw.upsert(ItemA { id: 2 }).unwrap();
+    rw.upsert(ItemA { id: 2 }).unwrap();
+    rw.commit().unwrap();
+
+    let mut origins = vec![];
+    for _ in 0..4 {
+        let origin = match recv.recv_timeout(TIMEOUT).unwrap() {
+            Event::Insert(insert) => ("insert", insert.origin()),
+            Event::Update(update) => ("update", update.origin()),
+            Event::Delete(_) => panic!("wrong event"),
+        };
+        origins.push(origin);
+    }
+    assert_eq!(
+        origins,
+        vec![
+            ("insert", Origin::Insert),
+            ("update", Origin::Update),
+            ("insert", Origin::Upsert),
+            ("update", Origin::Upsert),
+        ]
+    );
+    assert!(recv.try_recv().is_err());
+}
+
+#[test]
+fn watch_insert_if_changed() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemA1K>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let (recv, _) = db.watch().scan().primary().all::<ItemA1K>().unwrap();
+
+    let item = |name: &str| ItemA1K {
+        id: 1,
+        name: name.to_string(),
+    };
+    let rw = db.rw_transaction().unwrap();
+    assert!(rw.insert_if_changed(item("a")).unwrap());
+    assert!(!rw.insert_if_changed(item("a")).unwrap());
+    assert!(rw.insert_if_changed(item("b")).unwrap());
+    assert!(!rw.insert_if_changed(item("b")).unwrap());
+    rw.commit().unwrap();
+
+    let images: Vec<_> = (0..2)
+        .map(|_| recv.recv_timeout(TIMEOUT).unwrap().images::<ItemA1K>())
+        .collect();
+    assert_eq!(
+        images,
+        vec![(None, Some(item("a"))), (Some(item("a")), Some(item("b")))]
+    );
+    assert!(recv.try_recv().is_err());
+
+    let r = db.r_transaction().unwrap();
+    let stored: ItemA1K = r.get().primary(1u32).unwrap().unwrap();
+    assert_eq!(stored, item("b"));
+}
+
+#[test]
+fn watch_event_images() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemA>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let (recv, _) = db.watch().scan().primary().all::<ItemA>().unwrap();
+    let (recv_keys, _) = db
+        .watch()
+        .deletes_keys_only()
+        .scan()
+        .primary()
+        .all::<ItemA>()
+        .unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemA { id: 1 }).unwrap();
+    rw.force_update(ItemA { id: 1 }, ItemA { id: 1 }).unwrap();
+    rw.remove(ItemA { id: 1 }).unwrap();
+    rw.commit().unwrap();
+
+    let mut images = vec![];
+    for _ in 0..3 {
+        images.push(recv.recv_timeout(TIMEOUT).unwrap().images::<ItemA>());
+    }
+    assert_eq!(
+        images,
+        vec![
+            (None, Some(ItemA { id: 1 })),
+            (Some(ItemA { id: 1 }), Some(ItemA { id: 1 })),
+            (Some(ItemA { id: 1 }), None),
+        ]
+    );
+
+    // Without the deleted value
+    recv_keys.recv_timeout(TIMEOUT).unwrap();
+    recv_keys.recv_timeout(TIMEOUT).unwrap();
+    let delete = recv_keys.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(delete.images::<ItemA>(), (None, None));
+}
+
+#[test]
+fn watch_suppress_notifications() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemA>().unwrap();
+    builder.define::<ItemB>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let (recv_a, _) = db.watch().scan().primary().all::<ItemA>().unwrap();
+    let (recv_b, _) = db.watch().scan().primary().all::<ItemB>().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemA { id: 1 }).unwrap();
+    rw.suppress_notifications::<ItemA>();
+    rw.insert(ItemA { id: 2 }).unwrap();
+    rw.insert(ItemB { id: 1 }).unwrap();
+    rw.commit().unwrap();
+
+    assert!(recv_a.try_recv().is_err());
+    let inner_event: ItemB = if let Event::Insert(event) = recv_b.recv_timeout(TIMEOUT).unwrap() {
+        event.inner()
+    } else {
+        panic!("wrong event")
+    };
+    assert_eq!(inner_event, ItemB { id: 1 });
+    assert!(recv_b.try_recv().is_err());
+
+    // The next transactions notify the watchers again.
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemA { id: 3 }).unwrap();
+    rw.commit().unwrap();
+    assert!(matches!(
+        recv_a.recv_timeout(TIMEOUT).unwrap(),
+        Event::Insert(_)
+    ));
+}
+
+#[test]
+fn watch_insert_many_order() {
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemA>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let (recv, _) = db.watch().scan().primary().all::<ItemA>().unwrap();
+
+    let ids = vec![5, 1, 4, 2, 3];
+    let rw = db.rw_transaction().unwrap();
+    let count = rw
+        .insert_many(ids.iter().map(|id| ItemA { id: *id }))
+        .unwrap();
+    assert_eq!(count, 5);
+    rw.commit().unwrap();
+
+    let mut received = vec![];
+    for _ in 0..5 {
+        let inner_event: ItemA = if let Event::Insert(event) = recv.recv_timeout(TIMEOUT).unwrap() {
+            event.inner()
+        } else {
+            panic!("wrong event")
+        };
+        received.push(inner_event.id);
+    }
+    assert_eq!(received, ids);
+    assert!(recv.try_recv().is_err());
+
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<ItemA>().unwrap(), 5);
+}
+
+/// In-memory storage that fails to sync once `fail` is set, so the next commit fails.
+#[derive(Debug)]
+struct FailingBackend {
+    inner: redb::backends::InMemoryBackend,
+    fail: Arc<AtomicBool>,
+}
+
+impl redb::StorageBackend for FailingBackend {
+    fn len(&self) -> Result<u64, std::io::Error> {
+        self.inner.len()
+    }
+
+    fn read(&self, offset: u64, len: usize) -> Result<Vec<u8>, std::io::Error> {
+        self.inner.read(offset, len)
+    }
+
+    fn set_len(&self, len: u64) -> Result<(), std::io::Error> {
+        self.inner.set_len(len)
+    }
+
+    fn sync_data(&self, eventual: bool) -> Result<(), std::io::Error> {
+        if self.fail.load(Ordering::SeqCst) {
+            return Err(std::io::Error::other("injected failure"));
+        }
+        self.inner.sync_data(eventual)
+    }
+
+    fn write(&self, offset: u64, data: &[u8]) -> Result<(), std::io::Error> {
+        self.inner.write(offset, data)
+    }
+}
+
+#[test]
+fn watch_commit_failure() {
+    let fail = Arc::new(AtomicBool::new(false));
+    let backend = FailingBackend {
+        inner: redb::backends::InMemoryBackend::new(),
+        fail: Arc::clone(&fail),
+    };
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemA>().unwrap();
+    let db = builder.create_with_backend(backend).unwrap();
+
+    let (recv, _) = db.watch().scan().primary().all::<ItemA>().unwrap();
+
+    fail.store(true, Ordering::SeqCst);
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemA { id: 1 }).unwrap();
+    assert!(rw.commit().is_err());
+
+    // The events of the failed commit are discarded
+    assert!(recv.recv_timeout(TIMEOUT).is_err());
+}
+
+#[test]
+fn watch_serialize_events() {
+    fn round_trip(event: &Event) -> Event {
+        let config = bincode::config::standard();
+        let bytes = bincode::serde::encode_to_vec(event, config).unwrap();
+        let (event, _) = bincode::serde::decode_from_slice(&bytes, config).unwrap();
+        event
+    }
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemA>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let (recv, _) = db.watch().scan().primary().all::<ItemA>().unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(ItemA { id: 1 }).unwrap();
+    rw.update(ItemA { id: 1 }, ItemA { id: 2 }).unwrap();
+    rw.remove(ItemA { id: 2 }).unwrap();
+    rw.commit().unwrap();
+
+    let event = recv.recv_timeout(TIMEOUT).unwrap();
+    match round_trip(&event) {
+        Event::Insert(insert) => {
+            assert_eq!(insert.inner::<ItemA>(), ItemA { id: 1 });
+            assert_eq!(insert.seq(), event.seq());
+            assert_eq!(insert.origin(), watch::Origin::Insert);
+        }
+        _ => panic!("wrong event"),
+    }
+
+    let event = recv.recv_timeout(TIMEOUT).unwrap();
+    match round_trip(&event) {
+        Event::Update(update) => {
+            assert_eq!(update.inner_old::<ItemA>(), ItemA { id: 1 });
+            assert_eq!(update.inner_new::<ItemA>(), ItemA { id: 2 });
+            assert_eq!(update.seq(), event.seq());
+        }
+        _ => panic!("wrong event"),
+    }
+
+    let event = recv.recv_timeout(TIMEOUT).unwrap();
+    match round_trip(&event) {
+        Event::Delete(delete) => {
+            assert_eq!(delete.inner::<ItemA>(), ItemA { id: 2 });
+            assert_eq!(delete.key(), &2u32.database_inner_key_value());
+            assert_eq!(delete.seq(), event.seq());
+        }
+        _ => panic!("wrong event"),
+    }
+}
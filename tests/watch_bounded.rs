@@ -0,0 +1,219 @@
+use native_db::watch::{Event, FullPolicy, WatcherBacklog};
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+use shortcut_assert_fs::TmpFs;
+use std::thread;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[native_model(id = 1, version = 1)]
+#[native_db]
+struct Item {
+    #[primary_key]
+    id: u32,
+}
+
+#[test]
+fn watch_bounded_drop_event() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    let (recv, _) = db
+        .watch()
+        .scan()
+        .primary()
+        .all_bounded::<Item>(2, FullPolicy::DropEvent)
+        .unwrap();
+    assert_eq!(recv.capacity(), 2);
+
+    let rw = db.rw_transaction().unwrap();
+    for id in 1..=3 {
+        rw.insert(Item { id }).unwrap();
+    }
+    rw.commit().unwrap();
+
+    assert_eq!(recv.len(), 2);
+    assert_eq!(recv.dropped(), 1);
+    for id in 1..=2 {
+        match recv.recv_timeout(TIMEOUT).unwrap() {
+            Event::Insert(insert) => assert_eq!(insert.inner::<Item>(), Item { id }),
+            _ => panic!("wrong event"),
+        }
+    }
+    assert!(recv.is_empty());
+}
+
+#[test]
+fn watch_bounded_fail_commit() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    let (recv, id) = db
+        .watch()
+        .get()
+        .primary_bounded::<Item>(1u32, 1, FullPolicy::FailCommit)
+        .unwrap();
+
+    // Two events for a channel of one event: nothing is written
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Item { id: 1 }).unwrap();
+    rw.remove(Item { id: 1 }).unwrap();
+    rw.insert(Item { id: 2 }).unwrap();
+    let result = rw.commit();
+    assert!(
+        matches!(result, Err(db_type::Error::WatcherFull { id: watcher_id }) if watcher_id == id)
+    );
+    assert!(recv.is_empty());
+    let r = db.r_transaction().unwrap();
+    assert_eq!(r.len().primary::<Item>().unwrap(), 0);
+    drop(r);
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Item { id: 1 }).unwrap();
+    rw.commit().unwrap();
+    assert_eq!(recv.len(), 1);
+
+    // The channel is full
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Item { id: 3 }).unwrap();
+    rw.remove(Item { id: 1 }).unwrap();
+    assert!(matches!(
+        rw.commit(),
+        Err(db_type::Error::WatcherFull { .. })
+    ));
+
+    // The other keys are not watched
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Item { id: 3 }).unwrap();
+    rw.commit().unwrap();
+
+    assert!(recv.try_recv().is_ok());
+    let rw = db.rw_transaction().unwrap();
+    rw.remove(Item { id: 1 }).unwrap();
+    rw.commit().unwrap();
+    assert!(matches!(recv.try_recv().unwrap(), Event::Delete(_)));
+    assert!(recv.try_recv().is_err());
+    assert_eq!(recv.dropped(), 0);
+}
+
+#[test]
+fn watch_bounded_block() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    let (recv, _) = db
+        .watch()
+        .scan()
+        .primary()
+        .all_bounded::<Item>(1, FullPolicy::Block)
+        .unwrap();
+
+    thread::scope(|scope| {
+        let writer = scope.spawn(|| {
+            let rw = db.rw_transaction().unwrap();
+            for id in 1..=5 {
+                rw.insert(Item { id }).unwrap();
+            }
+            rw.commit().unwrap();
+        });
+        for id in 1..=5 {
+            match recv.recv_timeout(TIMEOUT).unwrap() {
+                Event::Insert(insert) => assert_eq!(insert.inner::<Item>(), Item { id }),
+                _ => panic!("wrong event"),
+            }
+            assert!(recv.len() <= 1);
+        }
+        writer.join().unwrap();
+    });
+    assert_eq!(recv.dropped(), 0);
+}
+
+#[test]
+fn watch_bounded_unwatch() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    let (recv, id) = db
+        .watch()
+        .scan()
+        .primary()
+        .all_bounded::<Item>(1, FullPolicy::Block)
+        .unwrap();
+
+    let rw = db.rw_transaction().unwrap();
+    rw.insert(Item { id: 1 }).unwrap();
+    rw.commit().unwrap();
+
+    db.unwatch(id).unwrap();
+    assert!(recv.recv().is_ok());
+    assert!(recv.recv().is_err());
+}
+
+#[test]
+fn watcher_backlog() {
+    let tf = TmpFs::new().unwrap();
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<Item>().unwrap();
+    let db = builder.create(tf.path("test").as_std_path()).unwrap();
+
+    let (recv, bounded_id) = db
+        .watch()
+        .scan()
+        .primary()
+        .all_bounded::<Item>(2, FullPolicy::DropEvent)
+        .unwrap();
+    let callback_id = db
+        .watch()
+        .get()
+        .primary_callback::<Item>(1u32, |_| {})
+        .unwrap();
+    let (_recv, channel_id) = db.watch().scan().primary().all::<Item>().unwrap();
+    assert_eq!(
+        db.watcher_backlog(bounded_id),
+        Some(WatcherBacklog::Pending(0))
+    );
+
+    let rw = db.rw_transaction().unwrap();
+    for id in 1..=3 {
+        rw.insert(Item { id }).unwrap();
+    }
+    rw.commit().unwrap();
+
+    assert_eq!(
+        db.watcher_backlog(bounded_id),
+        Some(WatcherBacklog::Pending(2))
+    );
+    assert_eq!(
+        db.watcher_backlog(callback_id),
+        Some(WatcherBacklog::Pending(0))
+    );
+    assert_eq!(
+        db.watcher_backlog(channel_id),
+        Some(WatcherBacklog::NotMeasured)
+    );
+
+    recv.try_recv().unwrap();
+    assert_eq!(
+        db.watcher_backlog(bounded_id),
+        Some(WatcherBacklog::Pending(1))
+    );
+
+    db.unwatch(bounded_id).unwrap();
+    assert_eq!(db.watcher_backlog(bounded_id), None);
+}
@@ -42,3 +42,31 @@ async fn watch_one_primary_key() {
 }
 
 // TODO: maybe do others tests but it should the same as a std::sync::mpsc::channel.
+
+#[cfg(feature = "futures")]
+#[tokio::test]
+async fn watch_stream() {
+    use futures_util::StreamExt;
+    use native_db::watch::WatchStream;
+
+    let mut builder = DatabaseBuilder::new();
+    builder.define::<ItemA>().unwrap();
+    let db = builder.create_in_memory().unwrap();
+
+    let (recv, id) = db.watch().scan().primary().all::<ItemA>().unwrap();
+    let stream = WatchStream::from(recv).map(|event| match event {
+        Event::Insert(insert) => insert.inner::<ItemA>().id,
+        _ => panic!("wrong event"),
+    });
+
+    let rw = db.rw_transaction().unwrap();
+    for id in 1..=3 {
+        rw.insert(ItemA { id }).unwrap();
+    }
+    rw.commit().unwrap();
+
+    // The stream ends when the watcher is removed.
+    db.unwatch(id).unwrap();
+    let ids: Vec<u32> = stream.collect().await;
+    assert_eq!(ids, vec![1, 2, 3]);
+}